@@ -0,0 +1,102 @@
+//! Compares the encoded channel_data column against the old integer[] layout.
+//!
+//! Run with `cargo bench --bench channel_data`.
+
+extern crate proton_cli;
+
+use std::time::{Duration, Instant};
+
+use proton_cli::dao::{encode_frames, decode_frames};
+
+
+// A five minute song at 25ms frames
+const NUM_FRAMES: usize = 12000;
+const NUM_CHANNELS: usize = 64;
+const ITERATIONS: u32 = 5;
+
+fn main() {
+    let channels = (0..NUM_CHANNELS)
+        .map(simulate_channel)
+        .collect::<Vec<Vec<u16>>>();
+
+    let array_bytes = channels.iter()
+        .map(|frames| int_array_size(frames.len()))
+        .sum::<usize>();
+    let encoded = channels.iter()
+        .map(|frames| encode_frames(frames))
+        .collect::<Vec<Vec<u8>>>();
+    let encoded_bytes = encoded.iter()
+        .map(|frames| frames.len())
+        .sum::<usize>();
+
+    println!("{} channels x {} frames", NUM_CHANNELS, NUM_FRAMES);
+    println!("integer[]: {:>10} bytes", array_bytes);
+    println!("bytea:     {:>10} bytes ({:.1}%)",
+        encoded_bytes,
+        100.0 * encoded_bytes as f64 / array_bytes as f64);
+
+    let widen = time(|| {
+        for frames in &channels {
+            let widened = frames.iter()
+                .map(|frame| *frame as i32)
+                .collect::<Vec<i32>>();
+            let narrowed = widened.iter()
+                .map(|frame| *frame as u16)
+                .collect::<Vec<u16>>();
+            assert_eq!(narrowed.len(), NUM_FRAMES);
+        }
+    });
+    let encode = time(|| {
+        for frames in &channels {
+            assert!(!encode_frames(frames).is_empty());
+        }
+    });
+    let decode = time(|| {
+        for frames in &encoded {
+            let decoded = decode_frames(frames).expect("Error decoding frames");
+            assert_eq!(decoded.len(), NUM_FRAMES);
+        }
+    });
+    println!("integer[] widen + narrow: {:>8.3} ms", millis(widen));
+    println!("bytea encode:             {:>8.3} ms", millis(encode));
+    println!("bytea decode:             {:>8.3} ms", millis(decode));
+}
+
+/// Mostly off, with holds, linear fades and the occasional flicker, like
+/// typical Vixen output
+fn simulate_channel(channel: usize) -> Vec<u16> {
+    let mut frames = Vec::with_capacity(NUM_FRAMES);
+    let mut seed = channel as u32 * 7919 + 1;
+    while frames.len() < NUM_FRAMES {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        let len = 20 + (seed >> 16) as usize % 400;
+        let level = (seed >> 8) as u16 % 256;
+        match seed % 4 {
+            0 | 1 => frames.extend(vec![0u16; len]),
+            2 => frames.extend(vec![level; len]),
+            _ => {
+                let fade_len = len.min(level as usize + 1);
+                frames.extend((0..fade_len).map(|i| (level as usize * i / fade_len) as u16));
+            },
+        }
+    }
+    frames.truncate(NUM_FRAMES);
+    frames
+}
+
+/// On-disk size of an int4[] in Postgres: array header plus 4 bytes per element
+fn int_array_size(num_frames: usize) -> usize {
+    20 + 4 * num_frames
+}
+
+fn time<F: Fn()>(f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + duration.subsec_nanos() as f64 / 1_000_000.0
+}
@@ -0,0 +1,90 @@
+//! The audit log: every change made through the library is recorded with who
+//! made it, when, and what it looked like before and after.
+
+use dao::ProtonDao;
+use error::Error;
+use project_types::{AuditEntry, AuditFilter, Credentials, PermissionEnum};
+use utils;
+
+
+/// Records a change made by uid just now. entity is the kind of thing changed
+/// (layout, project, section, sequence or user) and target its id, or a project's name.
+pub fn record<PD: ProtonDao>(
+    dao: &PD,
+    uid: u32,
+    command: &str,
+    project: Option<&str>,
+    entity: &str,
+    target: &str,
+    before: Option<String>,
+    after: Option<String>
+) -> Result<(), Error> {
+
+    let entry = AuditEntry {
+        auditid: 0,
+        uid: uid,
+        time: utils::now_secs(),
+        command: command.to_owned(),
+        project: project.map(|name| name.to_owned()),
+        entity: entity.to_owned(),
+        target: target.to_owned(),
+        before: before,
+        after: after
+    };
+    let _ = try!(dao.add_audit_entry(&entry));
+    Ok(())
+}
+
+/// Records a change once in each of the given projects, or once outside of any
+/// project if there are none, so it shows up in every project it affects
+pub fn record_in_projects<PD: ProtonDao>(
+    dao: &PD,
+    uid: u32,
+    command: &str,
+    projects: &[String],
+    entity: &str,
+    target: &str,
+    before: Option<String>,
+    after: Option<String>
+) -> Result<(), Error> {
+
+    if projects.is_empty() {
+        return record(dao, uid, command, None, entity, target, before, after);
+    }
+    for project in projects {
+        try!(record(dao, uid, command, Some(project), entity, target, before.clone(), after.clone()));
+    }
+    Ok(())
+}
+
+/// Records a change to a sequence or its contents in every project whose
+/// playlist has the sequence, the projects its permissions come from
+pub fn record_for_sequence<PD: ProtonDao>(
+    dao: &PD,
+    uid: u32,
+    command: &str,
+    seqid: u32,
+    entity: &str,
+    target: &str,
+    before: Option<String>,
+    after: Option<String>
+) -> Result<(), Error> {
+
+    let projects = try!(utils::sequence_projects(dao, seqid));
+    record_in_projects(dao, uid, command, &projects, entity, target, before, after)
+}
+
+/// Lists the audit entries the filter matches, oldest first. Needs Administrate,
+/// within the filter's project if it has one.
+pub fn audit_log<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    filter: &AuditFilter
+) -> Result<Vec<AuditEntry>, Error> {
+
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let project = filter.project.as_ref().map(|name| name.as_str());
+    let _ = try!(utils::check_valid_permission(dao, credentials, project, &valid_permissions));
+
+    dao.list_audit_entries(filter)
+}
@@ -0,0 +1,112 @@
+//! Proving who is running a command. The caller signs a challenge with their
+//! private key and the signature is checked against the public key stored for them,
+//! so knowing a user's public key is no longer enough to act as them.
+//!
+//! A challenge is random bytes issued and recorded by the database. Checking a
+//! signature over it takes the challenge out again, and challenges older than
+//! CHALLENGE_LIFETIME_SECS are rejected, so captured credentials can't be replayed.
+
+use openssl::rand;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use dao::ProtonDao;
+use error::Error;
+use keys::{self, PrivateKey, PublicKey};
+use project_types::Credentials;
+use utils;
+
+
+/// How long an issued challenge stays valid
+pub const CHALLENGE_LIFETIME_SECS: u64 = 300;
+
+const NUM_RANDOM_BYTES: usize = 32;
+
+/// Generates a key pair for a user to register with new-user or new-project.
+/// The private key (PEM) goes to private_key_path, readable only by its owner, and
+/// the public key to the same path with ".pub" appended. Existing files are never
+/// overwritten. Returns the public key's path.
+pub fn generate_key_pair<P: AsRef<Path>>(private_key_path: P) -> Result<PathBuf, Error> {
+    let mut public_key_path = private_key_path.as_ref().as_os_str().to_owned();
+    public_key_path.push(".pub");
+    let public_key_path = PathBuf::from(public_key_path);
+    for path in &[private_key_path.as_ref(), public_key_path.as_path()] {
+        if path.exists() {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()))));
+        }
+    }
+
+    let (public_key, private_key) = try!(utils::create_pub_priv_keys());
+    try!(write_new_file(private_key_path.as_ref(), &private_key, 0o600));
+    try!(write_new_file(&public_key_path, &public_key, 0o644));
+    Ok(public_key_path)
+}
+
+/// Issues a new challenge to sign. It works for one command only, and only for
+/// CHALLENGE_LIFETIME_SECS. Challenges that expired unused are forgotten.
+pub fn new_challenge<PD: ProtonDao>(dao: &PD) -> Result<Vec<u8>, Error> {
+    let now = utils::now_secs();
+    try!(dao.delete_challenges_before(now.saturating_sub(CHALLENGE_LIFETIME_SECS)));
+    let mut challenge = vec![0; NUM_RANDOM_BYTES];
+    try!(rand::rand_bytes(&mut challenge).map_err(Error::Ssl));
+    try!(dao.add_challenge(&challenge, now));
+    Ok(challenge)
+}
+
+/// Signs a challenge with the private key (PEM or OpenSSH) at the given path
+pub fn sign_challenge<P: AsRef<Path>>(
+    private_key_path: P,
+    challenge: &[u8]
+) -> Result<Credentials, Error> {
+    let private_key = try!(PrivateKey::load(private_key_path));
+    let signature = try!(private_key.sign(challenge));
+
+    Ok(Credentials {
+        public_key: private_key.public_key().to_openssh(),
+        challenge: challenge.to_vec(),
+        signature: signature
+    })
+}
+
+/// Checks that the credentials are signed by a known user's private key that hasn't
+/// been revoked, over a fresh challenge this database issued. The challenge is used
+/// up, so each command needs new credentials. Returns the user's id.
+pub fn verify_credentials<PD: ProtonDao>(dao: &PD, credentials: &Credentials) -> Result<u32, Error> {
+    if try!(dao.is_key_revoked(&credentials.public_key)) {
+        return Err(Error::KeyRevoked(try!(keys::fingerprint(&credentials.public_key))));
+    }
+
+    // Verify against the stored key, not the one the caller sent along
+    let uid = try!(dao.get_user_id(&credentials.public_key));
+    let user = try!(dao.get_user(uid));
+    let public_key = try!(PublicKey::parse(&user.public_key));
+    if !try!(public_key.verify(&credentials.challenge, &credentials.signature)) {
+        return Err(Error::InvalidSignature);
+    }
+
+    match try!(dao.take_challenge(&credentials.challenge)) {
+        Some(issued_at) if issued_at + CHALLENGE_LIFETIME_SECS >= utils::now_secs() => Ok(uid),
+        Some(_) => Err(Error::ExpiredChallenge),
+        None => Err(Error::UnknownChallenge),
+    }
+}
+
+fn write_new_file(path: &Path, contents: &str, mode: u32) -> Result<(), Error> {
+    let mut options = OpenOptions::new();
+    let _ = options.write(true).create_new(true);
+    set_mode(&mut options, mode);
+    let mut file = try!(options.open(path).map_err(Error::Io));
+    file.write_all(contents.as_bytes()).map_err(Error::Io)
+}
+
+#[cfg(unix)]
+fn set_mode(options: &mut OpenOptions, mode: u32) {
+    use std::os::unix::fs::OpenOptionsExt;
+    let _ = options.mode(mode);
+}
+
+#[cfg(not(unix))]
+fn set_mode(_: &mut OpenOptions, _: u32) {}
@@ -0,0 +1,477 @@
+//! Exporting a project to a self-contained bundle file and importing it again,
+//! possibly into a different storage backend
+
+use rustc_serialize::base64::{FromBase64, ToBase64, STANDARD};
+use rustc_serialize::json;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use audit;
+use dao::{ProtonDao, encode_frames, decode_frames};
+use error::Error;
+use project_types::{BundleChannelData, BundleFile, BundleSequence, Channel, Credentials, Fixture, ImportReport};
+use project_types::{Layout, PermissionEnum, Project, ProjectBundle, Sequence};
+use utils;
+
+
+/// Bundle layout version, bumped whenever ProjectBundle changes
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Writes a project and everything it depends on to a bundle file.
+/// Music files are read from music_dir. Needs Administrate within the project.
+pub fn export_project<PD: ProtonDao, P: AsRef<Path>, M: AsRef<Path>>(
+    dao: &PD,
+    credentials: &Credentials,
+    proj_name: &str,
+    bundle_path: P,
+    music_dir: M
+) -> Result<(), Error> {
+    let bundle = try!(create_bundle(dao, credentials, proj_name, music_dir));
+    let bundle_json = try!(json::encode(&bundle).map_err(Error::JsonEncode));
+    let mut bundle_file = try!(File::create(bundle_path.as_ref()).map_err(Error::Io));
+    bundle_file.write_all(bundle_json.as_bytes()).map_err(Error::Io)
+}
+
+/// Recreates the project in a bundle file, optionally under a new name.
+/// Music files are written to music_dir. Needs Administrate.
+pub fn import_project<PD: ProtonDao, P: AsRef<Path>, M: AsRef<Path>>(
+    dao: &PD,
+    credentials: &Credentials,
+    bundle_path: P,
+    new_name: Option<&str>,
+    music_dir: M
+) -> Result<ImportReport, Error> {
+    let bundle_json = try!(read_file(bundle_path.as_ref()));
+    let bundle_str = try!(String::from_utf8(bundle_json)
+        .map_err(|_| Error::InvalidBundle("not UTF-8".to_string())));
+    let bundle: ProjectBundle = try!(json::decode(&bundle_str).map_err(Error::JsonDecode));
+    import_bundle(dao, credentials, &bundle, new_name, music_dir)
+}
+
+/// Gathers a project and everything it depends on.
+/// Section permissions are left out since sections aren't bundled.
+/// Needs Administrate within the project, since the bundle holds its users.
+pub fn create_bundle<PD: ProtonDao, M: AsRef<Path>>(
+    dao: &PD,
+    credentials: &Credentials,
+    proj_name: &str,
+    music_dir: M
+) -> Result<ProjectBundle, Error> {
+    let project = try!(dao.get_project(proj_name));
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let _ = try!(utils::check_valid_permission(dao, credentials, Some(proj_name), &valid_permissions));
+
+    // Playlist sequences (a sequence may be in the playlist more than once)
+    let mut sequences: Vec<Sequence> = vec![];
+    for seqid in &project.playlist {
+        if !sequences.iter().any(|sequence| sequence.seqid == *seqid) {
+            sequences.push(try!(dao.get_sequence(*seqid)));
+        }
+    }
+
+    // The project's layout and any other layouts its sequences use
+    let mut layouts: Vec<Layout> = vec![try!(dao.get_layout(project.layout_id))];
+    for sequence in &sequences {
+        if !layouts.iter().any(|layout| layout.layout_id == sequence.layout_id) {
+            layouts.push(try!(dao.get_layout(sequence.layout_id)));
+        }
+    }
+    let mut fixtures: Vec<Fixture> = vec![];
+    let mut channels: Vec<Channel> = vec![];
+    for layout in &layouts {
+        for fixture in try!(dao.list_fixtures(layout.layout_id)) {
+            if !fixtures.iter().any(|f| f.fixid == fixture.fixid) {
+                fixtures.push(fixture);
+            }
+        }
+        for channel in try!(dao.list_channels(layout.layout_id)) {
+            if !channels.iter().any(|c| c.chanid == channel.chanid) {
+                channels.push(channel);
+            }
+        }
+    }
+
+    // Channel data
+    let mut bundle_sequences = vec![];
+    for sequence in sequences {
+        let mut channel_data = vec![];
+        for chanid in try!(dao.get_channel_ids(sequence.seqid)) {
+            let frames = match dao.get_data(sequence.seqid, chanid) {
+                Ok(frames) => frames,
+                Err(Error::ChannelDataNotFound(_, _)) => continue,
+                Err(e) => return Err(e),
+            };
+            channel_data.push(BundleChannelData {
+                chanid: chanid,
+                frames: encode_frames(&frames).to_base64(STANDARD)
+            });
+        }
+        bundle_sequences.push(BundleSequence {
+            sequence: sequence,
+            channel_data: channel_data
+        });
+    }
+
+    // The project's root user, anyone with a permission within the project,
+    // plus anyone allowed to edit one of its sequences
+    let root_name = format!("{}_{}", "root", proj_name);
+    let mut users = vec![];
+    let mut permissions = vec![];
+    for user in try!(dao.list_users(None)) {
+        let user_permissions = try!(dao.get_all_permissions(user.uid))
+            .into_iter()
+            .filter(|permission| match (permission.project.as_ref(), &permission.permission) {
+                (Some(name), _) => name == proj_name,
+                (None, &PermissionEnum::EditSequence(seqid)) => bundle_sequences.iter()
+                    .any(|bundle_seq| bundle_seq.sequence.seqid == seqid),
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+        if user.name == root_name || !user_permissions.is_empty() {
+            users.push(user);
+            permissions.extend(user_permissions);
+        }
+    }
+
+    // Music files
+    let mut music_files: Vec<BundleFile> = vec![];
+    for bundle_seq in &bundle_sequences {
+        let file_name = &bundle_seq.sequence.music_file_name;
+        if music_files.iter().any(|music_file| &music_file.name == file_name) {
+            continue;
+        }
+        let contents = try!(read_file(&music_dir.as_ref().join(file_name)));
+        music_files.push(BundleFile {
+            name: file_name.to_owned(),
+            contents: contents.to_base64(STANDARD)
+        });
+    }
+
+    Ok(ProjectBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        project: project,
+        layouts: layouts,
+        fixtures: fixtures,
+        channels: channels,
+        sequences: bundle_sequences,
+        users: users,
+        permissions: permissions,
+        music_files: music_files
+    })
+}
+
+/// Recreates a bundled project with new ids. Nothing is imported if the project
+/// name is taken, a layout, fixture or new user's name is taken, or a music file with
+/// the same name but different contents exists; every such conflict is reported at
+/// once. Users whose public key is already known, and layouts that already exist
+/// with the same fixtures and channels, are reused rather than duplicated. Needs Administrate, like new_project.
+pub fn import_bundle<PD: ProtonDao, M: AsRef<Path>>(
+    dao: &PD,
+    credentials: &Credentials,
+    bundle: &ProjectBundle,
+    new_name: Option<&str>,
+    music_dir: M
+) -> Result<ImportReport, Error> {
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let admin_uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
+
+    if bundle.format_version != BUNDLE_FORMAT_VERSION {
+        return Err(Error::InvalidBundle(format!(
+            "format version {} is not supported (expected {})",
+            bundle.format_version,
+            BUNDLE_FORMAT_VERSION)));
+    }
+    let proj_name = new_name.unwrap_or(&bundle.project.name);
+    if !Project::validate_name(proj_name) {
+        return Err(Error::InvalidProjectName(proj_name.to_owned()));
+    }
+
+    // Decode everything first so a damaged bundle doesn't import halfway
+    let mut music_files = vec![];
+    for music_file in &bundle.music_files {
+        if Path::new(&music_file.name).file_name().and_then(|name| name.to_str()) != Some(music_file.name.as_str()) {
+            return Err(Error::InvalidBundle(format!("bad music file name '{}'", music_file.name)));
+        }
+        music_files.push((&music_file.name, try!(decode_base64(&music_file.contents))));
+    }
+    let mut sequence_data = vec![];
+    for bundle_seq in &bundle.sequences {
+        let mut channel_data = vec![];
+        for data in &bundle_seq.channel_data {
+            let encoded = try!(decode_base64(&data.frames));
+            channel_data.push((data.chanid, try!(decode_frames(&encoded))));
+        }
+        sequence_data.push((&bundle_seq.sequence, channel_data));
+    }
+
+    // Report every conflict at once
+    let mut conflicts = vec![];
+    match dao.get_project(proj_name) {
+        Ok(_) => conflicts.push(format!("project '{}' already exists", proj_name)),
+        Err(Error::ProjectNotFound(_)) => {},
+        Err(e) => return Err(e),
+    }
+    // Layout, fixture and user names are unique too. A layout that already exists
+    // with the same fixtures and channels (say, when importing a copy into the
+    // database it came from) is reused along with them.
+    let mut notes = vec![];
+    let mut reused_layout_ids = BTreeMap::new();
+    let mut reused_fix_ids = BTreeMap::new();
+    let mut reused_chan_ids = BTreeMap::new();
+    for layout in &bundle.layouts {
+        let existing = try!(dao.list_layouts(Some(&layout.name)))
+            .into_iter()
+            .find(|existing| existing.name == layout.name);
+        if let Some(existing) = existing {
+            match try!(match_layout(dao, bundle, layout, &existing)) {
+                Some((fix_ids, chan_ids)) => {
+                    notes.push(format!("Layout '{}' already exists (layout {}), reusing it", layout.name, existing.layout_id));
+                    reused_layout_ids.insert(layout.layout_id, existing.layout_id);
+                    reused_fix_ids.extend(fix_ids);
+                    reused_chan_ids.extend(chan_ids);
+                },
+                None => conflicts.push(format!("layout '{}' already exists with other fixtures", layout.name)),
+            }
+        }
+    }
+    for fixture in &bundle.fixtures {
+        if reused_fix_ids.contains_key(&fixture.fixid) {
+            continue;
+        }
+        match dao.get_last_fixture(&fixture.name) {
+            Ok(_) => conflicts.push(format!("fixture '{}' already exists", fixture.name)),
+            Err(Error::FixtureNotFound(_)) => {},
+            Err(e) => return Err(e),
+        }
+    }
+    for user in &bundle.users {
+        match dao.get_user_id(&user.public_key) {
+            Ok(_) => {},
+            Err(Error::PublicKeyNotFound(_)) => if !try!(dao.get_users_by_name(&user.name)).is_empty() {
+                conflicts.push(format!("user '{}' already exists with another key", user.name));
+            },
+            Err(e) => return Err(e),
+        }
+    }
+    for &(name, ref contents) in &music_files {
+        let music_path = music_dir.as_ref().join(name);
+        if music_path.exists() && try!(read_file(&music_path)) != *contents {
+            conflicts.push(format!("{} already exists with different contents", music_path.display()));
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(Error::ImportConflicts(conflicts));
+    }
+
+    // Music first; leftover files are harmless if the database import fails
+    try!(fs::create_dir_all(music_dir.as_ref()).map_err(Error::Io));
+    for &(name, ref contents) in &music_files {
+        let music_path = music_dir.as_ref().join(name);
+        if !music_path.exists() {
+            let mut music_file = try!(File::create(&music_path).map_err(Error::Io));
+            try!(music_file.write_all(contents).map_err(Error::Io));
+        }
+    }
+
+    let (layout_ids, sequence_ids, user_ids) = try!(dao.transaction(|| {
+        let mut chan_ids = reused_chan_ids;
+        for channel in &bundle.channels {
+            if chan_ids.contains_key(&channel.chanid) {
+                continue;
+            }
+            let new_channel = try!(dao.new_channel(
+                &channel.name,
+                channel.numbers.0,
+                channel.numbers.1,
+                &channel.color,
+                channel.channel_internal,
+                channel.channel_dmx,
+                channel.location,
+                channel.rotation));
+            chan_ids.insert(channel.chanid, new_channel.chanid);
+        }
+
+        let mut fix_ids = reused_fix_ids;
+        for fixture in &bundle.fixtures {
+            if fix_ids.contains_key(&fixture.fixid) {
+                continue;
+            }
+            let mut channels = vec![];
+            for chanid in &fixture.channels {
+                channels.push(try!(map_id(&chan_ids, *chanid, "channel")));
+            }
+            let new_fixture = try!(dao.new_fixture(&fixture.name, fixture.location, fixture.rotation, channels));
+            fix_ids.insert(fixture.fixid, new_fixture.fixid);
+        }
+
+        let mut layout_ids = reused_layout_ids;
+        for layout in &bundle.layouts {
+            if layout_ids.contains_key(&layout.layout_id) {
+                continue;
+            }
+            let mut fixtures = vec![];
+            for fixid in &layout.fixtures {
+                fixtures.push(try!(map_id(&fix_ids, *fixid, "fixture")));
+            }
+            let new_layout = try!(dao.new_layout(&layout.name, fixtures));
+            layout_ids.insert(layout.layout_id, new_layout.layout_id);
+        }
+
+        let mut sequence_ids = BTreeMap::new();
+        for &(sequence, ref channel_data) in &sequence_data {
+            let new_sequence = try!(dao.new_sequence(&Sequence {
+                layout_id: try!(map_id(&layout_ids, sequence.layout_id, "layout")),
+                ..sequence.clone()
+            }));
+            for &(chanid, ref frames) in channel_data {
+                let new_chanid = try!(map_id(&chan_ids, chanid, "channel"));
+                try!(dao.new_data(new_sequence.seqid, new_chanid, frames));
+            }
+            sequence_ids.insert(sequence.seqid, new_sequence.seqid);
+        }
+
+        let mut user_ids = BTreeMap::new();
+        for user in &bundle.users {
+            let uid = match dao.get_user_id(&user.public_key) {
+                Ok(uid) => {
+                    notes.push(format!("User '{}' already exists (uid {}), reusing it", user.name, uid));
+                    uid
+                },
+                Err(Error::PublicKeyNotFound(_)) => try!(dao.add_user(&user.name, &user.public_key)),
+                Err(e) => return Err(e),
+            };
+            user_ids.insert(user.uid, uid);
+        }
+
+        for permission in &bundle.permissions {
+            let uid = try!(map_id(&user_ids, permission.uid, "user"));
+            let new_permission = match permission.permission {
+                PermissionEnum::EditSequence(seqid) =>
+                    PermissionEnum::EditSequence(try!(map_id(&sequence_ids, seqid, "sequence"))),
+                PermissionEnum::EditSection(seqid, secid) => {
+                    notes.push(format!("Skipped permission to edit section {} of sequence {}", secid, seqid));
+                    continue;
+                },
+                ref other => other.clone(),
+            };
+            // Permissions within the project move to its new name. Bundles from before
+            // permissions had projects hold the root's Administrate without one.
+            let project = match (permission.project.as_ref(), &new_permission) {
+                (Some(_), _) | (None, &PermissionEnum::Administrate) => Some(proj_name),
+                (None, _) => None,
+            };
+            let existing = try!(dao.get_all_permissions(uid));
+            let already_held = existing.iter().any(|p| {
+                p.permission == new_permission && p.project.as_ref().map(|name| name.as_str()) == project
+            });
+            if !already_held {
+                let _ = try!(dao.add_permission(uid, project, &new_permission));
+            }
+        }
+
+        let mut playlist = vec![];
+        for seqid in &bundle.project.playlist {
+            playlist.push(try!(map_id(&sequence_ids, *seqid, "sequence")));
+        }
+        let layout_id = try!(map_id(&layout_ids, bundle.project.layout_id, "layout"));
+        let _ = try!(dao.new_project(proj_name, layout_id));
+        try!(dao.update_project(Project {
+            name: proj_name.to_owned(),
+            playlist: playlist,
+            layout_id: layout_id
+        }));
+
+        let summary = format!("{} layouts, {} sequences, {} users from bundle of '{}'",
+            layout_ids.len(), sequence_ids.len(), user_ids.len(), bundle.project.name);
+        try!(audit::record(dao, admin_uid, "import-project", Some(proj_name), "project", proj_name,
+            None, Some(summary)));
+
+        Ok((layout_ids, sequence_ids, user_ids))
+    }));
+
+    Ok(ImportReport {
+        project_name: proj_name.to_owned(),
+        layout_ids: layout_ids,
+        sequence_ids: sequence_ids,
+        user_ids: user_ids,
+        notes: notes
+    })
+}
+
+/// Looks up the new id for a bundle id. A missing id means the bundle refers
+/// to something it doesn't contain.
+/// Pairs up a bundled layout's fixtures and channels with those of an existing
+/// layout of the same name, by bundled id, if they match apart from their ids
+fn match_layout<PD: ProtonDao>(
+    dao: &PD,
+    bundle: &ProjectBundle,
+    layout: &Layout,
+    existing: &Layout
+) -> Result<Option<(BTreeMap<u32, u32>, BTreeMap<u32, u32>)>, Error> {
+    let mut fix_ids = BTreeMap::new();
+    let mut chan_ids = BTreeMap::new();
+    if layout.fixtures.len() != existing.fixtures.len() {
+        return Ok(None);
+    }
+    for (fixid, existing_fixid) in layout.fixtures.iter().zip(&existing.fixtures) {
+        let fixture = match bundle.fixtures.iter().find(|fixture| fixture.fixid == *fixid) {
+            Some(fixture) => fixture,
+            None => return Err(Error::InvalidBundle(format!("missing fixture {}", fixid))),
+        };
+        let existing_fixture = try!(dao.get_fixture(*existing_fixid));
+        if fixture.name != existing_fixture.name ||
+            fixture.location != existing_fixture.location ||
+            fixture.rotation != existing_fixture.rotation ||
+            fixture.channels.len() != existing_fixture.channels.len() {
+            return Ok(None);
+        }
+        for (chanid, existing_chanid) in fixture.channels.iter().zip(&existing_fixture.channels) {
+            let channel = match bundle.channels.iter().find(|channel| channel.chanid == *chanid) {
+                Some(channel) => channel,
+                None => return Err(Error::InvalidBundle(format!("missing channel {}", chanid))),
+            };
+            if !same_channel(channel, &try!(dao.get_channel(*existing_chanid))) {
+                return Ok(None);
+            }
+            chan_ids.insert(*chanid, *existing_chanid);
+        }
+        fix_ids.insert(*fixid, *existing_fixid);
+    }
+    Ok(Some((fix_ids, chan_ids)))
+}
+
+/// True if the channels are the same apart from their ids
+fn same_channel(a: &Channel, b: &Channel) -> bool {
+    a.name == b.name &&
+        a.numbers == b.numbers &&
+        a.color == b.color &&
+        a.channel_internal == b.channel_internal &&
+        a.channel_dmx == b.channel_dmx &&
+        a.location == b.location &&
+        a.rotation == b.rotation
+}
+
+fn map_id(ids: &BTreeMap<u32, u32>, old_id: u32, kind: &str) -> Result<u32, Error> {
+    ids.get(&old_id)
+        .cloned()
+        .ok_or(Error::InvalidBundle(format!("{} {} is referenced but not included", kind, old_id)))
+}
+
+fn decode_base64(encoded: &str) -> Result<Vec<u8>, Error> {
+    encoded.from_base64()
+        .map_err(|e| Error::InvalidBundle(format!("bad base64 data: {}", e)))
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, Error> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound =>
+            return Err(Error::FileNotFound(path.display().to_string())),
+        Err(e) => return Err(Error::Io(e)),
+    };
+    let mut contents = vec![];
+    try!(file.read_to_end(&mut contents).map_err(Error::Io));
+    Ok(contents)
+}
@@ -0,0 +1,222 @@
+//! Editing a section offline: checking it out exports just its frames for its
+//! fixtures' channels, and checking it back in writes the edited frames back,
+//! unless someone else changed them in the meantime.
+
+use openssl::hash::{self, MessageDigest};
+use rustc_serialize::base64::{ToBase64, STANDARD};
+use rustc_serialize::json;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use audit;
+use dao::ProtonDao;
+use error::Error;
+use project_types::{Credentials, DataCell, PermissionEnum, Section, SectionChannel, SectionCheckout, Sequence};
+use utils;
+
+/// Checkout file layout version, bumped whenever SectionCheckout changes
+pub const CHECKOUT_FORMAT_VERSION: u32 = 1;
+
+/// Writes a section's frames to a checkout file. Needs EditSection on the section.
+pub fn checkout_section<PD: ProtonDao, P: AsRef<Path>>(
+    dao: &PD,
+    credentials: &Credentials,
+    secid: u32,
+    checkout_path: P
+) -> Result<SectionCheckout, Error> {
+    let checkout = try!(create_checkout(dao, credentials, secid));
+    let mut checkout_file = try!(File::create(checkout_path.as_ref()).map_err(Error::Io));
+    try!(write!(checkout_file, "{}", json::as_pretty_json(&checkout)).map_err(Error::Io));
+    Ok(checkout)
+}
+
+/// Writes the frames in a checkout file back to the section. Needs EditSection on
+/// the section. Returns the number of channels whose frames changed.
+pub fn checkin_section<PD: ProtonDao, P: AsRef<Path>>(
+    dao: &PD,
+    credentials: &Credentials,
+    secid: u32,
+    checkout_path: P
+) -> Result<u32, Error> {
+    if !checkout_path.as_ref().exists() {
+        return Err(Error::FileNotFound(checkout_path.as_ref().display().to_string()));
+    }
+    let mut checkout_json = String::new();
+    let mut checkout_file = try!(File::open(checkout_path.as_ref()).map_err(Error::Io));
+    try!(checkout_file.read_to_string(&mut checkout_json).map_err(Error::Io));
+    let checkout: SectionCheckout = try!(json::decode(&checkout_json).map_err(Error::JsonDecode));
+    apply_checkin(dao, credentials, secid, &checkout)
+}
+
+/// Gathers a section's frames. Needs EditSection on the section.
+pub fn create_checkout<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    secid: u32
+) -> Result<SectionCheckout, Error> {
+    let section = try!(dao.get_section(secid));
+    let valid_permissions = vec![PermissionEnum::EditSection(section.seqid, secid)];
+    let _ = try!(utils::check_sequence_permission(dao, credentials, section.seqid, &valid_permissions));
+
+    let sequence = try!(dao.get_sequence(section.seqid));
+    let (first_frame, end_frame) = section.frame_range(&sequence);
+    let channels = try!(section_channels(dao, &section, &sequence));
+    let checksum = try!(checksum(first_frame, &channels));
+    Ok(SectionCheckout {
+        format_version: CHECKOUT_FORMAT_VERSION,
+        secid: secid,
+        seqid: section.seqid,
+        frame_duration_ms: sequence.frame_duration_ms,
+        first_frame: first_frame,
+        num_frames: end_frame - first_frame,
+        channels: channels,
+        checksum: checksum
+    })
+}
+
+/// Writes a checkout's frames back to the section, all or nothing. The checkout
+/// must have exactly the section's channels and frames, and the section's data
+/// must not have changed since it was checked out. Needs EditSection on the section.
+/// Returns the number of channels whose frames changed.
+pub fn apply_checkin<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    secid: u32,
+    checkout: &SectionCheckout
+) -> Result<u32, Error> {
+    let section = try!(dao.get_section(secid));
+    let valid_permissions = vec![PermissionEnum::EditSection(section.seqid, secid)];
+    let uid = try!(utils::check_sequence_permission(dao, credentials, section.seqid, &valid_permissions));
+
+    if checkout.format_version != CHECKOUT_FORMAT_VERSION {
+        return Err(Error::InvalidCheckout(format!(
+            "format version {} is not supported (expected {})",
+            checkout.format_version,
+            CHECKOUT_FORMAT_VERSION)));
+    }
+    if checkout.secid != secid {
+        return Err(Error::InvalidCheckout(format!("this is a checkout of section {}", checkout.secid)));
+    }
+
+    // Compare and write in one transaction, so nobody can change the section's
+    // data in between (the data read is locked until it ends)
+    dao.transaction(|| {
+        // The section or its sequence may have been changed since
+        let sequence = try!(dao.get_sequence(section.seqid));
+        let (first_frame, end_frame) = section.frame_range(&sequence);
+        if checkout.seqid != section.seqid ||
+            checkout.frame_duration_ms != sequence.frame_duration_ms ||
+            checkout.first_frame != first_frame ||
+            checkout.num_frames != end_frame - first_frame {
+            return Err(Error::SectionChanged(secid));
+        }
+
+        // Check the shape before the checksum, so editing mistakes are reported as such
+        let current = try!(section_channels(dao, &section, &sequence));
+        for (i, channel) in checkout.channels.iter().enumerate() {
+            if !current.iter().any(|current_channel| current_channel.chanid == channel.chanid) {
+                return Err(Error::InvalidCheckout(format!("channel {} is not in the section", channel.chanid)));
+            }
+            if checkout.channels[..i].iter().any(|other| other.chanid == channel.chanid) {
+                return Err(Error::InvalidCheckout(format!("channel {} is given twice", channel.chanid)));
+            }
+            if channel.frames.len() != checkout.num_frames as usize {
+                return Err(Error::InvalidCheckout(format!(
+                    "channel {} has {} frames, expected {}",
+                    channel.chanid,
+                    channel.frames.len(),
+                    checkout.num_frames)));
+            }
+        }
+        for current_channel in &current {
+            if !checkout.channels.iter().any(|channel| channel.chanid == current_channel.chanid) {
+                return Err(Error::InvalidCheckout(format!("channel {} is missing", current_channel.chanid)));
+            }
+        }
+        if try!(checksum(first_frame, &current)) != checkout.checksum {
+            return Err(Error::SectionChanged(secid));
+        }
+
+        // Only write the frames that were edited
+        let mut cells = vec![];
+        let mut changed = 0;
+        for current_channel in &current {
+            let edited = checkout.channels.iter()
+                .find(|channel| channel.chanid == current_channel.chanid)
+                .expect("Checked above that every channel is in the checkout");
+            let num_cells = cells.len();
+            for (i, (old, new)) in current_channel.frames.iter().zip(&edited.frames).enumerate() {
+                if old != new {
+                    cells.push(DataCell {
+                        chanid: current_channel.chanid,
+                        frame: first_frame + i as u32,
+                        value: *new
+                    });
+                }
+            }
+            if cells.len() > num_cells {
+                changed += 1;
+            }
+        }
+
+        try!(dao.patch_data(section.seqid, &cells));
+        let after = format!("{} channels changed", changed);
+        try!(audit::record_for_sequence(dao, uid, "checkin-section", section.seqid, "section", &secid.to_string(),
+            Some(checkout.checksum.clone()), Some(after)));
+        Ok(changed)
+    })
+}
+
+/// The section's channels with their frames within the section, by DMX channel
+fn section_channels<PD: ProtonDao>(
+    dao: &PD,
+    section: &Section,
+    sequence: &Sequence
+) -> Result<Vec<SectionChannel>, Error> {
+    let (first_frame, end_frame) = section.frame_range(sequence);
+    let mut chan_ids = vec![];
+    for fixid in &section.fixtures {
+        let fixture = try!(dao.get_fixture(*fixid));
+        for chanid in fixture.channels {
+            if !chan_ids.contains(&chanid) {
+                chan_ids.push(chanid);
+            }
+        }
+    }
+
+    let mut range_data = try!(dao.get_data_range(section.seqid, &chan_ids, first_frame, end_frame));
+    let mut channels = vec![];
+    for chanid in chan_ids {
+        let channel = try!(dao.get_channel(chanid));
+        channels.push(SectionChannel {
+            chanid: chanid,
+            channel_dmx: channel.channel_dmx,
+            name: channel.name,
+            frames: range_data.remove(&chanid).unwrap_or_default()
+        });
+    }
+    channels.sort_by_key(|channel| (channel.channel_dmx, channel.chanid));
+    Ok(channels)
+}
+
+/// Identifies the section's frames as they are, to notice changes between
+/// checkout and checkin
+fn checksum(first_frame: u32, channels: &[SectionChannel]) -> Result<String, Error> {
+    let mut bytes = vec![];
+    push_u32(&mut bytes, first_frame);
+    for channel in channels {
+        push_u32(&mut bytes, channel.chanid);
+        push_u32(&mut bytes, channel.frames.len() as u32);
+        for value in &channel.frames {
+            bytes.push((value >> 8) as u8);
+            bytes.push(*value as u8);
+        }
+    }
+    let digest = try!(hash::hash(MessageDigest::sha256(), &bytes).map_err(Error::Ssl));
+    Ok(format!("SHA256:{}", digest.to_base64(STANDARD)))
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&[(value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8]);
+}
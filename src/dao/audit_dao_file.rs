@@ -0,0 +1,17 @@
+use project_types::{AuditEntry, AuditFilter};
+use dao::{AuditDao, DaoFile};
+use error::Error;
+
+
+impl AuditDao for DaoFile {
+
+    fn add_audit_entry(&self, entry: &AuditEntry) -> Result<AuditEntry, Error> {
+        let new_entry = try!(self.memory.add_audit_entry(entry));
+        try!(self.save());
+        Ok(new_entry)
+    }
+
+    fn list_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, Error> {
+        self.memory.list_audit_entries(filter)
+    }
+}
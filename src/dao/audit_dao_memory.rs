@@ -0,0 +1,31 @@
+use project_types::{AuditEntry, AuditFilter};
+use dao::{AuditDao, DaoMemory};
+use error::Error;
+
+
+impl AuditDao for DaoMemory {
+
+    fn add_audit_entry(&self, entry: &AuditEntry) -> Result<AuditEntry, Error> {
+        let mut store = self.store.borrow_mut();
+        if store.audit_log.is_none() {
+            store.audit_log = Some(vec![]);
+        }
+        let audit_log = store.audit_log.as_mut().unwrap();
+        // Entries are never deleted, so the last one has the highest id
+        let auditid = audit_log.last().map_or(1, |last| last.auditid + 1);
+        let mut new_entry = entry.clone();
+        new_entry.auditid = auditid;
+        audit_log.push(new_entry.clone());
+        Ok(new_entry)
+    }
+
+    fn list_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, Error> {
+        let store = self.store.borrow();
+        let entries = store.audit_log.iter()
+            .flat_map(|audit_log| audit_log.iter())
+            .filter(|entry| filter.matches(entry))
+            .cloned()
+            .collect::<Vec<AuditEntry>>();
+        Ok(entries)
+    }
+}
@@ -0,0 +1,74 @@
+use project_types::{AuditEntry, AuditFilter};
+use dao::{AuditDao, DaoPostgres};
+use error::Error;
+
+
+impl AuditDao for DaoPostgres {
+
+    fn add_audit_entry(&self, entry: &AuditEntry) -> Result<AuditEntry, Error> {
+        let statement = "INSERT INTO audit_log (uid, logged_at, command, project, entity, target, before, after) \
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING auditid";
+        let results = try!(
+            self.conn.query(
+                statement,
+                &[
+                    &(entry.uid as i32),
+                    &(entry.time as i64),
+                    &entry.command,
+                    &entry.project,
+                    &entry.entity,
+                    &entry.target,
+                    &entry.before,
+                    &entry.after
+                ])
+            .map_err(Error::Postgres));
+        let row = results.get(0);
+        let auditid: i32 = row.get(0);
+        let mut new_entry = entry.clone();
+        new_entry.auditid = auditid as u32;
+        Ok(new_entry)
+    }
+
+    fn list_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, Error> {
+        let query = "SELECT auditid, uid, logged_at, command, project, entity, target, before, after \
+            FROM audit_log \
+            WHERE ($1::integer IS NULL OR uid = $1) \
+            AND ($2::text IS NULL OR project = $2) \
+            AND ($3::text IS NULL OR entity = $3) \
+            AND ($4::text IS NULL OR target = $4) \
+            AND ($5::bigint IS NULL OR logged_at >= $5) \
+            AND ($6::bigint IS NULL OR logged_at <= $6) \
+            ORDER BY auditid";
+        let results = try!(
+            self.conn.query(
+                query,
+                &[
+                    &filter.uid.map(|uid| uid as i32),
+                    &filter.project,
+                    &filter.entity,
+                    &filter.target,
+                    &filter.since.map(|since| since as i64),
+                    &filter.until.map(|until| until as i64)
+                ])
+            .map_err(Error::Postgres));
+        let entries = results.iter()
+            .map(|row| {
+                let auditid: i32 = row.get(0);
+                let uid: i32 = row.get(1);
+                let logged_at: i64 = row.get(2);
+                AuditEntry {
+                    auditid: auditid as u32,
+                    uid: uid as u32,
+                    time: logged_at as u64,
+                    command: row.get(3),
+                    project: row.get(4),
+                    entity: row.get(5),
+                    target: row.get(6),
+                    before: row.get(7),
+                    after: row.get(8)
+                }
+            })
+            .collect::<Vec<AuditEntry>>();
+        Ok(entries)
+    }
+}
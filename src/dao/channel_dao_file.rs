@@ -0,0 +1,45 @@
+use dao::{ChannelDao, DaoFile};
+use error::Error;
+use project_types::Channel;
+
+
+impl ChannelDao for DaoFile {
+    /// Fetch a Channel with the given channel id
+    fn get_channel(&self, chanid: u32) -> Result<Channel, Error> {
+        self.memory.get_channel(chanid)
+    }
+
+    /// Add a channel and save
+    fn new_channel(
+        &self,
+        name: &str,
+        primary_num: Option<u32>,
+        secondary_num: Option<u32>,
+        color: &str,
+        channel_internal: u32,
+        channel_dmx: u32,
+        location: (Option<i32>, Option<i32>, Option<i32>),
+        rotation: (Option<i32>, Option<i32>, Option<i32>)
+    ) -> Result<Channel, Error> {
+        let channel = try!(self.memory.new_channel(
+            name,
+            primary_num,
+            secondary_num,
+            color,
+            channel_internal,
+            channel_dmx,
+            location,
+            rotation));
+        try!(self.save());
+        Ok(channel)
+    }
+
+    /// Get the last channel added with the given name
+    fn get_last_channel(&self, name: &str) -> Result<Channel, Error> {
+        self.memory.get_last_channel(name)
+    }
+
+    fn list_channels(&self, layoutid: u32) -> Result<Vec<Channel>, Error> {
+        self.memory.list_channels(layoutid)
+    }
+}
@@ -0,0 +1,68 @@
+use dao::{ChannelDao, DaoMemory};
+use error::Error;
+use project_types::Channel;
+
+
+impl ChannelDao for DaoMemory {
+    /// Fetch a Channel with the given channel id
+    fn get_channel(&self, chanid: u32) -> Result<Channel, Error> {
+        let store = self.store.borrow();
+        store.channels.get(&chanid)
+            .cloned()
+            .ok_or(Error::ChannelNotFound(chanid))
+    }
+
+    /// Add a channel to memory
+    fn new_channel(
+        &self,
+        name: &str,
+        primary_num: Option<u32>,
+        secondary_num: Option<u32>,
+        color: &str,
+        channel_internal: u32,
+        channel_dmx: u32,
+        location: (Option<i32>, Option<i32>, Option<i32>),
+        rotation: (Option<i32>, Option<i32>, Option<i32>)
+    ) -> Result<Channel, Error> {
+        let mut store = self.store.borrow_mut();
+        let chanid = store.next_chanid();
+        let channel = Channel {
+            chanid: chanid,
+            name: name.to_owned(),
+            numbers: (primary_num, secondary_num),
+            color: color.to_owned(),
+            channel_internal: channel_internal,
+            channel_dmx: channel_dmx,
+            location: location,
+            rotation: rotation
+        };
+        store.channels.insert(chanid, channel.clone());
+        Ok(channel)
+    }
+
+    /// Get the last channel added with the given name
+    fn get_last_channel(&self, name: &str) -> Result<Channel, Error> {
+        let store = self.store.borrow();
+        store.channels.values()
+            .rev()
+            .find(|channel| channel.name == name)
+            .cloned()
+            .ok_or(Error::ChannelNotFound(0))
+    }
+
+    fn list_channels(&self, layoutid: u32) -> Result<Vec<Channel>, Error> {
+        let store = self.store.borrow();
+        let mut channels = vec![];
+        if let Some(layout) = store.layouts.get(&layoutid) {
+            for fixid in &layout.fixtures {
+                if let Some(fixture) = store.fixtures.get(fixid) {
+                    channels.extend(fixture.channels.iter()
+                        .filter_map(|chanid| store.channels.get(chanid))
+                        .cloned());
+                }
+            }
+        }
+        channels.sort_by_key(|channel| (channel.channel_dmx, channel.chanid));
+        Ok(channels)
+    }
+}
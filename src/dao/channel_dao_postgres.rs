@@ -58,9 +58,9 @@ impl ChannelDao for DaoPostgres {
         let statement = "INSERT INTO channels (name,primary_num,secondary_num,\
             color,channel_internal,channel_dmx,location_x,location_y,location_z,\
             rotation_a,rotation_b,rotation_c) \
-            VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12)";
-        let _ = try!(
-            self.conn.execute(
+            VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12) RETURNING chanid";
+        let results = try!(
+            self.conn.query(
                 statement,
                 &[
                     &name.to_owned(),
@@ -77,9 +77,18 @@ impl ChannelDao for DaoPostgres {
                     &rotation.2
                 ])
             .map_err(Error::Postgres));
-        // Get the most recently added channel with the given name
-        let channel = try!(self.get_last_channel(name));
-        Ok(channel)
+        let row = results.get(0);
+        let chanid: i32 = row.get(0);
+        Ok(Channel {
+            chanid: chanid as u32,
+            name: name.to_owned(),
+            numbers: (primary_num, secondary_num),
+            color: color.to_owned(),
+            channel_internal: channel_internal,
+            channel_dmx: channel_dmx,
+            location: location,
+            rotation: rotation
+        })
     }
 
     /// Get the last channel added with the given name
@@ -118,4 +127,45 @@ impl ChannelDao for DaoPostgres {
             rotation: (rotation_a, rotation_b, rotation_c)
         })
     }
+
+    fn list_channels(&self, layoutid: u32) -> Result<Vec<Channel>, Error> {
+        let query = "SELECT c.chanid,c.name,c.primary_num,c.secondary_num,c.color,c.channel_internal, \
+            c.channel_dmx,c.location_x,c.location_y,c.location_z,c.rotation_a,c.rotation_b,c.rotation_c \
+            FROM layouts l \
+            INNER JOIN fixtures f ON f.fixid = ANY(l.fixtures) \
+            INNER JOIN channels c ON c.chanid = ANY(f.channels) \
+            WHERE l.layoutid = $1 \
+            ORDER BY c.channel_dmx, c.chanid";
+        let results = try!(
+            self.conn.query(query, &[&(layoutid as i32)])
+            .map_err(Error::Postgres));
+        let channels = results.iter()
+            .map(|row| {
+                let chanid: i32 = row.get(0);
+                let name: String = row.get(1);
+                let primary_num: Option<i32> = row.get(2);
+                let secondary_num: Option<i32> = row.get(3);
+                let color: String = row.get(4);
+                let channel_internal: i32 = row.get(5);
+                let channel_dmx: i32 = row.get(6);
+                let location_x: Option<i32> = row.get(7);
+                let location_y: Option<i32> = row.get(8);
+                let location_z: Option<i32> = row.get(9);
+                let rotation_a: Option<i32> = row.get(10);
+                let rotation_b: Option<i32> = row.get(11);
+                let rotation_c: Option<i32> = row.get(12);
+                Channel {
+                    chanid: chanid as u32,
+                    name: name,
+                    numbers: (primary_num.map(|pnum| pnum as u32), secondary_num.map(|snum| snum as u32)),
+                    color: color,
+                    channel_internal: channel_internal as u32,
+                    channel_dmx: channel_dmx as u32,
+                    location: (location_x, location_y, location_z),
+                    rotation: (rotation_a, rotation_b, rotation_c)
+                }
+            })
+            .collect::<Vec<Channel>>();
+        Ok(channels)
+    }
 }
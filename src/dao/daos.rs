@@ -1,10 +1,17 @@
+//! DAO traits, one per kind of entity. The get_last_* lookups by name are query
+//! helpers only: the new_* methods return the id of what they created, and that
+//! is the id to use, since names need not be unique.
+
+use std::collections::BTreeMap;
+
 use error::Error;
-use project_types::{Channel, Fixture, Layout, Permission, Project, Section, Sequence, User};
+use project_types::{AuditEntry, AuditFilter, Channel, DataCell, Fixture, Layout, Permission, PermissionEnum, Project, Section, Sequence, SequenceDeletion, User};
 
 
 // Aggregate trait type containing all of the daos
 pub trait ProtonDao:
-    ChannelDao
+    AuditDao
+    + ChannelDao
     + DataDao
     + FixtureDao
     + LayoutDao
@@ -12,11 +19,21 @@ pub trait ProtonDao:
     + ProjectDao
     + SectionDao
     + SequenceDao
+    + TransactionDao
     + UserDao {}
 
+/// Handles the audit log of changes
+pub trait AuditDao {
+    /// Records a change. The returned entry has the id of the row actually inserted.
+    fn add_audit_entry(&self, entry: &AuditEntry) -> Result<AuditEntry, Error>;
+
+    /// Lists the entries the filter matches, oldest first
+    fn list_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, Error>;
+}
+
 /// Handles metadata related to channels
 pub trait ChannelDao {
-    /// Add a channel
+    /// Add a channel. The returned channel has the id of the row actually inserted.
     fn new_channel(
         &self,
         name: &str,
@@ -32,8 +49,11 @@ pub trait ChannelDao {
     /// Fetch a Channel with the given channel id
     fn get_channel(&self, chanid: u32) -> Result<Channel, Error>;
 
-    /// Get the last channel added with the given name
+    /// Get the last channel added with the given name.
     fn get_last_channel(&self, name: &str) -> Result<Channel, Error>;
+
+    /// Lists every channel in a layout, ordered by DMX channel
+    fn list_channels(&self, layoutid: u32) -> Result<Vec<Channel>, Error>;
 }
 
 /// Handles the raw output data for specific channel/sequence pairs
@@ -57,13 +77,34 @@ pub trait DataDao {
     /// Retrieve the data for a given sequence and channel
     fn get_data(&self, seqid: u32, chanid: u32) -> Result<Vec<u16>, Error>;
 
+    /// Retrieve the data for every channel in the sequence's layout in one go,
+    /// keyed by each channel's DMX channel. Fails if a channel has no data.
+    fn get_sequence_data(&self, seqid: u32) -> Result<BTreeMap<u32, Vec<u16>>, Error>;
+
+    /// Retrieve frames [start_frame, end_frame) of the given channels' data for
+    /// a sequence, keyed by chanid. Inside a transaction, the channels read stay
+    /// locked against other writers until it ends.
+    fn get_data_range(
+        &self,
+        seqid: u32,
+        chan_ids: &[u32],
+        start_frame: u32,
+        end_frame: u32
+    ) -> Result<BTreeMap<u32, Vec<u16>>, Error>;
+
+    /// Set single frames of a sequence's channels' data, all or nothing.
+    /// A later cell for the same channel and frame wins. Every channel with a
+    /// cell is still read and rewritten whole, since frames are stored encoded.
+    fn patch_data(&self, seqid: u32, cells: &[DataCell]) -> Result<(), Error>;
+
     /// Update a sequence's channel's data
     fn update_data<'a>(&'a self, seqid: u32, chanid: u32, new_data: &'a Vec<u16>) -> Result<(), Error>;
 }
 
 /// Handles metadata related to fixtures
 pub trait FixtureDao {
-    /// Creates a new fixture made up of some channels
+    /// Creates a new fixture made up of some channels.
+    /// The returned fixture has the id of the row actually inserted.
     fn new_fixture(
         &self, 
         name: &str,
@@ -75,7 +116,7 @@ pub trait FixtureDao {
     /// Retrieves and returns a fixture based on its id
     fn get_fixture(&self, fixid: u32) -> Result<Fixture, Error>;
 
-    /// Retrieves and returns the last fixture added with the given name
+    /// Retrieves and returns the last fixture added with the given name.
     fn get_last_fixture(&self, name: &str) -> Result<Fixture, Error>;
 
     /// Retrieves and returns the number of channels a fixture has
@@ -83,11 +124,15 @@ pub trait FixtureDao {
 
     /// Returns true if the fixture exists, false otherwise
     fn fixture_exists(&self, fixid: u32) -> Result<bool, Error>;
+
+    /// Lists the fixtures in a layout, in the layout's order
+    fn list_fixtures(&self, layoutid: u32) -> Result<Vec<Fixture>, Error>;
 }
 
 /// Handles metadata related to layouts
 pub trait LayoutDao {
-    /// Create a new layout with the given fixtures
+    /// Create a new layout with the given fixtures.
+    /// The returned layout has the id of the row actually inserted.
     fn new_layout(&self, name: &str, fixtures: Vec<u32>) -> Result<Layout, Error>;
 
     /// Retrieve and return the default layout
@@ -96,12 +141,16 @@ pub trait LayoutDao {
     /// Retrieve and return a layout
     fn get_layout(&self, layoutid: u32) -> Result<Layout, Error>;
 
-    /// Retrieve and return the last layout created with the given name
+    /// Retrieve and return the last layout created with the given name.
     fn get_last_layout(&self, name: &str) -> Result<Layout, Error>;
 
     /// Returns true if the layout exists, false otherwise
     fn layout_exists(&self, layoutid: u32) -> Result<bool, Error>;
 
+    /// Lists layouts by id. If given, only layouts whose name contains
+    /// name_filter (ignoring case) are listed.
+    fn list_layouts(&self, name_filter: Option<&str>) -> Result<Vec<Layout>, Error>;
+
     /// Patch a channel (change a channel's dmx output channel)
     fn patch_channel(
         &self,
@@ -111,10 +160,32 @@ pub trait LayoutDao {
     ) -> Result<u64, Error>;
 }
 
-/// [INCOMPLETE] Handles data related to permissions
+/// Handles data related to permissions
 pub trait PermissionDao {
-    fn add_initial_permission(&self, root_uid: u32) -> Result<(), Error>;
+    /// Makes a project's root user that project's administrator
+    fn add_initial_permission(&self, root_uid: u32, proj_name: &str) -> Result<(), Error>;
+
+    /// Grants a user a permission, within a project if given. Returns the permission as stored.
+    fn add_permission(
+        &self,
+        uid: u32,
+        project: Option<&str>,
+        permission: &PermissionEnum
+    ) -> Result<Permission, Error>;
+
+    /// Revokes a permission from a user. Fails if the user doesn't have it
+    /// within the given project (or, for None, outside of any project).
+    fn revoke_permission(
+        &self,
+        uid: u32,
+        project: Option<&str>,
+        permission: &PermissionEnum
+    ) -> Result<(), Error>;
+
+    /// Retrieves every permission a user has
     fn get_all_permissions(&self, uid: u32) -> Result<Vec<Permission>, Error>;
+
+    /// Retrieves a single permission
     fn get_permission(&self, permid: u32) -> Result<Permission, Error>;
 }
 
@@ -128,12 +199,26 @@ pub trait ProjectDao {
 
     /// Update a project's metadata
     fn update_project(&self, new_project: Project) -> Result<(), Error>;
+
+    /// Lists projects by name. If given, only projects whose name contains
+    /// name_filter (ignoring case) are listed.
+    fn list_projects(&self, name_filter: Option<&str>) -> Result<Vec<Project>, Error>;
 }
 
 /// Handles metadata about sections of sequences
 pub trait SectionDao {
+    /// Adds a section. The returned section has the id of the row actually inserted.
+    fn new_section(&self, section: &Section) -> Result<Section, Error>;
+
     /// Retrieve and return a sequence section
     fn get_section(&self, secid: u32) -> Result<Section, Error>;
+
+    /// Lists a sequence's sections by start time. Within a transaction, other
+    /// transactions listing the same sequence's sections wait until it ends.
+    fn list_sections(&self, seqid: u32) -> Result<Vec<Section>, Error>;
+
+    /// Deletes a section along with the permissions to edit it
+    fn delete_section(&self, secid: u32) -> Result<(), Error>;
 }
 
 /// Handles metadata related to sequences
@@ -141,33 +226,113 @@ pub trait SequenceDao {
     /// Retrieves and returns a sequence
     fn get_sequence(&self, seqid: u32) -> Result<Sequence, Error>;
 
-    /// Retrieves and returns the last sequence added with the given name
+    /// Retrieves and returns the last sequence added with the given name.
     fn get_last_sequence(&self, name: &str) -> Result<Sequence, Error>;
 
-    /// Creates a new sequence
+    /// Creates a new sequence. The returned sequence has the id of the row actually inserted.
     fn new_sequence(&self, sequence: &Sequence) -> Result<Sequence, Error>;
 
     /// Sets a sequence's layout
     fn set_layout(&self, seqid: u32, layout_id: u32) -> Result<(), Error>;
 
+    /// Sets a sequence's frame duration and number of frames. Rewrite its
+    /// channel data to match in the same transaction.
+    fn set_frames(&self, seqid: u32, frame_duration_ms: u32, num_frames: u32) -> Result<(), Error>;
+
     /// Returns true if the sequence exists, false otherwise
     fn sequence_exists(&self, seqid: u32) -> Result<bool, Error>;
 
     /// Retrieves and returns a vector of all channels in a sequence
     fn get_channel_ids(&self, seqid: u32) -> Result<Vec<u32>, Error>;
+
+    /// Lists sequences by id. If given, only sequences whose name contains
+    /// name_filter (ignoring case) are listed.
+    fn list_sequences(&self, name_filter: Option<&str>) -> Result<Vec<Sequence>, Error>;
+
+    /// Deletes a sequence along with its channel data, sections and the permissions
+    /// that target it, and removes it from every playlist. Call it in a transaction.
+    fn delete_sequence(&self, seqid: u32) -> Result<SequenceDeletion, Error>;
+}
+
+/// Groups several dao calls so they are applied all or nothing.
+/// Transactions can be nested; an inner rollback only undoes the inner transaction.
+pub trait TransactionDao {
+    /// Starts a (possibly nested) transaction
+    fn begin_transaction(&self) -> Result<(), Error>;
+
+    /// Commits the innermost open transaction
+    fn commit_transaction(&self) -> Result<(), Error>;
+
+    /// Undoes everything done since the innermost open transaction began
+    fn rollback_transaction(&self) -> Result<(), Error>;
+
+    /// Runs the given function in a transaction, committing if it succeeds
+    /// and rolling back if it fails
+    fn transaction<T, F>(&self, f: F) -> Result<T, Error>
+        where F: FnOnce() -> Result<T, Error>
+    {
+        try!(self.begin_transaction());
+        match f() {
+            Ok(value) => {
+                try!(self.commit_transaction());
+                Ok(value)
+            },
+            Err(e) => {
+                // Report the original error, not a failed rollback
+                let _ = self.rollback_transaction();
+                Err(e)
+            },
+        }
+    }
 }
 
 /// Handles user data
 pub trait UserDao {
-    /// Creates a project's initial root user
-    fn add_initial_user(&self, proj_name: &str, private_key: &str, public_key: &str) -> Result<u32, Error>;
+    /// Creates a project's initial root user. Returns the new user's id.
+    fn add_initial_user(&self, proj_name: &str, public_key: &str) -> Result<u32, Error>;
 
-    /// Creates a new user
-    fn add_user(&self, name: &str, private_key: &str, public_key: &str) -> Result<u32, Error>;
+    /// Creates a new user. Returns the id of the row actually inserted.
+    fn add_user(&self, name: &str, public_key: &str) -> Result<u32, Error>;
 
     /// Retrieves and returns a user
     fn get_user(&self, uid: u32) -> Result<User, Error>;
 
     /// Identifies a user based on their public key
     fn get_user_id(&self, public_key: &str) -> Result<u32, Error>;
+
+    /// Retrieves the user with exactly the given name, if any, as a list ordered by id
+    fn get_users_by_name(&self, name: &str) -> Result<Vec<User>, Error>;
+
+    /// Deletes a user along with all of their permissions
+    fn delete_user(&self, uid: u32) -> Result<(), Error>;
+
+    /// Replaces a user's public key, keeping their uid and permissions
+    fn set_public_key(&self, uid: u32, public_key: &str) -> Result<(), Error>;
+
+    /// Marks a user's public key as revoked. It stays revoked even if the user is removed.
+    fn revoke_key(&self, uid: u32, public_key: &str) -> Result<(), Error>;
+
+    /// True if the public key, given in any format, has been revoked
+    fn is_key_revoked(&self, public_key: &str) -> Result<bool, Error>;
+
+    /// True while new_admin may register an administrator without credentials.
+    /// New databases start open; new_admin closes it again.
+    fn is_new_admin_open(&self) -> Result<bool, Error>;
+
+    /// Opens or closes new_admin
+    fn set_new_admin_open(&self, open: bool) -> Result<(), Error>;
+
+    /// Records a challenge issued at the given time (seconds since 1970)
+    fn add_challenge(&self, challenge: &[u8], issued_at: u64) -> Result<(), Error>;
+
+    /// Forgets a challenge so it can't be used again. Returns when it was issued,
+    /// or None if it never was or has already been taken.
+    fn take_challenge(&self, challenge: &[u8]) -> Result<Option<u64>, Error>;
+
+    /// Forgets every challenge issued before the given time
+    fn delete_challenges_before(&self, issued_before: u64) -> Result<(), Error>;
+
+    /// Lists users by id. If given, only users whose name contains
+    /// name_filter (ignoring case) are listed.
+    fn list_users(&self, name_filter: Option<&str>) -> Result<Vec<User>, Error>;
 }
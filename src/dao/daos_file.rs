@@ -0,0 +1,93 @@
+use rustc_serialize::json;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use dao::DaoMemory;
+use dao::daos_memory::MemoryStore;
+use error::Error;
+use utils;
+
+
+/// Single-file storage backend for shows too small to need a database server.
+/// All data is kept in memory and the whole project database is rewritten
+/// to the file after every change (or once per transaction).
+/// Only one DaoFile at a time can have a file open, since each one would
+/// overwrite the other's changes with its own copy.
+pub struct DaoFile {
+    pub path: PathBuf,
+    pub memory: DaoMemory,
+    _lock: FileLock
+}
+
+/// Lock file next to the project database, held from loading the file until
+/// the last save. Removed when dropped.
+struct FileLock {
+    path: PathBuf
+}
+
+// DaoFile-specific functions
+impl DaoFile {
+    /// Opens the project database at the given path, creating it if it doesn't exist
+    /// Fails with DatabaseLocked while another DaoFile has it open.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<DaoFile, Error> {
+        let lock = try!(FileLock::acquire(path.as_ref()));
+        let dao = if path.as_ref().exists() {
+            let contents = try!(utils::file_as_string(path.as_ref()));
+            let store: MemoryStore = try!(json::decode(&contents).map_err(Error::JsonDecode));
+            DaoFile {
+                path: path.as_ref().to_path_buf(),
+                memory: DaoMemory::from_store(store),
+                _lock: lock
+            }
+        } else {
+            let dao = DaoFile {
+                path: path.as_ref().to_path_buf(),
+                memory: DaoMemory::new(),
+                _lock: lock
+            };
+            try!(dao.save());
+            dao
+        };
+        Ok(dao)
+    }
+
+    /// Writes the current state to disk, unless a transaction is open.
+    /// The file is replaced atomically, so a crash mid-write leaves the
+    /// previous version intact.
+    pub fn save(&self) -> Result<(), Error> {
+        if self.memory.in_transaction() {
+            return Ok(());
+        }
+        let contents = try!(json::encode(&*self.memory.store.borrow()).map_err(Error::JsonEncode));
+        let tmp_path = PathBuf::from(format!("{}.tmp", self.path.display()));
+        let mut tmp_file = try!(File::create(&tmp_path).map_err(Error::Io));
+        try!(tmp_file.write_all(contents.as_bytes()).map_err(Error::Io));
+        try!(tmp_file.sync_all().map_err(Error::Io));
+        fs::rename(&tmp_path, &self.path).map_err(Error::Io)
+    }
+}
+
+impl FileLock {
+    /// Creates <db_path>.lock, holding the id of this process.
+    /// Fails if the lock file already exists.
+    fn acquire(db_path: &Path) -> Result<FileLock, Error> {
+        let path = PathBuf::from(format!("{}.lock", db_path.display()));
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists =>
+                return Err(Error::DatabaseLocked(path.display().to_string())),
+            Err(e) => return Err(Error::Io(e)),
+        };
+        let lock = FileLock { path: path };
+        try!(write!(file, "{}", process::id()).map_err(Error::Io));
+        Ok(lock)
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
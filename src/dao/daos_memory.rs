@@ -0,0 +1,138 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use project_types::{AuditEntry, Channel, Fixture, Layout, Permission, Project, Section, Sequence, User};
+
+
+/// Stateful, in-memory storage backend. Nothing is persisted; everything
+/// is dropped along with the dao. Useful for offline work and for tests.
+pub struct DaoMemory {
+    pub store: RefCell<MemoryStore>,
+    pub snapshots: RefCell<Vec<MemoryStore>> // One per open transaction
+}
+
+/// The "tables" held by DaoMemory. Ids are assigned like Postgres serials:
+/// starting at 1 and never reused.
+#[derive(Clone, Default, RustcEncodable, RustcDecodable)]
+pub struct MemoryStore {
+    pub audit_log: Option<Vec<AuditEntry>>, // None in files written before the audit log existed
+    pub challenges: Option<BTreeMap<String, u64>>, // Hex challenge to when it was issued. None in files written before challenges were issued
+    pub channels: BTreeMap<u32, Channel>,
+    pub data: BTreeMap<u32, BTreeMap<u32, Vec<u16>>>, // Keyed by seqid, then chanid
+    pub fixtures: BTreeMap<u32, Fixture>,
+    pub layouts: BTreeMap<u32, Layout>,
+    pub new_admin_open: Option<bool>, // None in files written before new-admin had to be opened, which counts as closed
+    pub permissions: BTreeMap<u32, Permission>,
+    pub projects: BTreeMap<String, Project>,
+    pub revoked_keys: Option<BTreeMap<String, u32>>, // Fingerprint to uid. None in files written before keys could be revoked
+    pub sections: Option<BTreeMap<u32, Section>>, // None in files written before sections were kept
+    pub sequences: BTreeMap<u32, Sequence>,
+    pub users: BTreeMap<u32, MemoryUser>,
+    last_chanid: u32,
+    last_fixid: u32,
+    last_layoutid: u32,
+    last_permid: u32,
+    last_secid: Option<u32>,
+    last_seqid: u32,
+    last_uid: u32,
+}
+
+/// A row of the users table. Files written before private keys were dropped
+/// also have a private_key here, which is ignored.
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+pub struct MemoryUser {
+    pub user: User,
+}
+
+// DaoMemory-specific functions
+impl DaoMemory {
+    /// Creates an empty dao, in which new_admin is open
+    pub fn new() -> DaoMemory {
+        let mut store = MemoryStore::default();
+        store.new_admin_open = Some(true);
+        DaoMemory {
+            store: RefCell::new(store),
+            snapshots: RefCell::new(vec![])
+        }
+    }
+
+    /// Creates a dao holding previously saved state
+    pub fn from_store(store: MemoryStore) -> DaoMemory {
+        DaoMemory {
+            store: RefCell::new(store),
+            snapshots: RefCell::new(vec![])
+        }
+    }
+
+    /// True while a transaction is open
+    pub fn in_transaction(&self) -> bool {
+        !self.snapshots.borrow().is_empty()
+    }
+}
+
+impl MemoryStore {
+    pub fn next_chanid(&mut self) -> u32 {
+        self.last_chanid += 1;
+        self.last_chanid
+    }
+
+    pub fn next_fixid(&mut self) -> u32 {
+        self.last_fixid += 1;
+        self.last_fixid
+    }
+
+    pub fn next_layoutid(&mut self) -> u32 {
+        self.last_layoutid += 1;
+        self.last_layoutid
+    }
+
+    pub fn next_permid(&mut self) -> u32 {
+        self.last_permid += 1;
+        self.last_permid
+    }
+
+    pub fn next_secid(&mut self) -> u32 {
+        let secid = self.last_secid.unwrap_or(0) + 1;
+        self.last_secid = Some(secid);
+        secid
+    }
+
+    pub fn next_seqid(&mut self) -> u32 {
+        self.last_seqid += 1;
+        self.last_seqid
+    }
+
+    pub fn next_uid(&mut self) -> u32 {
+        self.last_uid += 1;
+        self.last_uid
+    }
+
+    /// Equivalent of the get_internal_chan_id() database function: the id of the
+    /// first channel in the layout with the given internal channel number
+    pub fn get_internal_chan_id(&self, layout_id: u32, channel_internal: u32) -> Option<u32> {
+        let layout = match self.layouts.get(&layout_id) {
+            Some(layout) => layout,
+            None => return None,
+        };
+        for fixid in &layout.fixtures {
+            if let Some(fixture) = self.fixtures.get(fixid) {
+                for chanid in &fixture.channels {
+                    if let Some(channel) = self.channels.get(chanid) {
+                        if channel.channel_internal == channel_internal {
+                            return Some(channel.chanid);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// True if name contains name_filter, ignoring case (the list_* name filters)
+pub fn name_matches(name: &str, name_filter: Option<&str>) -> bool {
+    match name_filter {
+        Some(filter) => name.to_lowercase().contains(&filter.to_lowercase()),
+        None => true,
+    }
+}
@@ -1,36 +1,57 @@
-use postgres::{Connection, TlsMode};
+use postgres::Connection;
+use postgres::error::{Error as PostgresError, SqlState};
+use std::cell::Cell;
 
 use dao::ConnectionConfig;
+use dao::migrations;
 use error::Error;
 
 
 pub struct DaoPostgres {
-    pub conn: Connection
+    pub conn: Connection,
+    pub trans_depth: Cell<u32>
 }
 
 // DaoPostgres-specific functions
 impl DaoPostgres {
-    pub fn new() -> Result<DaoPostgres, Error> {
-        let conn = try!(get_connection());
+    /// Connects to the database, refusing to use a schema version this build doesn't understand
+    pub fn new(config: &ConnectionConfig) -> Result<DaoPostgres, Error> {
+        let dao = try!(DaoPostgres::new_unchecked(config));
+        try!(migrations::check_schema_version(&dao.conn));
+        Ok(dao)
+    }
+
+    /// Connects to the database without checking its schema version.
+    /// Only meant for setting up or migrating the schema.
+    pub fn new_unchecked(config: &ConnectionConfig) -> Result<DaoPostgres, Error> {
+        let conn = try!(config.connect());
         Ok(DaoPostgres {
-            conn: conn
+            conn: conn,
+            trans_depth: Cell::new(0)
         })
     }
-}
 
-/// Gets a new connection to the postgresql database
-fn get_connection() -> Result<Connection, Error> {
-    // Load connection info from file
-    // NEVER commit this file; it contains the password
-    let connection_config = try!(ConnectionConfig::load());
-    let password = connection_config.password;
-    let host = connection_config.host;
+    /// Creates the schema in an empty database. Returns the migration versions applied.
+    pub fn init_db(&self) -> Result<Vec<u32>, Error> {
+        migrations::init_db(&self.conn)
+    }
 
-    // Build connection string
-    let connection_string = format!("postgresql://proton:{}@{}/proton_cli", password, host);
+    /// Brings the schema up to date. Returns the migration versions applied.
+    pub fn migrate(&self) -> Result<Vec<u32>, Error> {
+        migrations::migrate(&self.conn)
+    }
 
-    // Connect
-    Connection::connect(connection_string, TlsMode::None)
-        .map_err(Error::PostgresConnection)
+    /// Deletes all data, keeping the schema
+    pub fn reset_db(&self) -> Result<(), Error> {
+        migrations::reset_db(&self.conn)
+    }
 }
 
+/// Reports a unique constraint violation as the given error, the way DaoMemory
+/// reports duplicates. Any other error stays a Postgres error.
+pub fn duplicate_or_postgres(err: PostgresError, duplicate: Error) -> Error {
+    match err {
+        PostgresError::Db(ref db_err) if db_err.code == SqlState::UniqueViolation => duplicate,
+        err => Error::Postgres(err),
+    }
+}
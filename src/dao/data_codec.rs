@@ -0,0 +1,130 @@
+//! Compact binary encoding for a channel's frame data.
+//!
+//! Frames are stored as runs of equal deltas: a run of `n` frames with delta `d`
+//! means each of the next `n` frames is the previous frame plus `d` (the frame
+//! before the first is 0). Holds and silence become a single run with delta 0 and
+//! linear fades a single run with a constant delta, so typical sequences shrink
+//! to a tiny fraction of an integer array.
+//!
+//! Layout: format version byte, varint frame count, then (varint run length,
+//! zigzag varint delta) pairs.
+
+use std::cmp;
+
+use error::Error;
+
+
+const FORMAT_VERSION: u8 = 1;
+
+/// Most frames any channel can hold: a sequence's length in ms fits a u32
+/// and its frames are at least 25 ms long. A few bytes of runs can declare
+/// any number of frames, so decoding refuses more than this.
+pub const MAX_FRAMES: u32 = ::std::u32::MAX / 25;
+
+/// Encodes frame data into its compact binary form
+pub fn encode_frames(frames: &[u16]) -> Vec<u8> {
+    let mut encoded = vec![FORMAT_VERSION];
+    write_varint(&mut encoded, frames.len() as u64);
+
+    let mut prev = 0i32;
+    let mut run_delta = 0i32;
+    let mut run_len = 0u64;
+    for frame in frames {
+        let delta = *frame as i32 - prev;
+        if run_len > 0 && delta != run_delta {
+            write_run(&mut encoded, run_len, run_delta);
+            run_len = 0;
+        }
+        run_delta = delta;
+        run_len += 1;
+        prev = *frame as i32;
+    }
+    if run_len > 0 {
+        write_run(&mut encoded, run_len, run_delta);
+    }
+    encoded
+}
+
+/// Decodes frame data created by encode_frames
+pub fn decode_frames(encoded: &[u8]) -> Result<Vec<u16>, Error> {
+    match encoded.first() {
+        Some(&FORMAT_VERSION) => {},
+        Some(version) => return Err(Error::CorruptChannelData(
+            format!("unknown format version {}", version))),
+        None => return Err(Error::CorruptChannelData("empty".to_string())),
+    }
+    let mut pos = 1;
+    let num_frames = try!(read_varint(encoded, &mut pos));
+    if num_frames > MAX_FRAMES as u64 {
+        return Err(Error::CorruptChannelData(format!("declares {} frames", num_frames)));
+    }
+    // Don't trust the declared count for the allocation
+    let mut frames = Vec::with_capacity(cmp::min(num_frames, 1 << 16) as usize);
+
+    let mut prev = 0i32;
+    while pos < encoded.len() {
+        let run_len = try!(read_varint(encoded, &mut pos));
+        let delta = unzigzag(try!(read_varint(encoded, &mut pos)));
+        if run_len > num_frames - frames.len() as u64 {
+            return Err(Error::CorruptChannelData("more frames than declared".to_string()));
+        }
+        for _ in 0..run_len {
+            let frame = prev as i64 + delta as i64;
+            if frame < 0 || frame > u16::max_value() as i64 {
+                return Err(Error::CorruptChannelData(format!("frame value {} out of range", frame)));
+            }
+            frames.push(frame as u16);
+            prev = frame as i32;
+        }
+    }
+
+    if frames.len() as u64 != num_frames {
+        return Err(Error::CorruptChannelData(
+            format!("expected {} frames, found {}", num_frames, frames.len())));
+    }
+    Ok(frames)
+}
+
+fn write_run(out: &mut Vec<u8>, run_len: u64, delta: i32) {
+    write_varint(out, run_len);
+    write_varint(out, zigzag(delta));
+}
+
+/// Maps signed to unsigned so small magnitudes stay small (0, -1, 1, -2, ...)
+fn zigzag(n: i32) -> u64 {
+    ((n << 1) ^ (n >> 31)) as u32 as u64
+}
+
+fn unzigzag(n: u64) -> i32 {
+    let n = n as u32;
+    ((n >> 1) as i32) ^ -((n & 1) as i32)
+}
+
+/// LEB128: 7 bits per byte, high bit set on all but the last byte
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn read_varint(encoded: &[u8], pos: &mut usize) -> Result<u64, Error> {
+    let mut n = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = match encoded.get(*pos) {
+            Some(byte) => *byte,
+            None => return Err(Error::CorruptChannelData("truncated".to_string())),
+        };
+        *pos += 1;
+        if shift > 63 {
+            return Err(Error::CorruptChannelData("varint too long".to_string()));
+        }
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(n);
+        }
+        shift += 7;
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use dao::{DataDao, DaoFile};
+use error::Error;
+use project_types::DataCell;
+
+
+impl DataDao for DaoFile {
+
+    fn new_data_default(
+        &self,
+        seqid: u32,
+        chan_ids: Vec<u32>,
+        default_data: Vec<u16>
+    ) -> Result<(), Error> {
+        try!(self.memory.new_data_default(seqid, chan_ids, default_data));
+        self.save()
+    }
+
+    fn new_data<'a>(
+        &'a self,
+        seqid: u32,
+        chanid: u32,
+        new_data: &'a Vec<u16>
+    ) -> Result<(), Error> {
+        try!(self.memory.new_data(seqid, chanid, new_data));
+        self.save()
+    }
+
+    fn get_data(&self, seqid: u32, chanid: u32) -> Result<Vec<u16>, Error> {
+        self.memory.get_data(seqid, chanid)
+    }
+
+    fn get_sequence_data(&self, seqid: u32) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+        self.memory.get_sequence_data(seqid)
+    }
+
+    fn get_data_range(
+        &self,
+        seqid: u32,
+        chan_ids: &[u32],
+        start_frame: u32,
+        end_frame: u32
+    ) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+        self.memory.get_data_range(seqid, chan_ids, start_frame, end_frame)
+    }
+
+    fn patch_data(&self, seqid: u32, cells: &[DataCell]) -> Result<(), Error> {
+        try!(self.memory.patch_data(seqid, cells));
+        self.save()
+    }
+
+    fn update_data<'a>(&'a self, seqid: u32, chanid: u32, new_data: &'a Vec<u16>) -> Result<(), Error> {
+        try!(self.memory.update_data(seqid, chanid, new_data));
+        self.save()
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use dao::{ChannelDao, DataDao, DaoMemory, SequenceDao, check_channel_data, check_channels_data, check_frame, check_frame_value, slice_frames};
+use error::Error;
+use dao::daos_memory::MemoryStore;
+use project_types::DataCell;
+
+
+impl DataDao for DaoMemory {
+
+    fn new_data_default(
+        &self,
+        seqid: u32,
+        chan_ids: Vec<u32>,
+        default_data: Vec<u16>
+    ) -> Result<(), Error> {
+        try!(check_channels_data(self, seqid, &chan_ids, &default_data));
+        let mut store = self.store.borrow_mut();
+        let seq_data = store.data.entry(seqid).or_insert_with(Default::default);
+        for chanid in chan_ids {
+            seq_data.insert(chanid, default_data.clone());
+        }
+        Ok(())
+    }
+
+    fn new_data<'a>(
+        &'a self,
+        seqid: u32,
+        chanid: u32,
+        new_data: &'a Vec<u16>
+    ) -> Result<(), Error> {
+        try!(check_channel_data(self, seqid, chanid, new_data));
+        let mut store = self.store.borrow_mut();
+        store.data.entry(seqid)
+            .or_insert_with(Default::default)
+            .insert(chanid, new_data.to_owned());
+        Ok(())
+    }
+
+    fn get_data(&self, seqid: u32, chanid: u32) -> Result<Vec<u16>, Error> {
+        let store = self.store.borrow();
+        stored_data(&store, seqid, chanid).map(|data| data.clone())
+    }
+
+    fn get_sequence_data(&self, seqid: u32) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+        // Channels come back sorted by DMX channel, like the Postgres query
+        let chan_ids = try!(self.get_channel_ids(seqid));
+        let store = self.store.borrow();
+        let mut seq_data = BTreeMap::new();
+        for chanid in chan_ids {
+            let chan_data = try!(stored_data(&store, seqid, chanid));
+            if let Some(channel) = store.channels.get(&chanid) {
+                seq_data.insert(channel.channel_dmx, chan_data.clone());
+            }
+        }
+        Ok(seq_data)
+    }
+
+    fn get_data_range(
+        &self,
+        seqid: u32,
+        chan_ids: &[u32],
+        start_frame: u32,
+        end_frame: u32
+    ) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+        let store = self.store.borrow();
+        let mut range_data = BTreeMap::new();
+        for chanid in chan_ids {
+            let data = try!(stored_data(&store, seqid, *chanid));
+            let frames = try!(slice_frames(data, seqid, *chanid, start_frame, end_frame));
+            range_data.insert(*chanid, frames);
+        }
+        Ok(range_data)
+    }
+
+    fn patch_data(&self, seqid: u32, cells: &[DataCell]) -> Result<(), Error> {
+        for cell in cells {
+            let channel = try!(self.get_channel(cell.chanid));
+            try!(check_frame_value(&channel, cell.frame, cell.value));
+        }
+        let mut store = self.store.borrow_mut();
+        let seq_data = match store.data.get_mut(&seqid) {
+            Some(seq_data) => seq_data,
+            None => return match cells.first() {
+                Some(cell) => Err(Error::ChannelDataNotFound(seqid, cell.chanid)),
+                None => Ok(()),
+            },
+        };
+        // Check every cell before changing anything
+        for cell in cells {
+            let data = try!(seq_data.get(&cell.chanid).ok_or(Error::ChannelDataNotFound(seqid, cell.chanid)));
+            try!(check_frame(data, seqid, cell));
+        }
+        for cell in cells {
+            if let Some(data) = seq_data.get_mut(&cell.chanid) {
+                data[cell.frame as usize] = cell.value;
+            }
+        }
+        Ok(())
+    }
+
+    fn update_data<'a>(&'a self, seqid: u32, chanid: u32, new_data: &'a Vec<u16>) -> Result<(), Error> {
+        try!(check_channel_data(self, seqid, chanid, new_data));
+        let mut store = self.store.borrow_mut();
+        // Like an UPDATE, a missing row is silently left alone
+        if let Some(data) = store.data.get_mut(&seqid).and_then(|seq_data| seq_data.get_mut(&chanid)) {
+            *data = new_data.to_owned();
+        }
+        Ok(())
+    }
+}
+
+/// A channel's stored data. Writes check it against the sequence's frame count,
+/// so a mismatch here means the caller changed the count and then read data
+/// stored for the old one; that's reported instead of returning stale frames.
+fn stored_data<'a>(store: &'a MemoryStore, seqid: u32, chanid: u32) -> Result<&'a Vec<u16>, Error> {
+    let data = try!(store.data.get(&seqid)
+        .and_then(|seq_data| seq_data.get(&chanid))
+        .ok_or(Error::ChannelDataNotFound(seqid, chanid)));
+    if let Some(sequence) = store.sequences.get(&seqid) {
+        if data.len() != sequence.num_frames as usize {
+            return Err(Error::InvalidFrameCount(seqid, chanid, data.len() as u32, sequence.num_frames));
+        }
+    }
+    Ok(data)
+}
@@ -1,5 +1,9 @@
-use dao::{DataDao, DaoPostgres};
+use std::collections::BTreeMap;
+
+use dao::{ChannelDao, DataDao, DaoPostgres, TransactionDao};
+use dao::{check_channel_data, check_channels_data, check_frame, check_frame_value, encode_frames, decode_frames, slice_frames};
 use error::Error;
+use project_types::DataCell;
 
 
 impl DataDao for DaoPostgres {
@@ -10,18 +14,17 @@ impl DataDao for DaoPostgres {
         chan_ids: Vec<u32>,
         default_data: Vec<u16>
     ) -> Result<(), Error> {
-        let statement = "INSERT INTO channel_data (chanid,seqid,data) VALUES ($1,$2,$3)";
+        try!(check_channels_data(self, seqid, &chan_ids, &default_data));
+        let statement = "INSERT INTO channel_data (chanid,seqid,frames) VALUES ($1,$2,$3)";
         let insert_stmt = try!(self.conn.prepare(statement).map_err(Error::Postgres));
-        let default_data_i32 = default_data.iter()
-            .map(|frame| *frame as i32)
-            .collect::<Vec<i32>>();
+        let default_frames = encode_frames(&default_data);
         for chanid in chan_ids {
             let _ = try!(
                 insert_stmt.execute(
                     &[
                         &(chanid as i32),
                         &(seqid as i32),
-                        &default_data_i32
+                        &default_frames
                     ])
                 .map_err(Error::Postgres));
         }
@@ -34,24 +37,23 @@ impl DataDao for DaoPostgres {
         chanid: u32,
         new_data: &'a Vec<u16>
     ) -> Result<(), Error> {
-        let statement = "INSERT INTO channel_data (chanid,seqid,data) VALUES ($1,$2,$3)";
-        let new_data_i32 = new_data.iter()
-            .map(|frame| *frame as i32)
-            .collect::<Vec<i32>>();
+        try!(check_channel_data(self, seqid, chanid, new_data));
+        let statement = "INSERT INTO channel_data (chanid,seqid,frames) VALUES ($1,$2,$3)";
+        let new_frames = encode_frames(new_data);
         let _ = try!(
             self.conn.execute(
                 statement,
                 &[
                     &(chanid as i32),
                     &(seqid as i32),
-                    &new_data_i32
+                    &new_frames
                 ])
             .map_err(Error::Postgres));
         Ok(())
     }
 
     fn get_data(&self, seqid: u32, chanid: u32) -> Result<Vec<u16>, Error> {
-        let query = "SELECT data FROM channel_data WHERE seqid = $1 AND chanid = $2";
+        let query = "SELECT frames FROM channel_data WHERE seqid = $1 AND chanid = $2";
         let results = try!(
             self.conn.query(query, &[&(seqid as i32), &(chanid as i32)])
             .map_err(Error::Postgres));
@@ -59,26 +61,128 @@ impl DataDao for DaoPostgres {
             0 => Err(Error::ChannelDataNotFound(seqid, chanid)),
             1 => {
                 let row = results.get(0);
-                let data: Vec<i32> = row.get(0);
-                let data_u16 = data.iter()
-                    .map(|frame_val| *frame_val as u16)
-                    .collect::<Vec<u16>>();
-                Ok(data_u16)
+                let frames: Vec<u8> = row.get(0);
+                decode_frames(&frames)
             },
             x => Err(Error::InvalidNumResults(x)),
         }
     }
 
-    fn update_data<'a>(&'a self, seqid: u32, chanid: u32, new_data: &'a Vec<u16>) -> Result<(), Error> {
-        let statement = "UPDATE channel_data SET data = $1 WHERE seqid = $2 AND chanid = $3";
-        let new_data_i32 = new_data.iter()
-            .map(|val_u16| *val_u16 as i32)
+    fn get_sequence_data(&self, seqid: u32) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+        // Left join, so a layout channel without data is an error instead of silence
+        let query = "SELECT c.chanid, c.channel_dmx, d.frames FROM sequences s \
+            INNER JOIN layouts l ON l.layoutid = s.layoutid \
+            INNER JOIN fixtures f ON f.fixid = ANY(l.fixtures) \
+            INNER JOIN channels c ON c.chanid = ANY(f.channels) \
+            LEFT JOIN channel_data d ON d.chanid = c.chanid AND d.seqid = s.seqid \
+            WHERE s.seqid = $1 \
+            ORDER BY c.channel_dmx";
+        let results = try!(
+            self.conn.query(query, &[&(seqid as i32)])
+            .map_err(Error::Postgres));
+        let mut seq_data = BTreeMap::new();
+        for row in &results {
+            let chanid: i32 = row.get(0);
+            let channel_dmx: i32 = row.get(1);
+            let frames: Option<Vec<u8>> = row.get(2);
+            let frames = try!(frames.ok_or(Error::ChannelDataNotFound(seqid, chanid as u32)));
+            seq_data.insert(channel_dmx as u32, try!(decode_frames(&frames)));
+        }
+        Ok(seq_data)
+    }
+
+    // Frames are stored encoded (see data_codec), so they can't be sliced in SQL.
+    // Instead only the channels asked for are fetched, in one query. Within a
+    // transaction they are read FOR UPDATE, so callers can compare and then write.
+    fn get_data_range(
+        &self,
+        seqid: u32,
+        chan_ids: &[u32],
+        start_frame: u32,
+        end_frame: u32
+    ) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+        let query = match self.trans_depth.get() {
+            0 => "SELECT chanid, frames FROM channel_data WHERE seqid = $1 AND chanid = ANY($2)",
+            _ => "SELECT chanid, frames FROM channel_data WHERE seqid = $1 AND chanid = ANY($2) FOR UPDATE",
+        };
+        let chan_ids_i32 = chan_ids.iter()
+            .map(|chanid| *chanid as i32)
             .collect::<Vec<i32>>();
+        let results = try!(
+            self.conn.query(query, &[&(seqid as i32), &chan_ids_i32])
+            .map_err(Error::Postgres));
+        let mut stored = BTreeMap::new();
+        for row in &results {
+            let chanid: i32 = row.get(0);
+            let frames: Vec<u8> = row.get(1);
+            stored.insert(chanid as u32, frames);
+        }
+
+        let mut range_data = BTreeMap::new();
+        for chanid in chan_ids {
+            let frames = try!(stored.get(chanid).ok_or(Error::ChannelDataNotFound(seqid, *chanid)));
+            let data = try!(decode_frames(frames));
+            range_data.insert(*chanid, try!(slice_frames(&data, seqid, *chanid, start_frame, end_frame)));
+        }
+        Ok(range_data)
+    }
+
+    fn patch_data(&self, seqid: u32, cells: &[DataCell]) -> Result<(), Error> {
+        let mut chan_ids = cells.iter()
+            .map(|cell| cell.chanid)
+            .collect::<Vec<u32>>();
+        chan_ids.sort();
+        chan_ids.dedup();
+
+        self.transaction(|| {
+            // Lock the rows so nobody changes them between reading and writing
+            let query = "SELECT chanid, frames FROM channel_data \
+                WHERE seqid = $1 AND chanid = ANY($2) FOR UPDATE";
+            let chan_ids_i32 = chan_ids.iter()
+                .map(|chanid| *chanid as i32)
+                .collect::<Vec<i32>>();
+            let results = try!(
+                self.conn.query(query, &[&(seqid as i32), &chan_ids_i32])
+                .map_err(Error::Postgres));
+            let mut seq_data = BTreeMap::new();
+            for row in &results {
+                let chanid: i32 = row.get(0);
+                let frames: Vec<u8> = row.get(1);
+                seq_data.insert(chanid as u32, try!(decode_frames(&frames)));
+            }
+
+            let mut channels = BTreeMap::new();
+            for chanid in &chan_ids {
+                channels.insert(*chanid, try!(self.get_channel(*chanid)));
+            }
+            for cell in cells {
+                let data = try!(seq_data.get_mut(&cell.chanid).ok_or(Error::ChannelDataNotFound(seqid, cell.chanid)));
+                try!(check_frame(data, seqid, cell));
+                try!(check_frame_value(&channels[&cell.chanid], cell.frame, cell.value));
+                data[cell.frame as usize] = cell.value;
+            }
+
+            // Only the patched frames changed, so the rest needn't be checked again
+            let statement = "UPDATE channel_data SET frames = $1 WHERE seqid = $2 AND chanid = $3";
+            let update_stmt = try!(self.conn.prepare(statement).map_err(Error::Postgres));
+            for (chanid, data) in &seq_data {
+                let _ = try!(
+                    update_stmt.execute(&[&encode_frames(data), &(seqid as i32), &(*chanid as i32)])
+                    .map_err(Error::Postgres));
+            }
+            Ok(())
+        })
+    }
+
+    fn update_data<'a>(&'a self, seqid: u32, chanid: u32, new_data: &'a Vec<u16>) -> Result<(), Error> {
+        try!(check_channel_data(self, seqid, chanid, new_data));
+        let statement = "UPDATE channel_data SET frames = $1 WHERE seqid = $2 AND chanid = $3";
+        let new_frames = encode_frames(new_data);
         let _ = try!(
             self.conn.execute(
                 statement,
                 &[
-                    &new_data_i32,
+                    &new_frames,
                     &(seqid as i32),
                     &(chanid as i32)
                 ])
@@ -0,0 +1,83 @@
+//! Channel data checks shared by every dao, for storing data and reading frame ranges
+
+use dao::{ChannelDao, SequenceDao};
+use error::Error;
+use project_types::{Channel, DataCell, Sequence};
+
+
+/// Checks that data for a sequence's channel has exactly one value per frame of
+/// the sequence, and that no value is above the channel's maximum
+pub fn check_channel_data<D: ChannelDao + SequenceDao>(
+    dao: &D,
+    seqid: u32,
+    chanid: u32,
+    data: &[u16]
+) -> Result<(), Error> {
+    let sequence = try!(dao.get_sequence(seqid));
+    let channel = try!(dao.get_channel(chanid));
+    check_data(&sequence, &channel, data)
+}
+
+/// Checks the same data for several of a sequence's channels, like
+/// check_channel_data. The sequence and its layout's channels are fetched once
+/// for the whole batch instead of once per channel.
+pub fn check_channels_data<D: ChannelDao + SequenceDao>(
+    dao: &D,
+    seqid: u32,
+    chan_ids: &[u32],
+    data: &[u16]
+) -> Result<(), Error> {
+    let sequence = try!(dao.get_sequence(seqid));
+    let channels = try!(dao.list_channels(sequence.layout_id));
+    for chanid in chan_ids {
+        match channels.iter().find(|channel| channel.chanid == *chanid) {
+            Some(channel) => try!(check_data(&sequence, channel, data)),
+            None => try!(check_data(&sequence, &try!(dao.get_channel(*chanid)), data)),
+        }
+    }
+    Ok(())
+}
+
+fn check_data(sequence: &Sequence, channel: &Channel, data: &[u16]) -> Result<(), Error> {
+    if data.len() != sequence.num_frames as usize {
+        return Err(Error::InvalidFrameCount(sequence.seqid, channel.chanid, data.len() as u32, sequence.num_frames));
+    }
+    for (frame, value) in data.iter().enumerate() {
+        try!(check_frame_value(channel, frame as u32, *value));
+    }
+    Ok(())
+}
+
+/// Checks that a value isn't above the channel's maximum
+pub fn check_frame_value(channel: &Channel, frame: u32, value: u16) -> Result<(), Error> {
+    if value > channel.max_value() {
+        return Err(Error::InvalidFrameValue(channel.chanid, frame, value, channel.max_value()));
+    }
+    Ok(())
+}
+
+/// Copies frames [start_frame, end_frame) out of a channel's data
+pub fn slice_frames(
+    data: &[u16],
+    seqid: u32,
+    chanid: u32,
+    start_frame: u32,
+    end_frame: u32
+) -> Result<Vec<u16>, Error> {
+    if start_frame > end_frame || end_frame as usize > data.len() {
+        return Err(Error::InvalidFrameRange(format!(
+            "frames {}-{} of channel {} of sequence {}, which has {} frames",
+            start_frame, end_frame, chanid, seqid, data.len())));
+    }
+    Ok(data[start_frame as usize..end_frame as usize].to_vec())
+}
+
+/// Checks that a patch cell's frame is within the channel's data
+pub fn check_frame(data: &[u16], seqid: u32, cell: &DataCell) -> Result<(), Error> {
+    if cell.frame as usize >= data.len() {
+        return Err(Error::InvalidFrameRange(format!(
+            "frame {} of channel {} of sequence {}, which has {} frames",
+            cell.frame, cell.chanid, seqid, data.len())));
+    }
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use project_types::Fixture;
+use error::Error;
+use dao::{FixtureDao, DaoFile};
+
+impl FixtureDao for DaoFile {
+
+    fn new_fixture(
+        &self, 
+        name: &str,
+        location: (i32, i32, i32),
+        rotation: (i32, i32, i32),
+        channels: Vec<u32>
+    ) -> Result<Fixture, Error> {
+        let fixture = try!(self.memory.new_fixture(name, location, rotation, channels));
+        try!(self.save());
+        Ok(fixture)
+    }
+
+    fn get_last_fixture(&self, name: &str) -> Result<Fixture, Error> {
+        self.memory.get_last_fixture(name)
+    }
+
+    fn get_fixture(&self, fixid: u32) -> Result<Fixture, Error> {
+        self.memory.get_fixture(fixid)
+    }
+
+    fn fixture_exists(&self, fixid: u32) -> Result<bool, Error> {
+        self.memory.fixture_exists(fixid)
+    }
+
+    fn get_num_channels(&self, fixid: u32) -> Result<u32, Error> {
+        self.memory.get_num_channels(fixid)
+    }
+
+    fn list_fixtures(&self, layoutid: u32) -> Result<Vec<Fixture>, Error> {
+        self.memory.list_fixtures(layoutid)
+    }
+}
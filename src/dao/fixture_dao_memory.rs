@@ -0,0 +1,70 @@
+use project_types::Fixture;
+use error::Error;
+use dao::{FixtureDao, DaoMemory};
+
+impl FixtureDao for DaoMemory {
+
+    fn new_fixture(
+        &self, 
+        name: &str,
+        location: (i32, i32, i32),
+        rotation: (i32, i32, i32),
+        channels: Vec<u32>
+    ) -> Result<Fixture, Error> {
+        let mut store = self.store.borrow_mut();
+        // Fixture names are unique, like in the Postgres schema
+        if store.fixtures.values().any(|fixture| fixture.name == name) {
+            return Err(Error::DuplicateFixture(name.to_owned()));
+        }
+        let fixid = store.next_fixid();
+        let fixture = Fixture {
+            fixid: fixid,
+            name: name.to_owned(),
+            location: location,
+            rotation: rotation,
+            channels: channels
+        };
+        store.fixtures.insert(fixid, fixture.clone());
+        Ok(fixture)
+    }
+
+    fn get_last_fixture(&self, name: &str) -> Result<Fixture, Error> {
+        let store = self.store.borrow();
+        store.fixtures.values()
+            .rev()
+            .find(|fixture| fixture.name == name)
+            .cloned()
+            .ok_or(Error::FixtureNotFound(0))
+    }
+
+    fn get_fixture(&self, fixid: u32) -> Result<Fixture, Error> {
+        let store = self.store.borrow();
+        store.fixtures.get(&fixid)
+            .cloned()
+            .ok_or(Error::FixtureNotFound(fixid))
+    }
+
+    fn fixture_exists(&self, fixid: u32) -> Result<bool, Error> {
+        let store = self.store.borrow();
+        Ok(store.fixtures.contains_key(&fixid))
+    }
+
+    fn get_num_channels(&self, fixid: u32) -> Result<u32, Error> {
+        let store = self.store.borrow();
+        store.fixtures.get(&fixid)
+            .map(|fixture| fixture.channels.len() as u32)
+            .ok_or(Error::FixtureNotFound(fixid))
+    }
+
+    fn list_fixtures(&self, layoutid: u32) -> Result<Vec<Fixture>, Error> {
+        let store = self.store.borrow();
+        let fixtures = match store.layouts.get(&layoutid) {
+            Some(layout) => layout.fixtures.iter()
+                .filter_map(|fixid| store.fixtures.get(fixid))
+                .cloned()
+                .collect::<Vec<Fixture>>(),
+            None => vec![],
+        };
+        Ok(fixtures)
+    }
+}
@@ -1,6 +1,7 @@
 use project_types::Fixture;
 use error::Error;
 use dao::{FixtureDao, DaoPostgres};
+use dao::daos_postgres::duplicate_or_postgres;
 
 impl FixtureDao for DaoPostgres {
 
@@ -12,12 +13,12 @@ impl FixtureDao for DaoPostgres {
         channels: Vec<u32>
     ) -> Result<Fixture, Error> {
         let statement = "INSERT INTO fixtures (name,location_x,location_y,location_z,\
-            rotation_a,rotation_b,rotation_c,channels) VALUES ($1,$2,$3,$4,$5,$6,$7,$8)";
+            rotation_a,rotation_b,rotation_c,channels) VALUES ($1,$2,$3,$4,$5,$6,$7,$8) RETURNING fixid";
         let channels_i32 = channels.iter()
             .map(|channel| *channel as i32)
             .collect::<Vec<i32>>();
-        let _ = try!(
-            self.conn.execute(
+        let results = try!(
+            self.conn.query(
                 statement,
                 &[
                     &name.to_owned(),
@@ -29,10 +30,16 @@ impl FixtureDao for DaoPostgres {
                     &rotation.2,
                     &channels_i32
                 ])
-            .map_err(Error::Postgres));
-
-        let fixture = try!(self.get_last_fixture(name));
-        Ok(fixture)
+            .map_err(|e| duplicate_or_postgres(e, Error::DuplicateFixture(name.to_owned()))));
+        let row = results.get(0);
+        let fixid: i32 = row.get(0);
+        Ok(Fixture {
+            fixid: fixid as u32,
+            name: name.to_owned(),
+            location: location,
+            rotation: rotation,
+            channels: channels
+        })
     }
 
     fn get_last_fixture(&self, name: &str) -> Result<Fixture, Error> {
@@ -68,9 +75,37 @@ impl FixtureDao for DaoPostgres {
         })
     }
 
-    #[allow(unused_variables)]
     fn get_fixture(&self, fixid: u32) -> Result<Fixture, Error> {
-        Err(Error::TodoErr)
+        let query = "SELECT name,location_x,location_y,location_z,rotation_a,rotation_b,\
+            rotation_c,channels FROM fixtures WHERE fixid = $1";
+        let results = try!(
+            self.conn.query(query, &[&(fixid as i32)])
+            .map_err(Error::Postgres));
+        match results.len() {
+            0 => Err(Error::FixtureNotFound(fixid)),
+            1 => {
+                let row = results.get(0);
+                let name: String = row.get(0);
+                let location_x: i32 = row.get(1);
+                let location_y: i32 = row.get(2);
+                let location_z: i32 = row.get(3);
+                let rotation_a: i32 = row.get(4);
+                let rotation_b: i32 = row.get(5);
+                let rotation_c: i32 = row.get(6);
+                let channels_i32: Vec<i32> = row.get(7);
+                let channels = channels_i32.iter()
+                    .map(|channel| *channel as u32)
+                    .collect::<Vec<u32>>();
+                Ok(Fixture {
+                    fixid: fixid,
+                    name: name,
+                    location: (location_x, location_y, location_z),
+                    rotation: (rotation_a, rotation_b, rotation_c),
+                    channels: channels
+                })
+            },
+            x => Err(Error::InvalidNumResults(x)),
+        }
     }
 
     fn fixture_exists(&self, fixid: u32) -> Result<bool, Error> {
@@ -96,4 +131,41 @@ impl FixtureDao for DaoPostgres {
             x => Err(Error::InvalidNumResults(x)),
         }
     }
+
+    fn list_fixtures(&self, layoutid: u32) -> Result<Vec<Fixture>, Error> {
+        let query = "SELECT f.fixid,f.name,f.location_x,f.location_y,f.location_z,f.rotation_a, \
+            f.rotation_b,f.rotation_c,f.channels \
+            FROM layouts l \
+            CROSS JOIN LATERAL unnest(l.fixtures) WITH ORDINALITY AS lf(fixid, idx) \
+            INNER JOIN fixtures f ON f.fixid = lf.fixid \
+            WHERE l.layoutid = $1 \
+            ORDER BY lf.idx";
+        let results = try!(
+            self.conn.query(query, &[&(layoutid as i32)])
+            .map_err(Error::Postgres));
+        let fixtures = results.iter()
+            .map(|row| {
+                let fixid: i32 = row.get(0);
+                let name: String = row.get(1);
+                let location_x: i32 = row.get(2);
+                let location_y: i32 = row.get(3);
+                let location_z: i32 = row.get(4);
+                let rotation_a: i32 = row.get(5);
+                let rotation_b: i32 = row.get(6);
+                let rotation_c: i32 = row.get(7);
+                let channels_i32: Vec<i32> = row.get(8);
+                let channels = channels_i32.iter()
+                    .map(|channel| *channel as u32)
+                    .collect::<Vec<u32>>();
+                Fixture {
+                    fixid: fixid as u32,
+                    name: name,
+                    location: (location_x, location_y, location_z),
+                    rotation: (rotation_a, rotation_b, rotation_c),
+                    channels: channels
+                }
+            })
+            .collect::<Vec<Fixture>>();
+        Ok(fixtures)
+    }
 }
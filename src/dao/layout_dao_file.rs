@@ -0,0 +1,44 @@
+use project_types::Layout;
+use error::Error;
+use dao::{LayoutDao, DaoFile};
+
+
+impl LayoutDao for DaoFile {
+
+    fn new_layout(&self, name: &str, fixtures: Vec<u32>) -> Result<Layout, Error> {
+        let layout = try!(self.memory.new_layout(name, fixtures));
+        try!(self.save());
+        Ok(layout)
+    }
+
+    fn patch_channel(
+        &self,
+        layoutid: u32,
+        channel_internal: u32,
+        channel_dmx: u32
+    ) -> Result<u64, Error> {
+        let rows_altered = try!(self.memory.patch_channel(layoutid, channel_internal, channel_dmx));
+        try!(self.save());
+        Ok(rows_altered)
+    }
+
+    fn get_last_layout(&self, name: &str) -> Result<Layout, Error> {
+        self.memory.get_last_layout(name)
+    }
+    
+    fn get_default_layout(&self) -> Result<Layout, Error> {
+        self.memory.get_default_layout()
+    }
+
+    fn get_layout(&self, layout_id: u32) -> Result<Layout, Error> {
+        self.memory.get_layout(layout_id)
+    }
+
+    fn layout_exists(&self, layout_id: u32) -> Result<bool, Error> {
+        self.memory.layout_exists(layout_id)
+    }
+
+    fn list_layouts(&self, name_filter: Option<&str>) -> Result<Vec<Layout>, Error> {
+        self.memory.list_layouts(name_filter)
+    }
+}
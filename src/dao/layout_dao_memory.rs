@@ -0,0 +1,86 @@
+use project_types::Layout;
+use error::Error;
+use dao::{LayoutDao, DaoMemory};
+use dao::daos_memory::name_matches;
+
+
+impl LayoutDao for DaoMemory {
+
+    fn new_layout(&self, name: &str, fixtures: Vec<u32>) -> Result<Layout, Error> {
+        let mut store = self.store.borrow_mut();
+        // Layout names are unique, like in the Postgres schema
+        if store.layouts.values().any(|layout| layout.name == name) {
+            return Err(Error::DuplicateLayout(name.to_owned()));
+        }
+        let layout_id = store.next_layoutid();
+        let layout = Layout {
+            layout_id: layout_id,
+            name: name.to_owned(),
+            fixtures: fixtures
+        };
+        store.layouts.insert(layout_id, layout.clone());
+        Ok(layout)
+    }
+
+    fn patch_channel(
+        &self,
+        layoutid: u32,
+        channel_internal: u32,
+        channel_dmx: u32
+    ) -> Result<u64, Error> {
+        let mut store = self.store.borrow_mut();
+        let chanid = match store.get_internal_chan_id(layoutid, channel_internal) {
+            Some(chanid) => chanid,
+            None => return Ok(0),
+        };
+        match store.channels.get_mut(&chanid) {
+            Some(channel) => {
+                channel.channel_dmx = channel_dmx;
+                Ok(1)
+            },
+            None => Ok(0),
+        }
+    }
+
+    fn get_last_layout(&self, name: &str) -> Result<Layout, Error> {
+        let store = self.store.borrow();
+        store.layouts.values()
+            .rev()
+            .find(|layout| layout.name == name)
+            .cloned()
+            .ok_or(Error::LayoutNotFound(0))
+    }
+    
+    fn get_default_layout(&self) -> Result<Layout, Error> {
+        let store = self.store.borrow();
+        let defaults = store.layouts.values()
+            .filter(|layout| layout.name == "default")
+            .collect::<Vec<&Layout>>();
+        match defaults.len() {
+            0 => Err(Error::LayoutNotFound(0)),
+            1 => Ok(defaults[0].clone()),
+            x => Err(Error::InvalidNumResults(x)),
+        }
+    }
+
+    fn get_layout(&self, layout_id: u32) -> Result<Layout, Error> {
+        let store = self.store.borrow();
+        store.layouts.get(&layout_id)
+            .cloned()
+            .ok_or(Error::LayoutNotFound(layout_id))
+    }
+
+    fn layout_exists(&self, layout_id: u32) -> Result<bool, Error> {
+        let store = self.store.borrow();
+        Ok(store.layouts.contains_key(&layout_id))
+    }
+
+    fn list_layouts(&self, name_filter: Option<&str>) -> Result<Vec<Layout>, Error> {
+        let store = self.store.borrow();
+        let layouts = store.layouts.values()
+            .filter(|layout| name_matches(&layout.name, name_filter))
+            .cloned()
+            .collect::<Vec<Layout>>();
+        Ok(layouts)
+    }
+}
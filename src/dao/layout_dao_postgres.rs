@@ -1,26 +1,31 @@
 use project_types::Layout;
 use error::Error;
 use dao::{LayoutDao, DaoPostgres};
+use dao::daos_postgres::duplicate_or_postgres;
 
 
 impl LayoutDao for DaoPostgres {
 
     fn new_layout(&self, name: &str, fixtures: Vec<u32>) -> Result<Layout, Error> {
-        let statement = "INSERT INTO layouts (name,fixtures) VALUES ($1,$2)";
+        let statement = "INSERT INTO layouts (name,fixtures) VALUES ($1,$2) RETURNING layoutid";
         let fixtures_i32 = fixtures.iter()
             .map(|fixture| *fixture as i32)
             .collect::<Vec<i32>>();
-        let _ = try!(
-            self.conn.execute(
+        let results = try!(
+            self.conn.query(
                 statement,
                 &[
                     &name.to_owned(),
                     &fixtures_i32
                 ])
-            .map_err(Error::Postgres));
-
-        let layout = try!(self.get_last_layout(name));
-        Ok(layout)
+            .map_err(|e| duplicate_or_postgres(e, Error::DuplicateLayout(name.to_owned()))));
+        let row = results.get(0);
+        let layout_id: i32 = row.get(0);
+        Ok(Layout {
+            layout_id: layout_id as u32,
+            name: name.to_owned(),
+            fixtures: fixtures
+        })
     }
 
     fn patch_channel(
@@ -118,4 +123,26 @@ impl LayoutDao for DaoPostgres {
             .map_err(Error::Postgres));
         Ok(results.len() > 0)
     }
+
+    fn list_layouts(&self, name_filter: Option<&str>) -> Result<Vec<Layout>, Error> {
+        let query = "SELECT layoutid, name, fixtures FROM layouts \
+            WHERE $1::text IS NULL OR strpos(lower(name), lower($1)) > 0 \
+            ORDER BY layoutid";
+        let results = try!(
+            self.conn.query(query, &[&name_filter.map(|filter| filter.to_owned())])
+            .map_err(Error::Postgres));
+        let layouts = results.iter()
+            .map(|row| {
+                let layout_id: i32 = row.get(0);
+                let name: String = row.get(1);
+                let fixtures: Vec<i32> = row.get(2);
+                Layout {
+                    layout_id: layout_id as u32,
+                    name: name,
+                    fixtures: fixtures.iter().map(|x| x.to_owned() as u32).collect()
+                }
+            })
+            .collect::<Vec<Layout>>();
+        Ok(layouts)
+    }
 }
@@ -0,0 +1,307 @@
+//! Versioned PostgreSQL schema. Each migration is applied at most once and
+//! recorded in the schema_version table.
+
+use postgres::Connection;
+use postgres::transaction::Transaction;
+
+use dao::encode_frames;
+use error::Error;
+use keys;
+
+
+/// One step of the schema's history
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub steps: &'static [MigrationStep],
+}
+
+/// Part of a migration. Rust steps are for data conversions that can't be
+/// expressed in SQL; all steps of a migration share one transaction.
+pub enum MigrationStep {
+    Sql(&'static str),
+    Rust(fn(&Transaction) -> Result<(), Error>),
+}
+
+/// All migrations, oldest first. Never edit a migration that has been released;
+/// add a new one instead.
+pub const MIGRATIONS: &'static [Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema",
+        steps: &[MigrationStep::Sql(include_str!("migrations/0001_initial_schema.sql"))],
+    },
+    Migration {
+        version: 2,
+        description: "Store channel data as encoded bytea",
+        steps: &[
+            MigrationStep::Sql("ALTER TABLE channel_data ADD COLUMN frames bytea"),
+            MigrationStep::Rust(encode_channel_data),
+            MigrationStep::Sql("ALTER TABLE channel_data ALTER COLUMN frames SET NOT NULL; \
+                ALTER TABLE channel_data DROP COLUMN data"),
+        ],
+    },
+    Migration {
+        version: 3,
+        description: "Stop storing users' private keys",
+        steps: &[MigrationStep::Sql("ALTER TABLE users DROP COLUMN private_key")],
+    },
+    Migration {
+        version: 4,
+        description: "Look users up by public key fingerprint",
+        steps: &[
+            MigrationStep::Sql("ALTER TABLE users ALTER COLUMN public_key TYPE text; \
+                ALTER TABLE users ADD COLUMN fingerprint character varying(64)"),
+            MigrationStep::Rust(fingerprint_users),
+            MigrationStep::Sql("ALTER TABLE users ALTER COLUMN fingerprint SET NOT NULL; \
+                CREATE INDEX users_fingerprint_idx ON users (fingerprint)"),
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "Scope permissions to projects",
+        steps: &[MigrationStep::Sql("ALTER TABLE permissions ADD COLUMN project character varying(40); \
+            UPDATE permissions SET project = projects.name \
+                FROM users, projects \
+                WHERE permissions.uid = users.uid AND users.name = 'root_' || projects.name \
+                AND permissions.permission = 'Administrate' AND permissions.project IS NULL")],
+    },
+    Migration {
+        version: 6,
+        description: "Add audit log",
+        steps: &[MigrationStep::Sql("CREATE TABLE audit_log ( \
+                auditid serial PRIMARY KEY, \
+                uid integer NOT NULL, \
+                logged_at bigint NOT NULL, \
+                command character varying(40) NOT NULL, \
+                project character varying(40), \
+                entity character varying(20) NOT NULL, \
+                target character varying(64) NOT NULL, \
+                before text, \
+                after text); \
+            CREATE INDEX audit_log_logged_at_idx ON audit_log (logged_at)")],
+    },
+    Migration {
+        version: 7,
+        description: "Add revoked keys",
+        steps: &[MigrationStep::Sql("CREATE TABLE revoked_keys ( \
+                fingerprint character varying(64) PRIMARY KEY, \
+                uid integer NOT NULL, \
+                revoked_at bigint NOT NULL)")],
+    },
+    Migration {
+        version: 8,
+        description: "Only open new-admin on new databases",
+        steps: &[MigrationStep::Sql("CREATE TABLE new_admin_open (opened_at bigint NOT NULL)")],
+    },
+    Migration {
+        version: 9,
+        description: "Issue single-use challenges",
+        steps: &[MigrationStep::Sql("CREATE TABLE challenges ( \
+                challenge bytea PRIMARY KEY, \
+                issued_at bigint NOT NULL)")],
+    },
+];
+
+/// The schema version this build of proton_cli understands
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|migration| migration.version).unwrap_or(0)
+}
+
+/// Gets the database's schema version. None if the database has never been
+/// initialized by proton_cli.
+pub fn get_schema_version(conn: &Connection) -> Result<Option<u32>, Error> {
+    if !try!(table_exists(conn, "schema_version")) {
+        return Ok(None);
+    }
+    let query = "SELECT MAX(version) FROM schema_version";
+    let results = try!(
+        conn.query(query, &[])
+        .map_err(Error::Postgres));
+    let row = results.get(0);
+    let version: Option<i32> = row.get(0);
+    Ok(version.map(|v| v as u32))
+}
+
+/// Makes sure the database's schema is exactly the one this build understands
+pub fn check_schema_version(conn: &Connection) -> Result<(), Error> {
+    let latest = latest_version();
+    match try!(get_schema_version(conn)) {
+        Some(version) if version == latest => Ok(()),
+        Some(version) => Err(Error::UnsupportedSchemaVersion(version, latest)),
+        None => Err(Error::UnsupportedSchemaVersion(0, latest)),
+    }
+}
+
+/// Describes the migration with the given version
+pub fn migration_description(version: u32) -> Option<&'static str> {
+    MIGRATIONS.iter()
+        .find(|migration| migration.version == version)
+        .map(|migration| migration.description)
+}
+
+/// Creates the full schema in an empty database. Returns the versions applied.
+pub fn init_db(conn: &Connection) -> Result<Vec<u32>, Error> {
+    if try!(get_schema_version(conn)).is_some() || try!(has_legacy_schema(conn)) {
+        return Err(Error::DatabaseAlreadyInitialized);
+    }
+    let applied = try!(apply_pending(conn, 0, true));
+    try!(set_new_admin_open(conn, true));
+    Ok(applied)
+}
+
+/// Applies every migration newer than the database's schema version.
+/// Databases restored from the old pg_dump backups are adopted as version 1.
+/// Returns the versions that were applied.
+pub fn migrate(conn: &Connection) -> Result<Vec<u32>, Error> {
+    let (current, adopt) = match try!(get_schema_version(conn)) {
+        Some(version) => (version, false),
+        None => {
+            if !try!(has_legacy_schema(conn)) {
+                return Err(Error::UnsupportedSchemaVersion(0, latest_version()));
+            }
+            (1, true)
+        }
+    };
+    if current > latest_version() {
+        return Err(Error::UnsupportedSchemaVersion(current, latest_version()));
+    }
+    apply_pending(conn, current, adopt)
+}
+
+/// Deletes all data and restarts every id sequence, keeping the schema
+pub fn reset_db(conn: &Connection) -> Result<(), Error> {
+    try!(check_schema_version(conn));
+    let query = "SELECT tablename FROM pg_tables \
+        WHERE schemaname = current_schema() AND tablename <> 'schema_version'";
+    let results = try!(
+        conn.query(query, &[])
+        .map_err(Error::Postgres));
+    let tables = results.iter()
+        .map(|row| {
+            let table: String = row.get(0);
+            table
+        })
+        .collect::<Vec<String>>();
+    if tables.is_empty() {
+        return Ok(());
+    }
+    let statement = format!("TRUNCATE {} RESTART IDENTITY", tables.join(", "));
+    try!(conn.batch_execute(&statement).map_err(Error::Postgres));
+    // Without any users, the database is as good as new
+    set_new_admin_open(conn, true)
+}
+
+/// Opens or closes new-admin. It's open while new_admin_open has a row.
+pub fn set_new_admin_open(conn: &Connection, open: bool) -> Result<(), Error> {
+    let _ = try!(conn.execute("DELETE FROM new_admin_open", &[]).map_err(Error::Postgres));
+    if open {
+        let statement = "INSERT INTO new_admin_open (opened_at) VALUES (extract(epoch FROM now())::bigint)";
+        let _ = try!(conn.execute(statement, &[]).map_err(Error::Postgres));
+    }
+    Ok(())
+}
+
+/// Applies the migrations after the given version, each in its own transaction.
+/// With new_history, schema_version is created (recording current, if any) in
+/// the first migration's transaction, so a failed first run can simply be re-run.
+fn apply_pending(conn: &Connection, current: u32, new_history: bool) -> Result<Vec<u32>, Error> {
+    let mut applied = vec![];
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > current) {
+        let trans = try!(conn.transaction().map_err(Error::Postgres));
+        if new_history && applied.is_empty() {
+            try!(create_version_table(&trans));
+            if current > 0 {
+                try!(record_version(&trans, current));
+            }
+        }
+        for step in migration.steps {
+            match *step {
+                MigrationStep::Sql(sql) => try!(trans.batch_execute(sql).map_err(Error::Postgres)),
+                MigrationStep::Rust(convert) => try!(convert(&trans)),
+            }
+        }
+        try!(record_version(&trans, migration.version));
+        try!(trans.commit().map_err(Error::Postgres));
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+/// Migration 2: fills channel_data.frames from the old integer[] column.
+/// Rows are converted in batches so large tables aren't loaded all at once.
+fn encode_channel_data(trans: &Transaction) -> Result<(), Error> {
+    let query = "SELECT dataid, data FROM channel_data WHERE dataid > $1 ORDER BY dataid LIMIT 100";
+    let statement = "UPDATE channel_data SET frames = $1 WHERE dataid = $2";
+    let update_stmt = try!(trans.prepare(statement).map_err(Error::Postgres));
+    let mut last_dataid = 0i32;
+    loop {
+        let results = try!(
+            trans.query(query, &[&last_dataid])
+            .map_err(Error::Postgres));
+        if results.len() == 0 {
+            return Ok(());
+        }
+        for row in &results {
+            let dataid: i32 = row.get(0);
+            let data: Vec<i32> = row.get(1);
+            let data_u16 = data.iter()
+                .map(|frame_val| *frame_val as u16)
+                .collect::<Vec<u16>>();
+            let _ = try!(
+                update_stmt.execute(&[&encode_frames(&data_u16), &dataid])
+                .map_err(Error::Postgres));
+            last_dataid = dataid;
+        }
+    }
+}
+
+/// Migration 4: fills users.fingerprint from each user's public key.
+/// Fails if a stored key can't be parsed, since that user could never sign in.
+fn fingerprint_users(trans: &Transaction) -> Result<(), Error> {
+    let results = try!(
+        trans.query("SELECT uid, public_key FROM users", &[])
+        .map_err(Error::Postgres));
+    let statement = "UPDATE users SET fingerprint = $1 WHERE uid = $2";
+    let update_stmt = try!(trans.prepare(statement).map_err(Error::Postgres));
+    for row in &results {
+        let uid: i32 = row.get(0);
+        let public_key: String = row.get(1);
+        let fingerprint = try!(keys::fingerprint(&public_key));
+        let _ = try!(
+            update_stmt.execute(&[&fingerprint, &uid])
+            .map_err(Error::Postgres));
+    }
+    Ok(())
+}
+
+fn create_version_table(trans: &Transaction) -> Result<(), Error> {
+    trans.batch_execute("CREATE TABLE schema_version (\
+            version integer PRIMARY KEY, \
+            applied_at timestamp NOT NULL DEFAULT now())")
+        .map_err(Error::Postgres)
+}
+
+fn record_version(trans: &Transaction, version: u32) -> Result<(), Error> {
+    let _ = try!(trans.execute(
+        "INSERT INTO schema_version (version) VALUES ($1)",
+        &[&(version as i32)])
+        .map_err(Error::Postgres));
+    Ok(())
+}
+
+/// True if the tables from the pre-migration pg_dump backups are present
+fn has_legacy_schema(conn: &Connection) -> Result<bool, Error> {
+    table_exists(conn, "channels")
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool, Error> {
+    let query = "SELECT EXISTS (SELECT 1 FROM pg_tables \
+        WHERE schemaname = current_schema() AND tablename = $1)";
+    let results = try!(
+        conn.query(query, &[&table.to_owned()])
+        .map_err(Error::Postgres));
+    let row = results.get(0);
+    let exists: bool = row.get(0);
+    Ok(exists)
+}
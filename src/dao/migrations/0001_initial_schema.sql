@@ -0,0 +1,113 @@
+-- Initial schema (matches the stable_1130 backup)
+
+CREATE TABLE channels (
+    chanid serial PRIMARY KEY,
+    name character varying(40),
+    primary_num integer,
+    secondary_num integer,
+    color character varying(16) NOT NULL,
+    channel_internal integer NOT NULL,
+    channel_dmx integer NOT NULL,
+    location_x integer,
+    location_y integer,
+    location_z integer,
+    rotation_a integer,
+    rotation_b integer,
+    rotation_c integer,
+    CONSTRAINT pos_channel_dmx CHECK (channel_dmx > 0),
+    CONSTRAINT pos_channel_internal CHECK (channel_internal > 0)
+);
+
+CREATE TABLE fixtures (
+    fixid serial PRIMARY KEY,
+    name character varying(40) NOT NULL,
+    location_x integer NOT NULL,
+    location_y integer NOT NULL,
+    location_z integer NOT NULL,
+    rotation_a integer NOT NULL,
+    rotation_b integer NOT NULL,
+    rotation_c integer NOT NULL,
+    channels integer[] NOT NULL,
+    CONSTRAINT channels_not_empty CHECK (array_length(channels, 1) > 0),
+    CONSTRAINT name_unique UNIQUE (name)
+);
+
+CREATE TABLE layouts (
+    layoutid serial PRIMARY KEY,
+    name character varying(64) NOT NULL,
+    fixtures integer[] NOT NULL,
+    CONSTRAINT fixtures_not_empty CHECK (array_length(fixtures, 1) > 0),
+    CONSTRAINT layout_name_unique UNIQUE (name)
+);
+
+CREATE TABLE sequences (
+    seqid serial PRIMARY KEY,
+    name character varying(64) NOT NULL,
+    music_file_name character varying(64) NOT NULL,
+    music_dur_sec integer NOT NULL,
+    frame_dur_ms integer NOT NULL,
+    num_frames integer NOT NULL,
+    layoutid integer NOT NULL,
+    CONSTRAINT pos_frame_dur CHECK (frame_dur_ms > 0),
+    CONSTRAINT pos_layout_id CHECK (layoutid > 0),
+    CONSTRAINT pos_music_dur CHECK (music_dur_sec > 0),
+    CONSTRAINT pos_num_frames CHECK (num_frames > 0)
+);
+
+CREATE TABLE channel_data (
+    dataid serial PRIMARY KEY,
+    chanid integer NOT NULL,
+    seqid integer NOT NULL,
+    data integer[] NOT NULL,
+    CONSTRAINT pos_chanid CHECK (chanid >= 0),
+    CONSTRAINT pos_seqid CHECK (seqid >= 0)
+);
+
+CREATE TABLE projects (
+    name character varying(40) PRIMARY KEY,
+    playlist integer[] NOT NULL,
+    layoutid integer NOT NULL,
+    CONSTRAINT pos_layout_id CHECK (layoutid >= 0)
+);
+
+CREATE TABLE sections (
+    secid serial PRIMARY KEY,
+    seqid integer NOT NULL,
+    t_start integer NOT NULL,
+    t_end integer NOT NULL,
+    fixtures integer[] NOT NULL,
+    CONSTRAINT fixtures_not_empty CHECK (array_length(fixtures, 1) > 0),
+    CONSTRAINT pos_seq_id CHECK (seqid >= 0),
+    CONSTRAINT pos_t_start CHECK (t_start >= 0),
+    CONSTRAINT start_after_end CHECK (t_end >= t_start)
+);
+
+CREATE TABLE users (
+    uid serial PRIMARY KEY,
+    name character varying(64) NOT NULL,
+    public_key character varying(1720) NOT NULL,
+    private_key character varying(1720) NOT NULL,
+    CONSTRAINT unique_name UNIQUE (name),
+    CONSTRAINT users_name_public_key_key UNIQUE (name, public_key)
+);
+
+CREATE TABLE permissions (
+    permid serial PRIMARY KEY,
+    uid integer NOT NULL,
+    seqid integer,
+    secid integer,
+    permission character varying(16) NOT NULL,
+    CONSTRAINT pos_secid CHECK (secid >= 0),
+    CONSTRAINT pos_seqid CHECK (seqid >= 0),
+    CONSTRAINT pos_uid CHECK (uid >= 0)
+);
+
+-- Used by LayoutDao::patch_channel. DaoMemory mirrors this in MemoryStore::get_internal_chan_id.
+CREATE FUNCTION get_internal_chan_id(lid integer, chan_internal integer) RETURNS integer
+    LANGUAGE sql STABLE
+    AS $$
+SELECT chanid FROM layouts l
+    INNER JOIN fixtures f ON f.fixid = ANY(l.fixtures)
+    INNER JOIN channels c ON c.chanid = ANY(f.channels)
+    WHERE l.layoutid = lid AND c.channel_internal = chan_internal
+$$;
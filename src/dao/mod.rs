@@ -4,6 +4,7 @@ mod daos;
 
 pub use self::daos::ProtonDao;
 
+pub use self::daos::AuditDao;
 pub use self::daos::ChannelDao;
 pub use self::daos::DataDao;
 pub use self::daos::FixtureDao;
@@ -12,17 +13,39 @@ pub use self::daos::PermissionDao;
 pub use self::daos::ProjectDao;
 pub use self::daos::SectionDao;
 pub use self::daos::SequenceDao;
+pub use self::daos::TransactionDao;
 pub use self::daos::UserDao;
 
 // Postgres implementations
 mod daos_postgres;
 pub use self::daos_postgres::DaoPostgres;
 
+// In-memory implementation
+mod daos_memory;
+pub use self::daos_memory::DaoMemory;
+
+// Single-file implementation
+mod daos_file;
+pub use self::daos_file::DaoFile;
+
 // Connection configuration
 mod connection_config;
-use self::connection_config::ConnectionConfig;
+pub use self::connection_config::{ConnectionConfig, SslMode};
+
+// Postgres schema migrations
+mod migrations;
+pub use self::migrations::migration_description;
+
+// Compact channel data encoding
+mod data_codec;
+pub use self::data_codec::{encode_frames, decode_frames, MAX_FRAMES};
+
+// Channel data checks shared by all implementations
+mod data_validation;
+pub use self::data_validation::{check_channel_data, check_channels_data, check_frame, check_frame_value, slice_frames};
 
 // Load postgres implementations to show that ProtonDao is satisfied
+mod audit_dao_postgres;
 mod channel_dao_postgres;
 mod data_dao_postgres;
 mod fixture_dao_postgres;
@@ -31,7 +54,40 @@ mod permission_dao_postgres;
 mod project_dao_postgres;
 mod section_dao_postgres;
 mod sequence_dao_postgres;
+mod transaction_dao_postgres;
 mod user_dao_postgres;
 
 // Make DaoPostgres conform to the ProtonDao interface (follow all Daos)
 impl ProtonDao for DaoPostgres {}
+
+// Load in-memory implementations to show that ProtonDao is satisfied
+mod audit_dao_memory;
+mod channel_dao_memory;
+mod data_dao_memory;
+mod fixture_dao_memory;
+mod layout_dao_memory;
+mod permission_dao_memory;
+mod project_dao_memory;
+mod section_dao_memory;
+mod sequence_dao_memory;
+mod transaction_dao_memory;
+mod user_dao_memory;
+
+// Make DaoMemory conform to the ProtonDao interface (follow all Daos)
+impl ProtonDao for DaoMemory {}
+
+// Load single-file implementations to show that ProtonDao is satisfied
+mod audit_dao_file;
+mod channel_dao_file;
+mod data_dao_file;
+mod fixture_dao_file;
+mod layout_dao_file;
+mod permission_dao_file;
+mod project_dao_file;
+mod section_dao_file;
+mod sequence_dao_file;
+mod transaction_dao_file;
+mod user_dao_file;
+
+// Make DaoFile conform to the ProtonDao interface (follow all Daos)
+impl ProtonDao for DaoFile {}
@@ -0,0 +1,41 @@
+use project_types::{Permission, PermissionEnum};
+use dao::{PermissionDao, DaoFile};
+use error::Error;
+
+
+impl PermissionDao for DaoFile {
+
+    fn add_initial_permission(&self, root_uid: u32, proj_name: &str) -> Result<(), Error> {
+        try!(self.memory.add_initial_permission(root_uid, proj_name));
+        self.save()
+    }
+
+    fn add_permission(
+        &self,
+        uid: u32,
+        project: Option<&str>,
+        permission: &PermissionEnum
+    ) -> Result<Permission, Error> {
+        let new_permission = try!(self.memory.add_permission(uid, project, permission));
+        try!(self.save());
+        Ok(new_permission)
+    }
+
+    fn revoke_permission(
+        &self,
+        uid: u32,
+        project: Option<&str>,
+        permission: &PermissionEnum
+    ) -> Result<(), Error> {
+        try!(self.memory.revoke_permission(uid, project, permission));
+        self.save()
+    }
+
+    fn get_all_permissions(&self, uid: u32) -> Result<Vec<Permission>, Error> {
+        self.memory.get_all_permissions(uid)
+    }
+
+    fn get_permission(&self, permid: u32) -> Result<Permission, Error> {
+        self.memory.get_permission(permid)
+    }
+}
@@ -0,0 +1,72 @@
+use project_types::{Permission, PermissionEnum};
+use dao::{PermissionDao, DaoMemory};
+use error::Error;
+
+
+impl PermissionDao for DaoMemory {
+
+    fn add_initial_permission(&self, root_uid: u32, proj_name: &str) -> Result<(), Error> {
+        let _ = try!(self.add_permission(root_uid, Some(proj_name), &PermissionEnum::Administrate));
+        Ok(())
+    }
+
+    fn add_permission(
+        &self,
+        uid: u32,
+        project: Option<&str>,
+        permission: &PermissionEnum
+    ) -> Result<Permission, Error> {
+        let mut store = self.store.borrow_mut();
+        let permid = store.next_permid();
+        let new_permission = Permission {
+            permid: permid,
+            uid: uid,
+            project: project.map(|name| name.to_owned()),
+            seqid: permission.seqid(),
+            secid: permission.secid(),
+            permission: permission.clone()
+        };
+        store.permissions.insert(permid, new_permission.clone());
+        Ok(new_permission)
+    }
+
+    fn revoke_permission(
+        &self,
+        uid: u32,
+        project: Option<&str>,
+        permission: &PermissionEnum
+    ) -> Result<(), Error> {
+        let mut store = self.store.borrow_mut();
+        let permids = store.permissions.values()
+            .filter(|p| {
+                p.uid == uid &&
+                p.project.as_ref().map(|name| name.as_str()) == project &&
+                p.permission == *permission
+            })
+            .map(|p| p.permid)
+            .collect::<Vec<u32>>();
+        if permids.is_empty() {
+            return Err(Error::PermissionNotHeld(uid, format!("{:?}", permission)));
+        }
+        for permid in permids {
+            store.permissions.remove(&permid);
+        }
+        Ok(())
+    }
+
+    fn get_all_permissions(&self, uid: u32) -> Result<Vec<Permission>, Error> {
+        let store = self.store.borrow();
+        let permissions = store.permissions.values()
+            .filter(|permission| permission.uid == uid)
+            .cloned()
+            .collect::<Vec<Permission>>();
+        Ok(permissions)
+    }
+
+    fn get_permission(&self, permid: u32) -> Result<Permission, Error> {
+        let store = self.store.borrow();
+        store.permissions.get(&permid)
+            .cloned()
+            .ok_or(Error::PermissionNotFound(permid))
+    }
+}
@@ -1,39 +1,86 @@
-use project_types::{self, Permission};
+use project_types::{self, Permission, PermissionEnum};
 use dao::{PermissionDao, DaoPostgres};
 use error::Error;
 
 
 impl PermissionDao for DaoPostgres {
 
-    fn add_initial_permission(&self, root_uid: u32) -> Result<(), Error> {
-        let statement = "INSERT INTO permissions (uid, seqid, secid, permission) VALUES ($1, $2, $3, $4)";
-        let seqid = None::<i32>;
-        let secid = None::<i32>;
-        let permission = "Administrate"; // Has to match up to permission_enum
-        let _ = try!(
-            self.conn.execute(statement, &[&(root_uid as i32), &seqid, &secid, &permission.to_owned()])
-            .map_err(Error::Postgres));
+    fn add_initial_permission(&self, root_uid: u32, proj_name: &str) -> Result<(), Error> {
+        let _ = try!(self.add_permission(root_uid, Some(proj_name), &PermissionEnum::Administrate));
         Ok(())
     }
 
+    fn add_permission(
+        &self,
+        uid: u32,
+        project: Option<&str>,
+        permission: &PermissionEnum
+    ) -> Result<Permission, Error> {
+        let statement = "INSERT INTO permissions (uid, project, seqid, secid, permission) \
+            VALUES ($1, $2, $3, $4, $5) RETURNING permid";
+        let project = project.map(|name| name.to_owned());
+        let seqid = permission.seqid().map(|seqid| seqid as i32);
+        let secid = permission.secid().map(|secid| secid as i32);
+        let results = try!(
+            self.conn.query(
+                statement,
+                &[&(uid as i32), &project, &seqid, &secid, &permission.name().to_owned()])
+            .map_err(Error::Postgres));
+        let row = results.get(0);
+        let permid: i32 = row.get(0);
+        Ok(Permission {
+            permid: permid as u32,
+            uid: uid,
+            project: project,
+            seqid: permission.seqid(),
+            secid: permission.secid(),
+            permission: permission.clone()
+        })
+    }
+
+    fn revoke_permission(
+        &self,
+        uid: u32,
+        project: Option<&str>,
+        permission: &PermissionEnum
+    ) -> Result<(), Error> {
+        let statement = "DELETE FROM permissions WHERE uid = $1 AND permission = $2 \
+            AND project IS NOT DISTINCT FROM $3 \
+            AND seqid IS NOT DISTINCT FROM $4 AND secid IS NOT DISTINCT FROM $5";
+        let project = project.map(|name| name.to_owned());
+        let seqid = permission.seqid().map(|seqid| seqid as i32);
+        let secid = permission.secid().map(|secid| secid as i32);
+        let num_deleted = try!(
+            self.conn.execute(
+                statement,
+                &[&(uid as i32), &permission.name().to_owned(), &project, &seqid, &secid])
+            .map_err(Error::Postgres));
+        match num_deleted {
+            0 => Err(Error::PermissionNotHeld(uid, format!("{:?}", permission))),
+            _ => Ok(()),
+        }
+    }
+
     fn get_all_permissions(&self, uid: u32) -> Result<Vec<Permission>, Error> {
-        let query = "SELECT permid, seqid, secid, permission FROM permissions WHERE uid = $1";
+        let query = "SELECT permid, project, seqid, secid, permission FROM permissions WHERE uid = $1";
         let results = try!(
             self.conn.query(query, &[&(uid as i32)])
             .map_err(Error::Postgres));
         let mut permissions = vec![];
         for row in &results {
             let permid: i32 = row.get(0);
-            let seqid: Option<i32> = row.get(1);
-            let secid: Option<i32> = row.get(2);
+            let project: Option<String> = row.get(1);
+            let seqid: Option<i32> = row.get(2);
+            let secid: Option<i32> = row.get(3);
             let seq = seqid.map(|s| s as u32);
             let sec = secid.map(|s| s as u32);
-            let perm_string: String = row.get(3);
+            let perm_string: String = row.get(4);
             let perm_enum = try!(
                 project_types::get_permission_enum(&perm_string, seq, sec));
             let permission = Permission {
                 permid: permid as u32,
                 uid: uid,
+                project: project,
                 seqid: seq,
                 secid: sec,
                 permission: perm_enum
@@ -44,8 +91,34 @@ impl PermissionDao for DaoPostgres {
         Ok(permissions)
     }
 
-    #[allow(unused_variables)]
     fn get_permission(&self, permid: u32) -> Result<Permission, Error> {
-        Err(Error::TodoErr)
+        let query = "SELECT uid, project, seqid, secid, permission FROM permissions WHERE permid = $1";
+        let results = try!(
+            self.conn.query(query, &[&(permid as i32)])
+            .map_err(Error::Postgres));
+        match results.len() {
+            0 => Err(Error::PermissionNotFound(permid)),
+            1 => {
+                let row = results.get(0);
+                let uid: i32 = row.get(0);
+                let project: Option<String> = row.get(1);
+                let seqid: Option<i32> = row.get(2);
+                let secid: Option<i32> = row.get(3);
+                let seq = seqid.map(|s| s as u32);
+                let sec = secid.map(|s| s as u32);
+                let perm_string: String = row.get(4);
+                let perm_enum = try!(
+                    project_types::get_permission_enum(&perm_string, seq, sec));
+                Ok(Permission {
+                    permid: permid,
+                    uid: uid as u32,
+                    project: project,
+                    seqid: seq,
+                    secid: sec,
+                    permission: perm_enum
+                })
+            },
+            x => Err(Error::InvalidNumResults(x)),
+        }
     }
 }
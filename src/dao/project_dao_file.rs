@@ -0,0 +1,24 @@
+use project_types::Project;
+use error::Error;
+use dao::{ProjectDao, DaoFile};
+
+impl ProjectDao for DaoFile {
+    fn new_project(&self, name: &str, layout_id: u32) -> Result<Project, Error> {
+        let project = try!(self.memory.new_project(name, layout_id));
+        try!(self.save());
+        Ok(project)
+    }
+
+    fn get_project(&self, name: &str) -> Result<Project, Error> {
+        self.memory.get_project(name)
+    }
+
+    fn update_project(&self, new_project: Project) -> Result<(), Error> {
+        try!(self.memory.update_project(new_project));
+        self.save()
+    }
+
+    fn list_projects(&self, name_filter: Option<&str>) -> Result<Vec<Project>, Error> {
+        self.memory.list_projects(name_filter)
+    }
+}
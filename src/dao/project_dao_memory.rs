@@ -0,0 +1,45 @@
+use project_types::Project;
+use error::Error;
+use dao::{ProjectDao, DaoMemory};
+use dao::daos_memory::name_matches;
+
+impl ProjectDao for DaoMemory {
+    fn new_project(&self, name: &str, layout_id: u32) -> Result<Project, Error> {
+        let mut store = self.store.borrow_mut();
+        // Project name is unique
+        if store.projects.contains_key(name) {
+            return Err(Error::DuplicateProject(name.to_owned()));
+        }
+        let project = Project {
+            name: name.to_owned(),
+            playlist: Vec::new(),
+            layout_id: layout_id
+        };
+        store.projects.insert(name.to_owned(), project.clone());
+        Ok(project)
+    }
+
+    fn get_project(&self, name: &str) -> Result<Project, Error> {
+        let store = self.store.borrow();
+        store.projects.get(name)
+            .cloned()
+            .ok_or(Error::ProjectNotFound(name.to_owned()))
+    }
+
+    fn update_project(&self, new_project: Project) -> Result<(), Error> {
+        let mut store = self.store.borrow_mut();
+        if let Some(project) = store.projects.get_mut(&new_project.name) {
+            *project = new_project;
+        }
+        Ok(())
+    }
+
+    fn list_projects(&self, name_filter: Option<&str>) -> Result<Vec<Project>, Error> {
+        let store = self.store.borrow();
+        let projects = store.projects.values()
+            .filter(|project| name_matches(&project.name, name_filter))
+            .cloned()
+            .collect::<Vec<Project>>();
+        Ok(projects)
+    }
+}
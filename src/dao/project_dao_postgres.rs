@@ -1,6 +1,7 @@
 use project_types::Project;
 use error::Error;
 use dao::{ProjectDao, DaoPostgres};
+use dao::daos_postgres::duplicate_or_postgres;
 
 impl ProjectDao for DaoPostgres {
     fn new_project(&self, name: &str, layout_id: u32) -> Result<Project, Error> {
@@ -15,7 +16,7 @@ impl ProjectDao for DaoPostgres {
                     &playlist,
                     &lid
                 ])
-            .map_err(Error::Postgres));
+            .map_err(|e| duplicate_or_postgres(e, Error::DuplicateProject(name.to_owned()))));
 
         // Project name is unique
         self.get_project(name)
@@ -56,4 +57,26 @@ impl ProjectDao for DaoPostgres {
             .map_err(Error::Postgres));
         Ok(())
     }
+
+    fn list_projects(&self, name_filter: Option<&str>) -> Result<Vec<Project>, Error> {
+        let query = "SELECT name, playlist, layoutid FROM projects \
+            WHERE $1::text IS NULL OR strpos(lower(name), lower($1)) > 0 \
+            ORDER BY name";
+        let results = try!(
+            self.conn.query(query, &[&name_filter.map(|filter| filter.to_owned())])
+            .map_err(Error::Postgres));
+        let projects = results.iter()
+            .map(|row| {
+                let name: String = row.get(0);
+                let playlist_i32: Vec<i32> = row.get(1);
+                let layout_id: i32 = row.get(2);
+                Project {
+                    name: name,
+                    playlist: playlist_i32.iter().map(|seqid| *seqid as u32).collect(),
+                    layout_id: layout_id as u32
+                }
+            })
+            .collect::<Vec<Project>>();
+        Ok(projects)
+    }
 }
@@ -0,0 +1,26 @@
+use project_types::Section;
+use dao::{SectionDao, DaoFile};
+use error::Error;
+
+
+impl SectionDao for DaoFile {
+
+    fn new_section(&self, section: &Section) -> Result<Section, Error> {
+        let section = try!(self.memory.new_section(section));
+        try!(self.save());
+        Ok(section)
+    }
+
+    fn get_section(&self, secid: u32) -> Result<Section, Error> {
+        self.memory.get_section(secid)
+    }
+
+    fn list_sections(&self, seqid: u32) -> Result<Vec<Section>, Error> {
+        self.memory.list_sections(seqid)
+    }
+
+    fn delete_section(&self, secid: u32) -> Result<(), Error> {
+        try!(self.memory.delete_section(secid));
+        self.save()
+    }
+}
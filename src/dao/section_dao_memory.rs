@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+use project_types::Section;
+use dao::{SectionDao, DaoMemory};
+use error::Error;
+
+
+impl SectionDao for DaoMemory {
+
+    fn new_section(&self, section: &Section) -> Result<Section, Error> {
+        let mut store = self.store.borrow_mut();
+        let mut new_section = section.clone();
+        new_section.secid = store.next_secid();
+        if store.sections.is_none() {
+            store.sections = Some(BTreeMap::new());
+        }
+        store.sections.as_mut().unwrap().insert(new_section.secid, new_section.clone());
+        Ok(new_section)
+    }
+
+    fn get_section(&self, secid: u32) -> Result<Section, Error> {
+        let store = self.store.borrow();
+        store.sections.as_ref()
+            .and_then(|sections| sections.get(&secid))
+            .cloned()
+            .ok_or(Error::SectionNotFound(secid))
+    }
+
+    fn list_sections(&self, seqid: u32) -> Result<Vec<Section>, Error> {
+        let store = self.store.borrow();
+        let mut sections = store.sections.iter()
+            .flat_map(|sections| sections.values())
+            .filter(|section| section.seqid == seqid)
+            .cloned()
+            .collect::<Vec<Section>>();
+        sections.sort_by_key(|section| (section.t_start, section.secid));
+        Ok(sections)
+    }
+
+    fn delete_section(&self, secid: u32) -> Result<(), Error> {
+        let mut store = self.store.borrow_mut();
+        if store.sections.as_mut().and_then(|sections| sections.remove(&secid)).is_none() {
+            return Err(Error::SectionNotFound(secid));
+        }
+        let permids = store.permissions.values()
+            .filter(|permission| permission.secid == Some(secid))
+            .map(|permission| permission.permid)
+            .collect::<Vec<u32>>();
+        for permid in permids {
+            store.permissions.remove(&permid);
+        }
+        Ok(())
+    }
+}
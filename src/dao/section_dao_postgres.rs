@@ -1,3 +1,5 @@
+use postgres::rows::Row;
+
 use project_types::Section;
 use dao::{SectionDao, DaoPostgres};
 use error::Error;
@@ -5,8 +7,87 @@ use error::Error;
 
 impl SectionDao for DaoPostgres {
 
-    #[allow(unused_variables)]
+    fn new_section(&self, section: &Section) -> Result<Section, Error> {
+        let statement = "INSERT INTO sections (seqid, t_start, t_end, fixtures) \
+            VALUES ($1, $2, $3, $4) RETURNING secid";
+        let fixtures = section.fixtures.iter()
+            .map(|fixid| *fixid as i32)
+            .collect::<Vec<i32>>();
+        let results = try!(
+            self.conn.query(
+                statement,
+                &[
+                    &(section.seqid as i32),
+                    &(section.t_start as i32),
+                    &(section.t_end as i32),
+                    &fixtures
+                ])
+            .map_err(Error::Postgres));
+        let row = results.get(0);
+        let secid: i32 = row.get(0);
+        let mut new_section = section.clone();
+        new_section.secid = secid as u32;
+        Ok(new_section)
+    }
+
     fn get_section(&self, secid: u32) -> Result<Section, Error> {
-        Err(Error::TodoErr)
+        let query = "SELECT secid, seqid, t_start, t_end, fixtures FROM sections WHERE secid = $1";
+        let results = try!(
+            self.conn.query(query, &[&(secid as i32)])
+            .map_err(Error::Postgres));
+        match results.len() {
+            0 => Err(Error::SectionNotFound(secid)),
+            1 => Ok(section_from_row(&results.get(0))),
+            x => Err(Error::InvalidNumResults(x)),
+        }
+    }
+
+    // Within a transaction the sequence row is locked first, so callers can check
+    // for overlaps and then insert without another insert slipping in between.
+    fn list_sections(&self, seqid: u32) -> Result<Vec<Section>, Error> {
+        if self.trans_depth.get() > 0 {
+            let _ = try!(
+                self.conn.query("SELECT seqid FROM sequences WHERE seqid = $1 FOR UPDATE", &[&(seqid as i32)])
+                .map_err(Error::Postgres));
+        }
+        let query = "SELECT secid, seqid, t_start, t_end, fixtures FROM sections \
+            WHERE seqid = $1 ORDER BY t_start, secid";
+        let results = try!(
+            self.conn.query(query, &[&(seqid as i32)])
+            .map_err(Error::Postgres));
+        let sections = results.iter()
+            .map(|row| section_from_row(&row))
+            .collect::<Vec<Section>>();
+        Ok(sections)
+    }
+
+    fn delete_section(&self, secid: u32) -> Result<(), Error> {
+        let secid_i32 = secid as i32;
+        let _ = try!(
+            self.conn.execute("DELETE FROM permissions WHERE secid = $1", &[&secid_i32])
+            .map_err(Error::Postgres));
+        let num_deleted = try!(
+            self.conn.execute("DELETE FROM sections WHERE secid = $1", &[&secid_i32])
+            .map_err(Error::Postgres));
+        match num_deleted {
+            0 => Err(Error::SectionNotFound(secid)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reads a row of (secid, seqid, t_start, t_end, fixtures)
+fn section_from_row(row: &Row) -> Section {
+    let secid: i32 = row.get(0);
+    let seqid: i32 = row.get(1);
+    let t_start: i32 = row.get(2);
+    let t_end: i32 = row.get(3);
+    let fixtures: Vec<i32> = row.get(4);
+    Section {
+        secid: secid as u32,
+        t_start: t_start as u32,
+        t_end: t_end as u32,
+        seqid: seqid as u32,
+        fixtures: fixtures.iter().map(|fixid| *fixid as u32).collect()
     }
 }
@@ -0,0 +1,50 @@
+use dao::{SequenceDao, DaoFile};
+use error::Error;
+use project_types::{Sequence, SequenceDeletion};
+
+
+impl SequenceDao for DaoFile {
+
+    fn get_channel_ids(&self, seqid: u32) -> Result<Vec<u32>, Error> {
+        self.memory.get_channel_ids(seqid)
+    }
+
+    fn get_sequence(&self, seqid: u32) -> Result<Sequence, Error> {
+        self.memory.get_sequence(seqid)
+    }
+
+    fn get_last_sequence(&self, name: &str) -> Result<Sequence, Error> {
+        self.memory.get_last_sequence(name)
+    }
+
+    fn new_sequence(&self, sequence: &Sequence) -> Result<Sequence, Error> {
+        let sequence = try!(self.memory.new_sequence(sequence));
+        try!(self.save());
+        Ok(sequence)
+    }
+
+    fn sequence_exists(&self, seqid: u32) -> Result<bool, Error> {
+        self.memory.sequence_exists(seqid)
+    }
+
+    fn set_layout(&self, seqid: u32, layout_id: u32) -> Result<(), Error> {
+        try!(self.memory.set_layout(seqid, layout_id));
+        self.save()
+    }
+
+    fn set_frames(&self, seqid: u32, frame_duration_ms: u32, num_frames: u32) -> Result<(), Error> {
+        try!(self.memory.set_frames(seqid, frame_duration_ms, num_frames));
+        self.save()
+    }
+
+    fn list_sequences(&self, name_filter: Option<&str>) -> Result<Vec<Sequence>, Error> {
+        self.memory.list_sequences(name_filter)
+    }
+
+
+    fn delete_sequence(&self, seqid: u32) -> Result<SequenceDeletion, Error> {
+        let deletion = try!(self.memory.delete_sequence(seqid));
+        try!(self.save());
+        Ok(deletion)
+    }
+}
@@ -0,0 +1,129 @@
+use dao::{SequenceDao, DaoMemory};
+use dao::daos_memory::name_matches;
+use error::Error;
+use project_types::{Channel, Sequence, SequenceDeletion};
+
+
+impl SequenceDao for DaoMemory {
+
+    fn get_channel_ids(&self, seqid: u32) -> Result<Vec<u32>, Error> {
+        let store = self.store.borrow();
+        let layout = match store.sequences.get(&seqid)
+            .and_then(|sequence| store.layouts.get(&sequence.layout_id)) {
+            Some(layout) => layout,
+            None => return Ok(vec![]),
+        };
+        let mut channels = layout.fixtures.iter()
+            .filter_map(|fixid| store.fixtures.get(fixid))
+            .flat_map(|fixture| fixture.channels.iter())
+            .filter_map(|chanid| store.channels.get(chanid))
+            .collect::<Vec<&Channel>>();
+        channels.sort_by_key(|channel| channel.channel_dmx);
+        let chan_ids = channels.iter()
+            .map(|channel| channel.chanid)
+            .collect::<Vec<u32>>();
+        Ok(chan_ids)
+    }
+
+    fn get_sequence(&self, seqid: u32) -> Result<Sequence, Error> {
+        let store = self.store.borrow();
+        store.sequences.get(&seqid)
+            .cloned()
+            .ok_or(Error::SequenceNotFound(seqid))
+    }
+
+    fn get_last_sequence(&self, name: &str) -> Result<Sequence, Error> {
+        let store = self.store.borrow();
+        store.sequences.values()
+            .rev()
+            .find(|sequence| sequence.name == name)
+            .cloned()
+            .ok_or(Error::SequenceNotFound(0))
+    }
+
+    fn new_sequence(&self, sequence: &Sequence) -> Result<Sequence, Error> {
+        let mut store = self.store.borrow_mut();
+        let mut new_sequence = sequence.clone();
+        new_sequence.seqid = store.next_seqid();
+        store.sequences.insert(new_sequence.seqid, new_sequence.clone());
+        Ok(new_sequence)
+    }
+
+    fn sequence_exists(&self, seqid: u32) -> Result<bool, Error> {
+        let store = self.store.borrow();
+        Ok(store.sequences.contains_key(&seqid))
+    }
+
+    fn set_layout(&self, seqid: u32, layout_id: u32) -> Result<(), Error> {
+        let mut store = self.store.borrow_mut();
+        if let Some(sequence) = store.sequences.get_mut(&seqid) {
+            sequence.layout_id = layout_id;
+        }
+        Ok(())
+    }
+
+    fn set_frames(&self, seqid: u32, frame_duration_ms: u32, num_frames: u32) -> Result<(), Error> {
+        let mut store = self.store.borrow_mut();
+        if let Some(sequence) = store.sequences.get_mut(&seqid) {
+            sequence.frame_duration_ms = frame_duration_ms;
+            sequence.num_frames = num_frames;
+        }
+        Ok(())
+    }
+
+    fn list_sequences(&self, name_filter: Option<&str>) -> Result<Vec<Sequence>, Error> {
+        let store = self.store.borrow();
+        let sequences = store.sequences.values()
+            .filter(|sequence| name_matches(&sequence.name, name_filter))
+            .cloned()
+            .collect::<Vec<Sequence>>();
+        Ok(sequences)
+    }
+
+
+    fn delete_sequence(&self, seqid: u32) -> Result<SequenceDeletion, Error> {
+        let mut store = self.store.borrow_mut();
+        let sequence = match store.sequences.remove(&seqid) {
+            Some(sequence) => sequence,
+            None => return Err(Error::SequenceNotFound(seqid)),
+        };
+
+        let mut projects = vec![];
+        for project in store.projects.values_mut() {
+            if project.playlist.contains(&seqid) {
+                project.playlist.retain(|playlist_seqid| *playlist_seqid != seqid);
+                projects.push(project.name.to_owned());
+            }
+        }
+
+        let channel_data = store.data.remove(&seqid)
+            .map_or(0, |sequence_data| sequence_data.len() as u64);
+
+        let secids = store.sections.iter()
+            .flat_map(|sections| sections.values())
+            .filter(|section| section.seqid == seqid)
+            .map(|section| section.secid)
+            .collect::<Vec<u32>>();
+        if let Some(sections) = store.sections.as_mut() {
+            for secid in &secids {
+                sections.remove(secid);
+            }
+        }
+
+        let permids = store.permissions.values()
+            .filter(|permission| permission.seqid == Some(seqid))
+            .map(|permission| permission.permid)
+            .collect::<Vec<u32>>();
+        for permid in &permids {
+            store.permissions.remove(permid);
+        }
+
+        Ok(SequenceDeletion {
+            sequence: sequence,
+            projects: projects,
+            channel_data: channel_data,
+            sections: secids.len() as u64,
+            permissions: permids.len() as u64
+        })
+    }
+}
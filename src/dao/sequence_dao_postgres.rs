@@ -1,6 +1,6 @@
 use dao::{SequenceDao, DaoPostgres};
 use error::Error;
-use project_types::Sequence;
+use project_types::{Sequence, SequenceDeletion};
 
 
 impl SequenceDao for DaoPostgres {
@@ -85,13 +85,13 @@ impl SequenceDao for DaoPostgres {
 
     fn new_sequence(&self, sequence: &Sequence) -> Result<Sequence, Error> {
         let statement = "INSERT INTO sequences (name,music_file_name,music_dur_sec,\
-            frame_dur_ms,num_frames,layoutid) VALUES ($1,$2,$3,$4,$5,$6)";
+            frame_dur_ms,num_frames,layoutid) VALUES ($1,$2,$3,$4,$5,$6) RETURNING seqid";
         let music_dur = sequence.music_duration_sec as i32;
         let frame_dur = sequence.frame_duration_ms as i32;
         let num_frames = sequence.num_frames as i32;
         let layout_id = sequence.layout_id as i32;
-        let _ = try!(
-            self.conn.execute(
+        let results = try!(
+            self.conn.query(
                 statement,
                 &[
                     &sequence.name.to_owned(),
@@ -102,8 +102,11 @@ impl SequenceDao for DaoPostgres {
                     &layout_id
                 ])
             .map_err(Error::Postgres));
-        let sequence = try!(self.get_last_sequence(&sequence.name));
-        Ok(sequence)
+        let row = results.get(0);
+        let seqid: i32 = row.get(0);
+        let mut new_sequence = sequence.clone();
+        new_sequence.seqid = seqid as u32;
+        Ok(new_sequence)
     }
 
     fn sequence_exists(&self, seqid: u32) -> Result<bool, Error> {
@@ -126,4 +129,86 @@ impl SequenceDao for DaoPostgres {
             .map_err(Error::Postgres));
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn set_frames(&self, seqid: u32, frame_duration_ms: u32, num_frames: u32) -> Result<(), Error> {
+        let statement = "UPDATE sequences SET frame_dur_ms = $1, num_frames = $2 WHERE seqid = $3";
+        let _ = try!(
+            self.conn.execute(
+                statement,
+                &[
+                    &(frame_duration_ms as i32),
+                    &(num_frames as i32),
+                    &(seqid as i32)
+                ])
+            .map_err(Error::Postgres));
+        Ok(())
+    }
+
+    fn list_sequences(&self, name_filter: Option<&str>) -> Result<Vec<Sequence>, Error> {
+        let query = "SELECT seqid,name,music_file_name,music_dur_sec,frame_dur_ms,num_frames,layoutid \
+            FROM sequences \
+            WHERE $1::text IS NULL OR strpos(lower(name), lower($1)) > 0 \
+            ORDER BY seqid";
+        let results = try!(
+            self.conn.query(query, &[&name_filter.map(|filter| filter.to_owned())])
+            .map_err(Error::Postgres));
+        let sequences = results.iter()
+            .map(|row| {
+                let seqid: i32 = row.get(0);
+                let name: String = row.get(1);
+                let music_file_name: String = row.get(2);
+                let music_dur_sec: i32 = row.get(3);
+                let frame_dur_ms: i32 = row.get(4);
+                let num_frames: i32 = row.get(5);
+                let layout_id: i32 = row.get(6);
+                Sequence {
+                    seqid: seqid as u32,
+                    name: name,
+                    music_file_name: music_file_name,
+                    music_duration_sec: music_dur_sec as u32,
+                    frame_duration_ms: frame_dur_ms as u32,
+                    num_frames: num_frames as u32,
+                    layout_id: layout_id as u32
+                }
+            })
+            .collect::<Vec<Sequence>>();
+        Ok(sequences)
+    }
+
+
+    fn delete_sequence(&self, seqid: u32) -> Result<SequenceDeletion, Error> {
+        let sequence = try!(self.get_sequence(seqid));
+        let seqid_i32 = seqid as i32;
+
+        let statement = "UPDATE projects SET playlist = array_remove(playlist, $1) \
+            WHERE $1 = ANY(playlist) RETURNING name";
+        let results = try!(
+            self.conn.query(statement, &[&seqid_i32])
+            .map_err(Error::Postgres));
+        let mut projects = results.iter()
+            .map(|row| row.get(0))
+            .collect::<Vec<String>>();
+        projects.sort();
+
+        let channel_data = try!(
+            self.conn.execute("DELETE FROM channel_data WHERE seqid = $1", &[&seqid_i32])
+            .map_err(Error::Postgres));
+        let sections = try!(
+            self.conn.execute("DELETE FROM sections WHERE seqid = $1", &[&seqid_i32])
+            .map_err(Error::Postgres));
+        let permissions = try!(
+            self.conn.execute("DELETE FROM permissions WHERE seqid = $1", &[&seqid_i32])
+            .map_err(Error::Postgres));
+        let _ = try!(
+            self.conn.execute("DELETE FROM sequences WHERE seqid = $1", &[&seqid_i32])
+            .map_err(Error::Postgres));
+
+        Ok(SequenceDeletion {
+            sequence: sequence,
+            projects: projects,
+            channel_data: channel_data,
+            sections: sections,
+            permissions: permissions
+        })
+    }
+}
@@ -0,0 +1,20 @@
+use dao::{TransactionDao, DaoFile};
+use error::Error;
+
+
+// Changes made in a transaction stay in memory until the outermost one commits
+impl TransactionDao for DaoFile {
+
+    fn begin_transaction(&self) -> Result<(), Error> {
+        self.memory.begin_transaction()
+    }
+
+    fn commit_transaction(&self) -> Result<(), Error> {
+        try!(self.memory.commit_transaction());
+        self.save()
+    }
+
+    fn rollback_transaction(&self) -> Result<(), Error> {
+        self.memory.rollback_transaction()
+    }
+}
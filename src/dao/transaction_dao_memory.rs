@@ -0,0 +1,30 @@
+use dao::{TransactionDao, DaoMemory};
+use error::Error;
+
+
+// Each open transaction keeps a snapshot of the store to restore on rollback
+impl TransactionDao for DaoMemory {
+
+    fn begin_transaction(&self) -> Result<(), Error> {
+        let snapshot = self.store.borrow().clone();
+        self.snapshots.borrow_mut().push(snapshot);
+        Ok(())
+    }
+
+    fn commit_transaction(&self) -> Result<(), Error> {
+        match self.snapshots.borrow_mut().pop() {
+            Some(_) => Ok(()),
+            None => Err(Error::NoTransaction),
+        }
+    }
+
+    fn rollback_transaction(&self) -> Result<(), Error> {
+        match self.snapshots.borrow_mut().pop() {
+            Some(snapshot) => {
+                *self.store.borrow_mut() = snapshot;
+                Ok(())
+            },
+            None => Err(Error::NoTransaction),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use dao::{TransactionDao, DaoPostgres};
+use error::Error;
+
+
+// Transactions are issued as plain SQL on the dao's connection, so every other
+// dao call made while one is open runs inside it. Nested transactions are savepoints.
+impl TransactionDao for DaoPostgres {
+
+    fn begin_transaction(&self) -> Result<(), Error> {
+        let depth = self.trans_depth.get();
+        let statement = match depth {
+            0 => "BEGIN".to_owned(),
+            _ => format!("SAVEPOINT proton_sp_{}", depth),
+        };
+        try!(self.conn.batch_execute(&statement).map_err(Error::Postgres));
+        self.trans_depth.set(depth + 1);
+        Ok(())
+    }
+
+    fn commit_transaction(&self) -> Result<(), Error> {
+        let depth = self.trans_depth.get();
+        let statement = match depth {
+            0 => return Err(Error::NoTransaction),
+            1 => "COMMIT".to_owned(),
+            _ => format!("RELEASE SAVEPOINT proton_sp_{}", depth - 1),
+        };
+        self.trans_depth.set(depth - 1);
+        self.conn.batch_execute(&statement).map_err(Error::Postgres)
+    }
+
+    fn rollback_transaction(&self) -> Result<(), Error> {
+        let depth = self.trans_depth.get();
+        let statement = match depth {
+            0 => return Err(Error::NoTransaction),
+            1 => "ROLLBACK".to_owned(),
+            _ => format!("ROLLBACK TO SAVEPOINT proton_sp_{}", depth - 1),
+        };
+        self.trans_depth.set(depth - 1);
+        self.conn.batch_execute(&statement).map_err(Error::Postgres)
+    }
+}
@@ -0,0 +1,80 @@
+use dao::{UserDao, DaoFile};
+use error::Error;
+use project_types::User;
+
+
+impl UserDao for DaoFile {
+
+    fn add_initial_user(&self, proj_name: &str, public_key: &str) -> Result<u32, Error> {
+        let uid = try!(self.memory.add_initial_user(proj_name, public_key));
+        try!(self.save());
+        Ok(uid)
+    }
+
+    fn add_user(&self, name: &str, public_key: &str) -> Result<u32, Error> {
+        let uid = try!(self.memory.add_user(name, public_key));
+        try!(self.save());
+        Ok(uid)
+    }
+
+    fn get_user_id(&self, public_key: &str) -> Result<u32, Error> {
+        self.memory.get_user_id(public_key)
+    }
+    
+    fn get_user(&self, uid: u32) -> Result<User, Error> {
+        self.memory.get_user(uid)
+    }
+
+    fn list_users(&self, name_filter: Option<&str>) -> Result<Vec<User>, Error> {
+        self.memory.list_users(name_filter)
+    }
+
+
+    fn get_users_by_name(&self, name: &str) -> Result<Vec<User>, Error> {
+        self.memory.get_users_by_name(name)
+    }
+
+    fn delete_user(&self, uid: u32) -> Result<(), Error> {
+        try!(self.memory.delete_user(uid));
+        self.save()
+    }
+
+    fn set_public_key(&self, uid: u32, public_key: &str) -> Result<(), Error> {
+        try!(self.memory.set_public_key(uid, public_key));
+        self.save()
+    }
+
+    fn revoke_key(&self, uid: u32, public_key: &str) -> Result<(), Error> {
+        try!(self.memory.revoke_key(uid, public_key));
+        self.save()
+    }
+
+    fn is_key_revoked(&self, public_key: &str) -> Result<bool, Error> {
+        self.memory.is_key_revoked(public_key)
+    }
+
+    fn is_new_admin_open(&self) -> Result<bool, Error> {
+        self.memory.is_new_admin_open()
+    }
+
+    fn set_new_admin_open(&self, open: bool) -> Result<(), Error> {
+        try!(self.memory.set_new_admin_open(open));
+        self.save()
+    }
+
+    fn add_challenge(&self, challenge: &[u8], issued_at: u64) -> Result<(), Error> {
+        try!(self.memory.add_challenge(challenge, issued_at));
+        self.save()
+    }
+
+    fn take_challenge(&self, challenge: &[u8]) -> Result<Option<u64>, Error> {
+        let issued_at = try!(self.memory.take_challenge(challenge));
+        try!(self.save());
+        Ok(issued_at)
+    }
+
+    fn delete_challenges_before(&self, issued_before: u64) -> Result<(), Error> {
+        try!(self.memory.delete_challenges_before(issued_before));
+        self.save()
+    }
+}
@@ -0,0 +1,154 @@
+use rustc_serialize::hex::ToHex;
+use std::collections::BTreeMap;
+
+use dao::{UserDao, DaoMemory};
+use dao::daos_memory::{name_matches, MemoryUser};
+use error::Error;
+use keys;
+use project_types::User;
+
+
+impl UserDao for DaoMemory {
+
+    fn add_initial_user(&self, proj_name: &str, public_key: &str) -> Result<u32, Error> {
+        let root_uname = format!("{}_{}", "root", proj_name);
+        self.add_user(&root_uname, public_key)
+    }
+
+    fn add_user(&self, name: &str, public_key: &str) -> Result<u32, Error> {
+        let mut store = self.store.borrow_mut();
+        // User names are unique, like in the Postgres schema
+        if store.users.values().any(|row| row.user.name == name) {
+            return Err(Error::DuplicateUser(public_key.trim_matches('\n').to_owned(), name.to_owned()));
+        }
+        let uid = store.next_uid();
+        store.users.insert(uid, MemoryUser {
+            user: User {
+                uid: uid,
+                name: name.to_owned(),
+                public_key: public_key.trim_matches('\n').to_owned()
+            }
+        });
+        Ok(uid)
+    }
+
+    /// Identifies a user by their public key's fingerprint, so the key
+    /// may be given in any format
+    fn get_user_id(&self, public_key: &str) -> Result<u32, Error> {
+        let store = self.store.borrow();
+        let fingerprint = try!(keys::fingerprint(public_key));
+        let uids = store.users.values()
+            .filter(|row| keys::fingerprint(&row.user.public_key).ok().as_ref() == Some(&fingerprint))
+            .map(|row| row.user.uid)
+            .collect::<Vec<u32>>();
+        match uids.len() {
+            0 => Err(Error::PublicKeyNotFound(public_key.to_owned())),
+            1 => Ok(uids[0]),
+            x => Err(Error::InvalidNumResults(x)),
+        }
+    }
+    
+    fn get_user(&self, uid: u32) -> Result<User, Error> {
+        let store = self.store.borrow();
+        store.users.get(&uid)
+            .map(|row| row.user.clone())
+            .ok_or(Error::UserNotFound)
+    }
+
+    fn list_users(&self, name_filter: Option<&str>) -> Result<Vec<User>, Error> {
+        let store = self.store.borrow();
+        let users = store.users.values()
+            .filter(|row| name_matches(&row.user.name, name_filter))
+            .map(|row| row.user.clone())
+            .collect::<Vec<User>>();
+        Ok(users)
+    }
+
+
+    fn get_users_by_name(&self, name: &str) -> Result<Vec<User>, Error> {
+        let store = self.store.borrow();
+        let users = store.users.values()
+            .filter(|row| row.user.name == name)
+            .map(|row| row.user.clone())
+            .collect::<Vec<User>>();
+        Ok(users)
+    }
+
+    fn delete_user(&self, uid: u32) -> Result<(), Error> {
+        let mut store = self.store.borrow_mut();
+        if store.users.remove(&uid).is_none() {
+            return Err(Error::UserNotFound);
+        }
+        let permids = store.permissions.values()
+            .filter(|permission| permission.uid == uid)
+            .map(|permission| permission.permid)
+            .collect::<Vec<u32>>();
+        for permid in permids {
+            store.permissions.remove(&permid);
+        }
+        Ok(())
+    }
+
+    fn set_public_key(&self, uid: u32, public_key: &str) -> Result<(), Error> {
+        let mut store = self.store.borrow_mut();
+        match store.users.get_mut(&uid) {
+            Some(row) => {
+                row.user.public_key = public_key.trim_matches('\n').to_owned();
+                Ok(())
+            },
+            None => Err(Error::UserNotFound),
+        }
+    }
+
+    fn revoke_key(&self, uid: u32, public_key: &str) -> Result<(), Error> {
+        let fingerprint = try!(keys::fingerprint(public_key));
+        let mut store = self.store.borrow_mut();
+        if store.revoked_keys.is_none() {
+            store.revoked_keys = Some(BTreeMap::new());
+        }
+        let _ = store.revoked_keys.as_mut().unwrap().insert(fingerprint, uid);
+        Ok(())
+    }
+
+    fn is_key_revoked(&self, public_key: &str) -> Result<bool, Error> {
+        let fingerprint = try!(keys::fingerprint(public_key));
+        let store = self.store.borrow();
+        Ok(store.revoked_keys.as_ref().map_or(false, |revoked_keys| revoked_keys.contains_key(&fingerprint)))
+    }
+
+    fn is_new_admin_open(&self) -> Result<bool, Error> {
+        Ok(self.store.borrow().new_admin_open.unwrap_or(false))
+    }
+
+    fn set_new_admin_open(&self, open: bool) -> Result<(), Error> {
+        self.store.borrow_mut().new_admin_open = Some(open);
+        Ok(())
+    }
+
+    fn add_challenge(&self, challenge: &[u8], issued_at: u64) -> Result<(), Error> {
+        let mut store = self.store.borrow_mut();
+        if store.challenges.is_none() {
+            store.challenges = Some(BTreeMap::new());
+        }
+        let _ = store.challenges.as_mut().unwrap().insert(challenge.to_hex(), issued_at);
+        Ok(())
+    }
+
+    fn take_challenge(&self, challenge: &[u8]) -> Result<Option<u64>, Error> {
+        let mut store = self.store.borrow_mut();
+        Ok(store.challenges.as_mut().and_then(|challenges| challenges.remove(&challenge.to_hex())))
+    }
+
+    fn delete_challenges_before(&self, issued_before: u64) -> Result<(), Error> {
+        if let Some(ref mut challenges) = self.store.borrow_mut().challenges {
+            let old = challenges.iter()
+                .filter(|&(_, issued_at)| *issued_at < issued_before)
+                .map(|(challenge, _)| challenge.clone())
+                .collect::<Vec<String>>();
+            for challenge in old {
+                let _ = challenges.remove(&challenge);
+            }
+        }
+        Ok(())
+    }
+}
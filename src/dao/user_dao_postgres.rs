@@ -1,36 +1,39 @@
 use dao::{UserDao, DaoPostgres};
+use dao::daos_postgres::duplicate_or_postgres;
+use dao::migrations;
 use error::Error;
+use keys;
 use project_types::User;
 
 
 impl UserDao for DaoPostgres {
 
-    fn add_initial_user(&self, proj_name: &str, private_key: &str, public_key: &str) -> Result<u32, Error> {
+    fn add_initial_user(&self, proj_name: &str, public_key: &str) -> Result<u32, Error> {
         let root_uname = format!("{}_{}", "root", proj_name);
-        self.add_user(&root_uname, private_key, public_key)
+        self.add_user(&root_uname, public_key)
     }
 
-    fn add_user(&self, name: &str, private_key: &str, public_key: &str) -> Result<u32, Error> {
-        let statement = "INSERT INTO users (name, private_key, public_key) VALUES ($1, $2, $3)";
-        let private_string = private_key.trim_matches('\n');
+    fn add_user(&self, name: &str, public_key: &str) -> Result<u32, Error> {
+        let statement = "INSERT INTO users (name, public_key, fingerprint) VALUES ($1, $2, $3) RETURNING uid";
         let public_string = public_key.trim_matches('\n');
-        let _ = try!(
-            self.conn.execute(
-                statement,
-                &[&name.to_owned(), &private_string, &public_string])
-            .map_err(Error::Postgres));
-        let uid = try!(self.get_user_id(&public_string));
-        Ok(uid)
+        let fingerprint = try!(keys::fingerprint(public_key));
+        let results = try!(
+            self.conn.query(statement, &[&name.to_owned(), &public_string, &fingerprint])
+            .map_err(|e| duplicate_or_postgres(e, Error::DuplicateUser(public_string.to_owned(), name.to_owned()))));
+        let row = results.get(0);
+        let uid: i32 = row.get(0);
+        Ok(uid as u32)
     }
 
-    /// Identifies a user by their public SSH key
+    /// Identifies a user by their public key's fingerprint, so the key
+    /// may be given in any format
     ///
     /// Impure.
     fn get_user_id(&self, public_key: &str) -> Result<u32, Error> {
-        let public_string = public_key.trim_matches('\n');
-        let query = "SELECT uid FROM users WHERE public_key = $1";
+        let fingerprint = try!(keys::fingerprint(public_key));
+        let query = "SELECT uid FROM users WHERE fingerprint = $1";
         let results = try!(
-            self.conn.query(query, &[&public_string])
+            self.conn.query(query, &[&fingerprint])
             .map_err(Error::Postgres));
         
         match results.len() {
@@ -65,4 +68,132 @@ impl UserDao for DaoPostgres {
             x => Err(Error::InvalidNumResults(x)),
         }
     }
+
+    fn list_users(&self, name_filter: Option<&str>) -> Result<Vec<User>, Error> {
+        let query = "SELECT uid, name, public_key FROM users \
+            WHERE $1::text IS NULL OR strpos(lower(name), lower($1)) > 0 \
+            ORDER BY uid";
+        let results = try!(
+            self.conn.query(query, &[&name_filter.map(|filter| filter.to_owned())])
+            .map_err(Error::Postgres));
+        let users = results.iter()
+            .map(|row| {
+                let uid: i32 = row.get(0);
+                let name: String = row.get(1);
+                let public_key: String = row.get(2);
+                User {
+                    uid: uid as u32,
+                    name: name,
+                    public_key: public_key
+                }
+            })
+            .collect::<Vec<User>>();
+        Ok(users)
+    }
+
+
+    fn get_users_by_name(&self, name: &str) -> Result<Vec<User>, Error> {
+        let query = "SELECT uid, public_key FROM users WHERE name = $1 ORDER BY uid";
+        let results = try!(
+            self.conn.query(query, &[&name.to_owned()])
+            .map_err(Error::Postgres));
+        let users = results.iter()
+            .map(|row| {
+                let uid: i32 = row.get(0);
+                let public_key: String = row.get(1);
+                User {
+                    uid: uid as u32,
+                    name: name.to_owned(),
+                    public_key: public_key
+                }
+            })
+            .collect::<Vec<User>>();
+        Ok(users)
+    }
+
+    fn delete_user(&self, uid: u32) -> Result<(), Error> {
+        let uid_i32 = uid as i32;
+        let _ = try!(
+            self.conn.execute("DELETE FROM permissions WHERE uid = $1", &[&uid_i32])
+            .map_err(Error::Postgres));
+        let num_deleted = try!(
+            self.conn.execute("DELETE FROM users WHERE uid = $1", &[&uid_i32])
+            .map_err(Error::Postgres));
+        match num_deleted {
+            0 => Err(Error::UserNotFound),
+            _ => Ok(()),
+        }
+    }
+
+    fn set_public_key(&self, uid: u32, public_key: &str) -> Result<(), Error> {
+        let statement = "UPDATE users SET public_key = $2, fingerprint = $3 WHERE uid = $1";
+        let public_string = public_key.trim_matches('\n');
+        let fingerprint = try!(keys::fingerprint(public_key));
+        let num_updated = try!(
+            self.conn.execute(statement, &[&(uid as i32), &public_string, &fingerprint])
+            .map_err(Error::Postgres));
+        match num_updated {
+            0 => Err(Error::UserNotFound),
+            _ => Ok(()),
+        }
+    }
+
+    fn revoke_key(&self, uid: u32, public_key: &str) -> Result<(), Error> {
+        let statement = "INSERT INTO revoked_keys (fingerprint, uid, revoked_at) \
+            VALUES ($1, $2, extract(epoch FROM now())::bigint)";
+        let fingerprint = try!(keys::fingerprint(public_key));
+        let _ = try!(
+            self.conn.execute(statement, &[&fingerprint, &(uid as i32)])
+            .map_err(Error::Postgres));
+        Ok(())
+    }
+
+    fn is_key_revoked(&self, public_key: &str) -> Result<bool, Error> {
+        let query = "SELECT 1 FROM revoked_keys WHERE fingerprint = $1";
+        let fingerprint = try!(keys::fingerprint(public_key));
+        let results = try!(
+            self.conn.query(query, &[&fingerprint])
+            .map_err(Error::Postgres));
+        Ok(!results.is_empty())
+    }
+
+    fn is_new_admin_open(&self) -> Result<bool, Error> {
+        let query = "SELECT 1 FROM new_admin_open";
+        let results = try!(
+            self.conn.query(query, &[])
+            .map_err(Error::Postgres));
+        Ok(!results.is_empty())
+    }
+
+    fn set_new_admin_open(&self, open: bool) -> Result<(), Error> {
+        migrations::set_new_admin_open(&self.conn, open)
+    }
+
+    fn add_challenge(&self, challenge: &[u8], issued_at: u64) -> Result<(), Error> {
+        let statement = "INSERT INTO challenges (challenge, issued_at) VALUES ($1, $2)";
+        let _ = try!(
+            self.conn.execute(statement, &[&challenge.to_vec(), &(issued_at as i64)])
+            .map_err(Error::Postgres));
+        Ok(())
+    }
+
+    fn take_challenge(&self, challenge: &[u8]) -> Result<Option<u64>, Error> {
+        // Deleting and returning in one statement, so a challenge is only ever taken once
+        let statement = "DELETE FROM challenges WHERE challenge = $1 RETURNING issued_at";
+        let results = try!(
+            self.conn.query(statement, &[&challenge.to_vec()])
+            .map_err(Error::Postgres));
+        Ok(results.iter().next().map(|row| {
+            let issued_at: i64 = row.get(0);
+            issued_at as u64
+        }))
+    }
+
+    fn delete_challenges_before(&self, issued_before: u64) -> Result<(), Error> {
+        let statement = "DELETE FROM challenges WHERE issued_at < $1";
+        let _ = try!(
+            self.conn.execute(statement, &[&(issued_before as i64)])
+            .map_err(Error::Postgres));
+        Ok(())
+    }
 }
@@ -0,0 +1,93 @@
+//! This module reads and writes parts of sequences' channel data, so editing a
+//! few seconds of a fixture doesn't move every frame of its channels
+
+use std::collections::BTreeMap;
+
+use audit;
+use dao::ProtonDao;
+use error::Error;
+use project_types::{Credentials, DataCell, PermissionEnum, Sequence};
+use utils;
+
+
+/// Gets the given channels' frames that start between t_start (inclusive) and
+/// t_end (exclusive), in ms, keyed by chanid. Needs Viewer (or any role that
+/// includes it) or EditSequence on the sequence.
+pub fn get_data_range<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    seqid: u32,
+    chan_ids: &[u32],
+    t_start: u32,
+    t_end: u32
+) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+
+    let valid_permissions = vec![PermissionEnum::Viewer, PermissionEnum::EditSequence(seqid)];
+    let _ = try!(utils::check_sequence_permission(dao, credentials, seqid, &valid_permissions));
+
+    let sequence = try!(dao.get_sequence(seqid));
+    try!(check_time_range(&sequence, t_start, t_end));
+    let (first_frame, end_frame) = sequence.frame_range(t_start, t_end);
+    dao.get_data_range(seqid, chan_ids, first_frame, end_frame)
+}
+
+/// Overwrites each given channel's frames from the first frame starting at or
+/// after t_start (in ms) on, with the given values, all or nothing. Writing back
+/// what get_data_range returned for the same t_start changes nothing else.
+/// Needs EditSequence on the sequence (Sequencer and Administrate include it).
+/// Returns the number of frames changed.
+pub fn set_data_range<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    seqid: u32,
+    t_start: u32,
+    chan_data: &BTreeMap<u32, Vec<u16>>
+) -> Result<u32, Error> {
+
+    let valid_permissions = vec![PermissionEnum::EditSequence(seqid)];
+    let uid = try!(utils::check_sequence_permission(dao, credentials, seqid, &valid_permissions));
+
+    let sequence = try!(dao.get_sequence(seqid));
+    let num_frames = chan_data.values().map(|data| data.len() as u32).max().unwrap_or(0);
+    let t_end = try!(num_frames.checked_mul(sequence.frame_duration_ms)
+        .and_then(|duration| t_start.checked_add(duration))
+        .ok_or(Error::InvalidFrameRange(format!(
+            "{} frames from {} ms don't fit in sequence {}", num_frames, t_start, seqid))));
+    try!(check_time_range(&sequence, t_start, t_end));
+    let (first_frame, _) = sequence.frame_range(t_start, t_end);
+    let chan_ids = chan_data.keys().cloned().collect::<Vec<u32>>();
+
+    dao.transaction(|| {
+        // Only write the frames that actually change
+        let current = try!(dao.get_data_range(seqid, &chan_ids, first_frame, first_frame + num_frames));
+        let mut cells = vec![];
+        for (chanid, data) in chan_data {
+            let current_data = &current[chanid];
+            for (i, value) in data.iter().enumerate() {
+                if current_data[i] != *value {
+                    cells.push(DataCell {
+                        chanid: *chanid,
+                        frame: first_frame + i as u32,
+                        value: *value
+                    });
+                }
+            }
+        }
+
+        try!(dao.patch_data(seqid, &cells));
+        let after = format!("{} frames of channels {:?} from {} ms", cells.len(), chan_ids, t_start);
+        try!(audit::record_for_sequence(dao, uid, "set-data-range", seqid, "sequence", &seqid.to_string(),
+            None, Some(after)));
+        Ok(cells.len() as u32)
+    })
+}
+
+/// Checks that the time range doesn't end before it starts and is within the sequence
+fn check_time_range(sequence: &Sequence, t_start: u32, t_end: u32) -> Result<(), Error> {
+    if t_start > t_end || t_end > sequence.duration_ms() {
+        return Err(Error::InvalidFrameRange(format!(
+            "{}-{} ms is not within sequence {} ({} ms)",
+            t_start, t_end, sequence.seqid, sequence.duration_ms())));
+    }
+    Ok(())
+}
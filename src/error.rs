@@ -16,32 +16,74 @@ pub enum Error {
     Rsfml(String),
     Postgres(postgres_err::Error),
     PostgresConnection(postgres_err::ConnectError),
+    MissingConnectionConfig(String),
+    InvalidConnectionConfig(String),
+    ConnectionProfileNotFound(String),
+    UnsupportedSchemaVersion(u32, u32),
+    DatabaseAlreadyInitialized,
+    NoTransaction,
+    DatabaseLocked(String),
     FileNotFound(String),
     FolderNotEmpty(String, usize),
+    InvalidBundle(String),
     InvalidFileName,
+    InvalidTime(String),
     InvalidFrameDuration(u32),
+    InvalidResampleMode(String),
+    ExpiredChallenge,
+    UnknownChallenge,
+    EncryptedPrivateKey(String),
     InvalidLayout(String),
     InvalidNumResults(usize),
     InvalidPatch(String),
     InvalidPermissionName(String),
+    InvalidPrivateKey(String),
     InvalidProjectName(String),
     InvalidPublicKey(String),
     InvalidSequenceName(String),
     InvalidSequenceSection(u32),
+    InvalidSection(String),
+    SectionOverlap(u32),
+    InvalidCheckout(String),
+    SectionChanged(u32),
+    InvalidSignature,
+    KeyRevoked(String),
     InvalidVixenData(String),
     LoadProjectError,
     MissingPermissionArg,
     OffsetOutOfBounds(u32, u32),
     DuplicateUser(String, String),
+    AmbiguousUser(String, Vec<u32>),
+    CannotRevokeRootAdmin(String),
+    CannotRemoveRoot(String),
+    CannotRevokeLastAdmin(String),
+    CannotRemoveLastAdmin(String),
+    AdminExists,
+    NewAdminClosed,
+    DuplicateProject(String),
+    DuplicateLayout(String),
+    DuplicateFixture(String),
     DuplicateSequence(String),
+    DuplicatePermission(u32, String),
+    SequenceInPlaylist(u32, Vec<String>),
+    ImportConflicts(Vec<String>),
     UnsupportedFileType(String),
     AdminNotFound,
     ChannelNotFound(u32),
     ChannelDataNotFound(u32, u32),
+    CorruptChannelData(String),
+    SequenceHasNoChannels(u32),
+    DmxChannelOutOfRange(u32, u32),
+    InvalidFrameRange(String),
+    InvalidFrameCount(u32, u32, u32, u32),
+    InvalidFrameValue(u32, u32, u16, u16),
     FixtureNotFound(u32),
     LayoutNotFound(u32),
+    PermissionNotFound(u32),
+    PermissionNotHeld(u32, String),
     ProjectNotFound(String),
     PublicKeyNotFound(String),
+    SectionNotFound(u32),
     SequenceNotFound(u32),
     UserNotFound,
     UnauthorizedAction,
@@ -60,32 +102,74 @@ impl error::Error for Error {
             Error::Rsfml(_) => "Rsfml error occured",
             Error::Postgres(_) => "Postgres error occured",
             Error::PostgresConnection(_) => "Postgres connection error occured",
+            Error::MissingConnectionConfig(_) => "No database connection configured",
+            Error::InvalidConnectionConfig(_) => "Invalid database connection configuration",
+            Error::ConnectionProfileNotFound(_) => "Connection profile not found",
+            Error::UnsupportedSchemaVersion(_, _) => "Unsupported database schema version",
+            Error::DatabaseAlreadyInitialized => "Database already initialized",
+            Error::NoTransaction => "No transaction open",
+            Error::DatabaseLocked(_) => "Project database file is in use",
             Error::FileNotFound(_) => "File not found",
             Error::FolderNotEmpty(_, _) => "Root folder was not empty",
+            Error::InvalidBundle(_) => "Invalid project bundle",
             Error::InvalidFileName => "Invalid file name",
+            Error::InvalidTime(_) => "Invalid time",
             Error::InvalidFrameDuration(_) => "Invalid frame duration",
+            Error::InvalidResampleMode(_) => "Invalid resample mode",
+            Error::ExpiredChallenge => "Challenge expired",
+            Error::UnknownChallenge => "Unknown challenge",
+            Error::EncryptedPrivateKey(_) => "Private key is encrypted",
             Error::InvalidLayout(_) => "Invalid layout",
             Error::InvalidNumResults(_) => "Invalid number of results returned",
             Error::InvalidPatch(_) => "Invalid patch file",
             Error::InvalidPermissionName(_) => "Invalid permission name",
+            Error::InvalidPrivateKey(_) => "Invalid private key",
             Error::InvalidProjectName(_) => "Invalid project name",
             Error::InvalidPublicKey(_) => "Invalid public key",
             Error::InvalidSequenceName(_) => "Invalid sequence name",
             Error::InvalidSequenceSection(_) => "Invalid sequence section",
+            Error::InvalidSection(_) => "Invalid section",
+            Error::SectionOverlap(_) => "Section overlaps another",
+            Error::InvalidCheckout(_) => "Invalid section checkout",
+            Error::SectionChanged(_) => "Section changed since checkout",
+            Error::InvalidSignature => "Signature does not match the user's public key",
+            Error::KeyRevoked(_) => "Key revoked",
             Error::InvalidVixenData(_) => "Invalid Vixen data",
             Error::LoadProjectError => "Loading project failed",
             Error::MissingPermissionArg => "Permission argument required but missing (seqid or secid)",
             Error::OffsetOutOfBounds(_, _) => "Offset out of bouds",
             Error::DuplicateUser(_, _) => "User already exists",
+            Error::AmbiguousUser(_, _) => "More than one user matches",
+            Error::CannotRevokeRootAdmin(_) => "Cannot revoke a project root user's Administrate",
+            Error::CannotRemoveRoot(_) => "Cannot remove a project root user",
+            Error::CannotRevokeLastAdmin(_) => "Cannot revoke the last administrator key",
+            Error::CannotRemoveLastAdmin(_) => "Cannot remove the last administrator",
+            Error::AdminExists => "An administrator already exists",
+            Error::NewAdminClosed => "new-admin is closed for this database",
+            Error::DuplicateProject(_) => "Project already exists",
+            Error::DuplicateLayout(_) => "Layout already exists",
+            Error::DuplicateFixture(_) => "Fixture already exists",
             Error::DuplicateSequence(_) => "Sequence already exists",
+            Error::DuplicatePermission(_, _) => "User already has permission",
+            Error::SequenceInPlaylist(_, _) => "Sequence is still in a playlist",
+            Error::ImportConflicts(_) => "Project import conflicts",
             Error::UnsupportedFileType(_) => "Unsupported file type",
             Error::AdminNotFound => "Admin not found",
             Error::ChannelNotFound(_) => "Channel not found",
             Error::ChannelDataNotFound(_, _) => "Channel data not found",
+            Error::CorruptChannelData(_) => "Corrupt channel data",
+            Error::SequenceHasNoChannels(_) => "Sequence has no channels",
+            Error::DmxChannelOutOfRange(_, _) => "DMX channel out of range",
+            Error::InvalidFrameRange(_) => "Invalid frame range",
+            Error::InvalidFrameCount(_, _, _, _) => "Invalid frame count",
+            Error::InvalidFrameValue(_, _, _, _) => "Invalid frame value",
             Error::FixtureNotFound(_) => "Fixture not found",
             Error::LayoutNotFound(_) => "Layout not found",
+            Error::PermissionNotFound(_) => "Permission not found",
+            Error::PermissionNotHeld(_, _) => "User does not have permission",
             Error::ProjectNotFound(_) => "Project not found",
             Error::PublicKeyNotFound(_) => "Public key not found",
+            Error::SectionNotFound(_) => "Section not found",
             Error::SequenceNotFound(_) => "Sequence not found",
             Error::UserNotFound => "User not found",
             Error::UnauthorizedAction => "Unauthorized action",
@@ -105,32 +189,74 @@ impl error::Error for Error {
            Error::Rsfml(_) => None,
            Error::Postgres(ref err) => Some(err),
            Error::PostgresConnection(ref err) => Some(err),
+           Error::MissingConnectionConfig(_) => None,
+           Error::InvalidConnectionConfig(_) => None,
+           Error::ConnectionProfileNotFound(_) => None,
+           Error::UnsupportedSchemaVersion(_, _) => None,
+           Error::DatabaseAlreadyInitialized => None,
+           Error::NoTransaction => None,
+           Error::DatabaseLocked(_) => None,
            Error::FileNotFound(_) => None,
            Error::FolderNotEmpty(_, _) => None,
+           Error::InvalidBundle(_) => None,
            Error::InvalidFileName => None,
+           Error::InvalidTime(_) => None,
            Error::InvalidFrameDuration(_) => None,
+           Error::InvalidResampleMode(_) => None,
+           Error::ExpiredChallenge => None,
+           Error::UnknownChallenge => None,
+           Error::EncryptedPrivateKey(_) => None,
            Error::InvalidLayout(_) => None,
            Error::InvalidNumResults(_) => None,
            Error::InvalidPatch(_) => None,
            Error::InvalidPermissionName(_) => None,
+           Error::InvalidPrivateKey(_) => None,
            Error::InvalidProjectName(_) => None,
            Error::InvalidPublicKey(_) => None,
            Error::InvalidSequenceName(_) => None,
            Error::InvalidSequenceSection(_) => None,
+           Error::InvalidSection(_) => None,
+           Error::SectionOverlap(_) => None,
+           Error::InvalidCheckout(_) => None,
+           Error::SectionChanged(_) => None,
+           Error::InvalidSignature => None,
+           Error::KeyRevoked(_) => None,
            Error::InvalidVixenData(_) => None,
            Error::LoadProjectError => None,
            Error::MissingPermissionArg => None,
            Error::OffsetOutOfBounds(_, _) => None,
            Error::DuplicateUser(_, _) => None,
+           Error::AmbiguousUser(_, _) => None,
+           Error::CannotRevokeRootAdmin(_) => None,
+           Error::CannotRemoveRoot(_) => None,
+           Error::CannotRevokeLastAdmin(_) => None,
+           Error::CannotRemoveLastAdmin(_) => None,
+           Error::AdminExists => None,
+           Error::NewAdminClosed => None,
+           Error::DuplicateProject(_) => None,
+           Error::DuplicateLayout(_) => None,
+           Error::DuplicateFixture(_) => None,
            Error::DuplicateSequence(_) => None,
+           Error::DuplicatePermission(_, _) => None,
+           Error::SequenceInPlaylist(_, _) => None,
+           Error::ImportConflicts(_) => None,
            Error::UnsupportedFileType(_) => None,
            Error::AdminNotFound => None,
            Error::ChannelNotFound(_) => None,
            Error::ChannelDataNotFound(_, _) => None,
+           Error::CorruptChannelData(_) => None,
+           Error::SequenceHasNoChannels(_) => None,
+           Error::DmxChannelOutOfRange(_, _) => None,
+           Error::InvalidFrameRange(_) => None,
+           Error::InvalidFrameCount(_, _, _, _) => None,
+           Error::InvalidFrameValue(_, _, _, _) => None,
            Error::FixtureNotFound(_) => None,
            Error::LayoutNotFound(_) => None,
+           Error::PermissionNotFound(_) => None,
+           Error::PermissionNotHeld(_, _) => None,
            Error::ProjectNotFound(_) => None,
            Error::PublicKeyNotFound(_) => None,
+           Error::SectionNotFound(_) => None,
            Error::SequenceNotFound(_) => None,
            Error::UserNotFound => None,
            Error::UnauthorizedAction => None,
@@ -159,14 +285,40 @@ impl fmt::Display for Error {
                 "Postgress error occured: {}", err),
             Error::PostgresConnection(ref err) => write!(f, 
                 "Postgress connection error occured: {}", err),
+            Error::MissingConnectionConfig(ref config_path) => write!(f,
+                "No database configured. Pass --db, set PROTON_DATABASE_URL or PGHOST, or add a host to {}", config_path),
+            Error::InvalidConnectionConfig(ref msg) => write!(f,
+                "Invalid database connection configuration: {}", msg),
+            Error::ConnectionProfileNotFound(ref profile) => write!(f,
+                "Connection profile not found: {}", profile),
+            Error::UnsupportedSchemaVersion(ref found, ref expected) => write!(f,
+                "Database schema version {} is not supported (expected {}). Run migrate or upgrade proton_cli", found, expected),
+            Error::DatabaseAlreadyInitialized => write!(f,
+                "Database already contains a schema. Use migrate instead"),
+            Error::NoTransaction => write!(f,
+                "Tried to end a transaction, but none was open"),
+            Error::DatabaseLocked(ref lock_path) => write!(f,
+                "Project database file is in use by another command (remove {} if none is running)", lock_path),
             Error::FileNotFound(ref path) => write!(f,
                 "File not found at path '{}'", path),
             Error::FolderNotEmpty(ref root, count) => write!(f,
                 "{} was not empty: {} files exist", root, count),
+            Error::InvalidBundle(ref msg) => write!(f,
+                "Invalid project bundle: {}", msg),
             Error::InvalidFileName => write!(f,
                 "File name provided is invalid and cannot be retrieved"),
+            Error::InvalidTime(ref time) => write!(f,
+                "Invalid time '{}': give seconds since 1970 or YYYY-MM-DD[THH:MM[:SS]] (UTC)", time),
             Error::InvalidFrameDuration(ref duration) => write!(f,
                 "Invalid frame duration: {}", duration),
+            Error::InvalidResampleMode(ref mode) => write!(f,
+                "Invalid resample mode: {} (expected nearest, hold or linear)", mode),
+            Error::ExpiredChallenge => write!(f,
+                "Signed challenge is too old; sign a new one"),
+            Error::UnknownChallenge => write!(f,
+                "Signed challenge was not issued by this database or was already used; sign a new one"),
+            Error::EncryptedPrivateKey(ref path) => write!(f,
+                "Passphrase-protected private keys are not supported: {}", path),
             Error::InvalidLayout(ref description) => write!(f,
                 "The layout being read or decoded is invalid: {}", description),
             Error::InvalidNumResults(ref num_results) => write!(f,
@@ -175,6 +327,8 @@ impl fmt::Display for Error {
                 "Invalid patch file: {}", description),
             Error::InvalidPermissionName(ref name) => write!(f,
                 "Invalid permission name provided: {}", name),
+            Error::InvalidPrivateKey(ref path) => write!(f,
+                "Not a valid private key: {}", path),
             Error::InvalidProjectName(ref name) => write!(f,
                 "Invalid project name provided: {}", name),
             Error::InvalidPublicKey(ref key) => write!(f, 
@@ -185,6 +339,18 @@ impl fmt::Display for Error {
                 "Invalid Vixen data provided: {}", details),
             Error::InvalidSequenceSection(ref section) => write!(f,
                 "Invalid sequence section: {}", section),
+            Error::InvalidSection(ref reason) => write!(f,
+                "Invalid section: {}", reason),
+            Error::SectionOverlap(ref secid) => write!(f,
+                "Section overlaps section {} in both time and fixtures", secid),
+            Error::InvalidCheckout(ref reason) => write!(f,
+                "Invalid section checkout: {}", reason),
+            Error::SectionChanged(ref secid) => write!(f,
+                "Section {} changed since it was checked out; check it out again and redo your edits", secid),
+            Error::InvalidSignature => write!(f,
+                "Signature does not match the user's public key"),
+            Error::KeyRevoked(ref fingerprint) => write!(f,
+                "Key {} has been revoked; ask an administrator for rotate-key", fingerprint),
             Error::LoadProjectError => write!(f, "Loading project failed"),
             Error::MissingPermissionArg => write!(f,
               "Permission argument required but missing (did you forget seqid or secid?)"),
@@ -192,8 +358,36 @@ impl fmt::Display for Error {
               "Offset {} not between 0 and {} (inclusive)", offset, upper_bound),
             Error::DuplicateUser(ref key, ref user) => write!(f,
                 "Duplicate user '{}' or key '{}'", user, key),
+            Error::AmbiguousUser(ref name, ref uids) => write!(f,
+                "More than one user matches '{}' (uids {}); give the uid with --uid",
+                name, uids.iter().map(|uid| uid.to_string()).collect::<Vec<String>>().join(", ")),
+            Error::CannotRevokeRootAdmin(ref name) => write!(f,
+                "'{}' is a project root user and must keep Administrate", name),
+            Error::CannotRemoveRoot(ref name) => write!(f,
+                "'{}' is a project root user and cannot be removed", name),
+            Error::CannotRevokeLastAdmin(ref name) => write!(f,
+                "Cannot revoke the key of {}, the only administrator outside of any project with a working key", name),
+            Error::CannotRemoveLastAdmin(ref name) => write!(f,
+                "{} is the only administrator outside of any project with a working key and must keep Administrate", name),
+            Error::AdminExists => write!(f,
+                "An administrator already exists; ask them to add you with new-user"),
+            Error::NewAdminClosed => write!(f,
+                "new-admin only works once on a new database; run migrate --allow-new-admin to open it for a database without an administrator"),
+            Error::DuplicateProject(ref name) => write!(f,
+                "Duplicate project with name '{}'", name),
+            Error::DuplicateLayout(ref name) => write!(f,
+                "Duplicate layout with name '{}'", name),
+            Error::DuplicateFixture(ref name) => write!(f,
+                "Duplicate fixture with name '{}'", name),
             Error::DuplicateSequence(ref name) => write!(f,
                 "Duplicate sequence with name '{}'", name),
+            Error::DuplicatePermission(ref uid, ref permission) => write!(f,
+                "User {} already has permission {}", uid, permission),
+            Error::SequenceInPlaylist(ref seqid, ref projects) => write!(f,
+                "Sequence {} is in the playlist of {}; use --force to delete it anyway",
+                seqid, projects.join(", ")),
+            Error::ImportConflicts(ref conflicts) => write!(f,
+                "Nothing was imported because of conflicts:\n  {}", conflicts.join("\n  ")),
             Error::UnsupportedFileType(ref file_type) => write!(f, 
                 "Unsupported file type: {}", file_type),
             Error::AdminNotFound => write!(f, "Admin not found"),
@@ -201,14 +395,32 @@ impl fmt::Display for Error {
                 "Channel not found: {}", chanid),
             Error::ChannelDataNotFound(ref seqid, ref chanid) => write!(f,
                 "Channel data not found. seqid: {}, chanid: {}", seqid, chanid),
+            Error::CorruptChannelData(ref details) => write!(f,
+                "Stored channel data could not be decoded: {}", details),
+            Error::SequenceHasNoChannels(ref seqid) => write!(f,
+                "Sequence {} has no channels to export", seqid),
+            Error::DmxChannelOutOfRange(ref seqid, ref channel_dmx) => write!(f,
+                "Sequence {} has data for DMX channel {}, outside the universe (1-512)", seqid, channel_dmx),
+            Error::InvalidFrameRange(ref details) => write!(f,
+                "Invalid frame range: {}", details),
+            Error::InvalidFrameCount(ref seqid, ref chanid, ref found, ref expected) => write!(f,
+                "Channel {} of sequence {} was given {} frames, but the sequence has {}", chanid, seqid, found, expected),
+            Error::InvalidFrameValue(ref chanid, ref frame, ref value, ref max) => write!(f,
+                "Frame {} of channel {} was given value {}, above the channel's maximum of {}", frame, chanid, value, max),
             Error::FixtureNotFound(ref fix_id) => write!(f,
                 "Fixture not found: {}", fix_id),
             Error::LayoutNotFound(ref layout_id) => write!(f,
                 "Layout not found: {}", layout_id),
+            Error::PermissionNotFound(ref permid) => write!(f,
+                "Permission not found: {}", permid),
+            Error::PermissionNotHeld(ref uid, ref permission) => write!(f,
+                "User {} does not have permission {}", uid, permission),
             Error::ProjectNotFound(ref proj_name) => write!(f,
                 "Project not found: {}", proj_name),
             Error::PublicKeyNotFound(ref key) => write!(f,
                 "PublicKey not found: {}", key),
+            Error::SectionNotFound(ref secid) => write!(f,
+                "Section not found: {}", secid),
             Error::SequenceNotFound(ref name) => write!(f,
                 "Sequence not found: '{}'", name),
             Error::UserNotFound => write!(f, "User not found"),
@@ -3,45 +3,62 @@
 use rustc_serialize::json;
 use std::path::Path;
 
+use audit;
 use dao::ProtonDao;
 use error::Error;
-use project_types::{FileLayout, FilePatch};
+use project_types::{Credentials, FileLayout, FilePatch, Layout, LayoutDetails, PermissionEnum};
 use utils;
 
 
-/// Patches a layout's channels based on a provided patch file
+/// Patches a layout's channels based on a provided patch file. Needs Administrate.
 pub fn patch_layout<P: AsRef<Path>, PD: ProtonDao> (
     dao: &PD,
+    credentials: &Credentials,
     layout_id: u32,
     patch_file_path: P
 ) -> Result<(), Error> {
 
+    // Check that the admin has sufficient privileges
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
+
     // Load patch file
     let patch_json = try!(utils::file_as_string(patch_file_path.as_ref()));
     let patch_file: FilePatch = try!(json::decode(&patch_json).map_err(Error::JsonDecode));
     
     // Make sure patch is valid
     try!(patch_file.validate());
-
-    // Apply patch
-    for patch in patch_file.patches.iter() {
-        match dao.patch_channel(layout_id, patch.internalChannel, patch.dmxChannel) {
-            Ok(1) => {},
-            Ok(0) => println!("No channels patched. vix: {}, dmx: {}", patch.internalChannel, patch.dmxChannel),
-            Ok(num_ch) => println!("Patched {} channels.", num_ch),
-            Err(e) => println!("Error: {}", e),
+    let internal_channels = patch_file.patches.iter()
+        .map(|patch| patch.internalChannel)
+        .collect::<Vec<u32>>();
+    let before = try!(describe_patched_channels(dao, layout_id, &internal_channels));
+
+    // Apply every patch and record it, or nothing
+    dao.transaction(|| {
+        for patch in patch_file.patches.iter() {
+            match try!(dao.patch_channel(layout_id, patch.internalChannel, patch.dmxChannel)) {
+                1 => {},
+                0 => println!("No channels patched. vix: {}, dmx: {}", patch.internalChannel, patch.dmxChannel),
+                num_ch => println!("Patched {} channels.", num_ch),
+            }
         }
-    }
 
-    Ok(())
+        let after = try!(describe_patched_channels(dao, layout_id, &internal_channels));
+        audit::record(dao, uid, "patch-layout", None, "layout", &layout_id.to_string(), Some(before), Some(after))
+    })
 }
 
-/// Creates a new layout
+/// Creates a new layout. Needs Administrate.
 pub fn new_layout<P: AsRef<Path>, PD: ProtonDao>(
     dao: &PD,
+    credentials: &Credentials,
     layout_path: P,
 ) -> Result<u32, Error> {
 
+    // Check that the admin has sufficient privileges
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
+
     // Load layout from file
     let layout_json = try!(utils::file_as_string(layout_path.as_ref()));
     let file_layout: FileLayout = try!(json::decode(&layout_json).map_err(Error::JsonDecode));
@@ -49,26 +66,36 @@ pub fn new_layout<P: AsRef<Path>, PD: ProtonDao>(
     // Make sure layout is valid
     try!(file_layout.validate());
 
-    // Create new channels and fixtures from layout and add to storage
-    let (_, fixtures) = try!(file_layout.create_new_parts(dao));
-
-    // Create new layout from fixtures
-    let fix_ids = fixtures.iter()
-        .map(|fixture| fixture.fixid)
-        .collect::<Vec<u32>>();
-    let layout = try!(dao.new_layout(&file_layout.layoutName, fix_ids));
+    // Add channels, fixtures and layout all or nothing
+    let layout = try!(dao.transaction(|| {
+        // Create new channels and fixtures from layout and add to storage
+        let (_, fixtures) = try!(file_layout.create_new_parts(dao));
+
+        // Create new layout from fixtures
+        let fix_ids = fixtures.iter()
+            .map(|fixture| fixture.fixid)
+            .collect::<Vec<u32>>();
+        let layout = try!(dao.new_layout(&file_layout.layoutName, fix_ids));
+        try!(audit::record(dao, uid, "new-layout", None, "layout", &layout.layout_id.to_string(),
+            None, Some(format!("'{}' with {} fixtures", layout.name, layout.fixtures.len()))));
+        Ok(layout)
+    }));
 
     // Return layout id
     Ok(layout.layout_id)
 }
 
-/// Set a layout's sequence
+/// Set a layout's sequence. Needs EditSequence on the sequence.
 pub fn set_sequence_layout<PD: ProtonDao>(
     dao: &PD,
+    credentials: &Credentials,
     layout_id: u32,
     seqid: u32
 ) -> Result<(), Error> {
 
+    // Check that the admin has sufficient privileges
+    let valid_permissions = vec![PermissionEnum::EditSequence(seqid)];
+    let uid = try!(utils::check_sequence_permission(dao, credentials, seqid, &valid_permissions));
 
     // Check that sequence exists
     let sequence = try!(dao.get_sequence(seqid));
@@ -79,8 +106,48 @@ pub fn set_sequence_layout<PD: ProtonDao>(
     // Check that new layout exists
     try!(dao.layout_exists(layout_id));
 
-    // Set sequence layout id
-    try!(dao.set_layout(seqid, layout_id));
+    // Set sequence layout id and record the change all or nothing
+    dao.transaction(|| {
+        try!(dao.set_layout(seqid, layout_id));
+        audit::record_for_sequence(dao, uid, "set-sequence-layout", seqid, "sequence", &seqid.to_string(),
+            Some(format!("layout {}", sequence.layout_id)), Some(format!("layout {}", layout_id)))
+    })
+}
 
-    Ok(())
+/// Lists layouts, optionally only those whose name contains name_filter
+pub fn list_layouts<PD: ProtonDao>(
+    dao: &PD,
+    name_filter: Option<&str>
+) -> Result<Vec<Layout>, Error> {
+    dao.list_layouts(name_filter)
+}
+
+/// Fetches a layout along with its fixtures and channels
+pub fn show_layout<PD: ProtonDao>(
+    dao: &PD,
+    layout_id: u32
+) -> Result<LayoutDetails, Error> {
+    let layout = try!(dao.get_layout(layout_id));
+    let fixtures = try!(dao.list_fixtures(layout_id));
+    let channels = try!(dao.list_channels(layout_id));
+    Ok(LayoutDetails {
+        layout: layout,
+        fixtures: fixtures,
+        channels: channels
+    })
+}
+
+/// Lists the layout's channels with the given internal numbers as
+/// internal -> DMX channel pairs, for the audit log
+fn describe_patched_channels<PD: ProtonDao>(
+    dao: &PD,
+    layout_id: u32,
+    internal_channels: &[u32]
+) -> Result<String, Error> {
+    let channels = try!(dao.list_channels(layout_id));
+    let patched = channels.iter()
+        .filter(|channel| internal_channels.contains(&channel.channel_internal))
+        .map(|channel| format!("{} -> {}", channel.channel_internal, channel.channel_dmx))
+        .collect::<Vec<String>>();
+    Ok(patched.join(", "))
 }
@@ -1,12 +1,20 @@
+extern crate ed25519_dalek;
 extern crate openssl;
 extern crate postgres;
 extern crate regex;
 extern crate rustc_serialize;
 extern crate sfml;
 
+mod audit;
+mod auth;
+mod bundle;
+mod checkout;
+mod data;
+mod keys;
 mod layout;
 mod permissions;
 mod project;
+mod section;
 mod sequence;
 mod user;
 pub mod dao;
@@ -15,8 +23,15 @@ pub mod project_types;
 pub mod utils;
 
 // Re-exports
+pub use audit::*;
+pub use auth::*;
+pub use bundle::*;
+pub use checkout::*;
+pub use data::*;
+pub use keys::*;
 pub use layout::*;
 pub use permissions::*;
 pub use project::*;
+pub use section::*;
 pub use sequence::*;
 pub use user::*;
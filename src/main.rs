@@ -3,14 +3,17 @@ extern crate rustc_serialize;
 extern crate proton_cli;
 extern crate docopt;
 
+use std::cmp;
 use std::env;
+use std::io;
+use std::iter;
 use std::path::Path;
 use rustc_serialize::json;
 use docopt::Docopt;
 
 use proton_cli::error::Error;
-use proton_cli::dao::{DaoPostgres, ProtonDao};
-use proton_cli::project_types::{PermissionEnum, Project, Sequence, User};
+use proton_cli::dao::{migration_description, ConnectionConfig, DaoFile, DaoPostgres, ProtonDao};
+use proton_cli::project_types::{AuditFilter, Credentials, LengthPolicy, Project, ResampleMode, Section, Sequence, User};
 use proton_cli::utils;
 
 
@@ -18,50 +21,96 @@ const USAGE: &'static str = "
 Command-line interface for Proton
 
 Usage:
-  ./proton_cli delete-sequence <admin-key> <seqid>
-  ./proton_cli get-layout-id <proj-name>
-  ./proton_cli get-playlist-data <proj-name>
-  ./proton_cli get-project <proj-name>
-  ./proton_cli get-sequence <seqid>
-  ./proton_cli get-user <public-key>
-  ./proton_cli insert-sequence <admin-key> <proj-name> <seqid> [<index>]
-  ./proton_cli list-permissions <uid>
-  ./proton_cli new-layout <layout-file>
-  ./proton_cli new-project <name> <layout-id>
-  ./proton_cli new-section <admin-key> <t_start> <t_end> <seqid> <fixid>..
-  ./proton_cli new-sequence <admin-key> <name> <music-file> <seq-duration> <layout-id>
-  ./proton_cli new-user <admin-key> <name>
-  ./proton_cli new-vixen-sequence <admin-key> <name> <music-file> <seq-duration> <frame-duration> <data-file> <layout-id>
-  ./proton_cli patch-layout <admin-key> <layout-id> <patch-file>
-  ./proton_cli remove-sequence <admin-key> <proj-name> <seqid>
-  ./proton_cli remove-user <admin-key> <name>
-  ./proton_cli set-permission <admin-key> (add | remove) <uid> Administrate
-  ./proton_cli set-permission <admin-key> (add | remove) <uid> EditSequence <target-sequence>
-  ./proton_cli set-permission <admin-key> (add | remove) <uid> EditSection <target-sequence> <target-section>
-  ./proton_cli set-permission <admin-key> (add | remove) <name> EditSeqSec <target-section>
-  ./proton_cli set-sequence-layout <admin-key> <seqid> <layout-id>
+  ./proton_cli audit-log [options] <admin-key>
+  ./proton_cli checkin-section [options] <admin-key> <secid> <file>
+  ./proton_cli checkout-section [options] <admin-key> <secid> <file>
+  ./proton_cli delete-section [options] <admin-key> <secid>
+  ./proton_cli delete-sequence [options] <admin-key> <seqid>
+  ./proton_cli export-project [options] <admin-key> <proj-name> <bundle>
+  ./proton_cli get-layout-id [options] <proj-name>
+  ./proton_cli get-playlist-data [options] <admin-key> <proj-name>
+  ./proton_cli get-project [options] <proj-name>
+  ./proton_cli get-section [options] <secid>
+  ./proton_cli get-sequence [options] <seqid>
+  ./proton_cli get-user [options] <admin-key> <public-key>
+  ./proton_cli import-project [options] <admin-key> <bundle> [<new-name>]
+  ./proton_cli init-db [options]
+  ./proton_cli keygen [options] <key-file>
+  ./proton_cli insert-sequence [options] <admin-key> <proj-name> <seqid> [<index>]
+  ./proton_cli list-layouts [options]
+  ./proton_cli list-permissions [options] <admin-key> <uid>
+  ./proton_cli list-projects [options]
+  ./proton_cli list-sections [options] <seqid>
+  ./proton_cli list-sequences [options]
+  ./proton_cli list-users [options]
+  ./proton_cli migrate [options] [--allow-new-admin]
+  ./proton_cli new-admin [options] <name> <public-key>
+  ./proton_cli new-layout [options] <admin-key> <layout-file>
+  ./proton_cli new-project [options] <admin-key> <name> <layout-id> <root-public-key>
+  ./proton_cli new-section [options] <admin-key> <t_start> <t_end> <seqid> <fixid>...
+  ./proton_cli new-sequence [options] <admin-key> <name> <music-file> <seq-duration> <layout-id>
+  ./proton_cli new-user [options] <admin-key> <name> <public-key>
+  ./proton_cli new-vixen-sequence [options] <admin-key> <name> <music-file> <seq-duration> <frame-duration> <data-file> <layout-id>
+  ./proton_cli patch-layout [options] <admin-key> <layout-id> <patch-file>
+  ./proton_cli recover-root-key [options] <admin-key> <proj-name> <public-key>
+  ./proton_cli remove-sequence [options] <admin-key> <proj-name> <seqid>
+  ./proton_cli remove-user [options] <admin-key> <name>
+  ./proton_cli reset-db [options]
+  ./proton_cli resample-sequence [options] <admin-key> <seqid> <frame-duration>
+  ./proton_cli revoke-key [options] <admin-key> <uid>
+  ./proton_cli rotate-key [options] <admin-key> <uid> <public-key>
+  ./proton_cli set-permission [options] <admin-key> (add | remove) <uid> (Administrate | Admin)
+  ./proton_cli set-permission [options] <admin-key> (add | remove) <uid> (Viewer | Sequencer | Operator)
+  ./proton_cli set-permission [options] <admin-key> (add | remove) <uid> EditSequence <target-sequence>
+  ./proton_cli set-permission [options] <admin-key> (add | remove) <uid> EditSection <target-sequence> <target-section>
+  ./proton_cli set-sequence-layout [options] <admin-key> <seqid> <layout-id>
+  ./proton_cli show-layout [options] <layout-id>
   ./proton_cli (-h | --help)
 
 Options:
-  -h --help     Show this screen
+  -h --help          Show this screen
+  --allow-new-admin  Let new-admin register an administrator once, for a database without one
+  --db <target>      Postgres database to use: a postgres:// URL or a profile from ~/.proton_conn.cfg
+  --dry-run          Only show what would be deleted
+  --entity <entity>  Only show audit entries for one kind of entity (layout, project, section, sequence or user)
+  --force            Delete a sequence even if it is in a playlist
+  --name <filter>    Only list entries whose name contains <filter> (ignoring case)
+  --pad              Pad Vixen channel data shorter than the sequence with zeros
+  --project <name>   Grant or revoke a permission within one project, or only show its audit entries
+  --json             Print listings as JSON instead of a table
+  --mode <mode>      How to resample: nearest, hold or linear [default: linear]
+  --music-dir <dir>  Directory holding sequence music files [default: Music]
+  --since <time>     Only show audit entries from <time> on (seconds since 1970 or YYYY-MM-DD[THH:MM[:SS]], UTC)
+  --target <id>      Only show audit entries for one layout id, project name, seqid or uid
+  --truncate         Cut Vixen channel data longer than the sequence short
+  --uid              Treat remove-user's <name> as a uid
+  --until <time>     Only show audit entries up to <time>
+  --user <uid>       Only show audit entries made by one user
 ";
 
-// Docopt arguments are mapped to this struct
+// Docopt arguments are mapped to this struct.
+// Permission names are commands too, so their fields keep docopt's capitals.
 #[derive(Debug, RustcDecodable)]
+#[allow(non_snake_case)]
 struct Args {
 	arg_admin_key: Option<String>,
+	arg_bundle: Option<String>,
 	arg_data_file: Option<String>,
-	arg_fixid: Option<u32>,
+	arg_file: Option<String>,
+	arg_fixid: Vec<u32>,
 	arg_frame_duration: Option<u32>,
 	arg_index: Option<u32>,
 	arg_layout_id: Option<u32>,
+	arg_key_file: Option<String>,
 	arg_layout_file: Option<String>,
 	arg_music_file: Option<String>,
 	arg_name: Option<String>,
+	arg_new_name: Option<String>,
 	arg_patch_file: Option<String>,
 	arg_proj_name: Option<String>,
 	arg_public_key: Option<String>,
 	arg_root_public_key: Option<String>,
+	arg_secid: Option<u32>,
 	arg_seqid: Option<u32>,
 	arg_seq_duration: Option<u32>,
 	arg_t_start: Option<u32>,
@@ -69,18 +118,47 @@ struct Args {
 	arg_target_sequence: Option<u32>,
 	arg_target_section: Option<u32>,
 	arg_uid: Option<u32>,
+	cmd_add: bool,
+	cmd_Admin: bool,
+	cmd_Administrate: bool,
+	cmd_EditSection: bool,
+	cmd_EditSequence: bool,
+	cmd_Operator: bool,
+	cmd_Sequencer: bool,
+	cmd_Viewer: bool,
+	flag_allow_new_admin: bool,
+	flag_db: Option<String>,
+	flag_dry_run: bool,
+	flag_entity: Option<String>,
+	flag_force: bool,
+	flag_json: bool,
+	flag_mode: String,
+	flag_music_dir: String,
+	flag_name: Option<String>,
+	flag_pad: bool,
+	flag_project: Option<String>,
+	flag_since: Option<String>,
+	flag_target: Option<String>,
+	flag_truncate: bool,
+	flag_uid: bool,
+	flag_until: Option<String>,
+	flag_user: Option<u32>,
 }
 
 // Generic return type of all functions that are called based on cli commands
 enum ProtonReturn {
 	LayoutId(u32),
+	Listing(String),
 	NoReturn,
-	PlaylistData(String),
+	PlaylistDataWritten,
 	Project(Project),
-	PublicKey(String),
+	SchemaVersion(u32),
 	Sequence(Sequence),
+	Section(Section),
+	SectionId(u32),
 	SequenceId(u32),
 	User(User),
+	UserId(u32),
 }
 
 // Entry point
@@ -93,22 +171,160 @@ fn main() {
 	// Below unwrap()'s are safe within Docopt's usage rules
 
 	// Create data access object for data retrieval from database, file, etc.
-	// Use only Postgres for now
-	let psql_dao = match DaoPostgres::new() {
-		Ok(d) => d,
-		Err(e) => panic!("Failed to retrieve DAO: {}", e)
+	// Postgres is the default; PROTON_DB_FILE selects a single-file project database.
+	// Schema commands skip the schema version check; keygen needs no database at all.
+	let command_name = get_command_name();
+	let result = match command_name.as_ref() {
+		"keygen" => run_keygen(args),
+		"init-db" | "migrate" | "reset-db" => run_schema_command(&command_name, args),
+		_ => run_with_dao(&command_name, args),
 	};
 
-	// Every proton command is mapped to a specific function that should be run
-	let command: fn(Args, DaoPostgres) -> Result<ProtonReturn, Error> = match env::args().nth(1).unwrap().as_ref() {
+	// Handle the command's return
+	match result {
+		Ok(ret) => match ret {
+			ProtonReturn::LayoutId(lid) => println!("Layout id: {}", lid),
+			ProtonReturn::Listing(listing) => println!("{}", listing),
+			ProtonReturn::NoReturn => println!("Worked!"),
+			ProtonReturn::PlaylistDataWritten => println!(),
+			ProtonReturn::Project(project) => println!("Project: {:?}", project),
+			ProtonReturn::SchemaVersion(version) => println!("Schema version: {}", version),
+			ProtonReturn::Section(section) => println!("Section: {:?}", section),
+			ProtonReturn::SectionId(secid) => println!("Section id: {}", secid),
+			ProtonReturn::Sequence(seq) => println!("Sequence: {:?}", seq),
+			ProtonReturn::SequenceId(sid) => println!("Sequence id: {}", sid),
+			ProtonReturn::User(user) => println!("User: {:?}", user),
+			ProtonReturn::UserId(uid) => println!("User id: {}", uid)
+		},
+		Err(e) => {
+			println!("Error: {:?}", e.to_string());
+			std::process::exit(1);
+		}
+	};
+}
+
+/// The command is the first argument that isn't an option or an option's value
+fn get_command_name() -> String {
+	let mut cli_args = env::args().skip(1);
+	while let Some(arg) = cli_args.next() {
+		let takes_value = ["--db", "--entity", "--mode", "--music-dir", "--name", "--project", "--since", "--target", "--until", "--user"];
+		if takes_value.contains(&arg.as_str()) {
+			let _ = cli_args.next();
+		} else if !arg.starts_with("-") {
+			return arg;
+		}
+	}
+	panic!("Invalid first argument")
+}
+
+/// Proves the caller holds the private key at admin_key by signing a challenge
+/// the database issues for this command
+fn sign_in<PD: ProtonDao>(dao: &PD, admin_key: &str) -> Result<Credentials, Error> {
+	let challenge = try!(proton_cli::new_challenge(dao));
+	proton_cli::sign_challenge(Path::new(admin_key), &challenge)
+}
+
+/// Creates the selected data access object and runs the command with it.
+/// An explicit --db always means Postgres.
+fn run_with_dao(command_name: &str, args: Args) -> Result<ProtonReturn, Error> {
+	if let Some(db_file) = get_db_file(&args) {
+		let dao = try!(DaoFile::open(&db_file));
+		return run_command(command_name, args, dao);
+	}
+	let config = try!(ConnectionConfig::load(args.flag_db.as_ref().map(|db| db.as_str())));
+	let dao = try!(DaoPostgres::new(&config));
+	run_command(command_name, args, dao)
+}
+
+/// The single-file project database to use instead of Postgres, if any
+fn get_db_file(args: &Args) -> Option<String> {
+	match args.flag_db {
+		Some(_) => None,
+		None => env::var("PROTON_DB_FILE").ok(),
+	}
+}
+
+/// keygen <key-file>
+fn run_keygen(args: Args) -> Result<ProtonReturn, Error> {
+	let key_file = args.arg_key_file.unwrap();
+	let public_key_path = try!(proton_cli::generate_key_pair(Path::new(&key_file)));
+	Ok(ProtonReturn::Listing(format!(
+		"Private key: {} (keep it secret)\nPublic key: {}",
+		key_file,
+		public_key_path.display())))
+}
+
+/// init-db
+/// migrate [--allow-new-admin]
+/// reset-db
+/// Project database files have no schema; migrate only opens new-admin in them.
+fn run_schema_command(command_name: &str, args: Args) -> Result<ProtonReturn, Error> {
+	if let (Some(db_file), "migrate") = (get_db_file(&args), command_name) {
+		let dao = try!(DaoFile::open(&db_file));
+		if args.flag_allow_new_admin {
+			try!(proton_cli::allow_new_admin(&dao));
+		}
+		return Ok(ProtonReturn::NoReturn);
+	}
+
+	let config = try!(ConnectionConfig::load(args.flag_db.as_ref().map(|db| db.as_str())));
+	let dao = try!(DaoPostgres::new_unchecked(&config));
+	match command_name {
+		"init-db" => {
+			let applied = try!(dao.init_db());
+			print_migrations(&applied);
+			Ok(ProtonReturn::SchemaVersion(applied.last().cloned().unwrap_or(0)))
+		},
+		"migrate" => {
+			let applied = try!(dao.migrate());
+			if applied.is_empty() {
+				println!("Schema already up to date");
+			}
+			print_migrations(&applied);
+			if args.flag_allow_new_admin {
+				try!(proton_cli::allow_new_admin(&dao));
+			}
+			Ok(ProtonReturn::NoReturn)
+		},
+		"reset-db" => {
+			try!(dao.reset_db());
+			Ok(ProtonReturn::NoReturn)
+		},
+		_ => panic!("Invalid first argument"),
+	}
+}
+
+/// Lists the migrations a schema command applied
+fn print_migrations(applied: &[u32]) {
+	for version in applied {
+		println!("Applied migration {}: {}", version, migration_description(*version).unwrap_or(""));
+	}
+}
+
+/// Every proton command is mapped to a specific function that should be run
+fn run_command<PD: ProtonDao>(command_name: &str, args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let command: fn(Args, PD) -> Result<ProtonReturn, Error> = match command_name {
+		"audit-log" => run_audit_log,
+		"checkin-section" => run_checkin_section,
+		"checkout-section" => run_checkout_section,
+		"delete-section" => run_delete_section,
 		"delete-sequence" => run_delete_sequence,
+		"export-project" => run_export_project,
 		"get-layout-id" => run_get_layout_id,
 		"get-playlist-data" => run_get_playlist_data,
 		"get-project" => run_get_project,
+		"get-section" => run_get_section,
 		"get-sequence" => run_get_sequence,
 		"get-user" => run_get_user,
+		"import-project" => run_import_project,
 		"insert-sequence" => run_insert_sequence,
+		"list-layouts" => run_list_layouts,
 		"list-permissions" => run_list_permissions,
+		"list-projects" => run_list_projects,
+		"list-sections" => run_list_sections,
+		"list-sequences" => run_list_sequences,
+		"list-users" => run_list_users,
+		"new-admin" => run_new_admin,
 		"new-layout" => run_new_layout,
 		"new-project" => run_new_project,
 		"new-section" => run_new_section,
@@ -116,39 +332,132 @@ fn main() {
 		"new-user" => run_new_user,
 		"new-vixen-sequence" => run_new_vixen_sequence,
 		"patch-layout" => run_patch_layout,
+		"recover-root-key" => run_recover_root_key,
 		"remove-sequence" => run_remove_sequence,
 		"remove-user" => run_remove_user,
+		"resample-sequence" => run_resample_sequence,
+		"revoke-key" => run_revoke_key,
+		"rotate-key" => run_rotate_key,
 		"set-permission" => run_set_permission,
 		"set-sequence-layout" => run_set_sequence_layout,
+		"show-layout" => run_show_layout,
 		_ => panic!("Invalid first argument"),
 	};
+	command(args, dao)
+}
 
-	// Run the appropriate command and handle its return
-	let result = command(args, psql_dao);
-	match result {
-		Ok(ret) => match ret {
-			ProtonReturn::LayoutId(lid) => println!("Layout id: {}", lid),
-			ProtonReturn::NoReturn => println!("Worked!"),
-			ProtonReturn::PlaylistData(data) => println!("PLAYLIST_DATA:::{}", data),
-			ProtonReturn::Project(project) => println!("Project: {:?}", project),
-			ProtonReturn::PublicKey(s) => println!("PubKey: {}", s),
-			ProtonReturn::Sequence(seq) => println!("Sequence: {:?}", seq),
-			ProtonReturn::SequenceId(sid) => println!("Sequence id: {}", sid),
-			ProtonReturn::User(user) => println!("User: {:?}", user)
-		},
-		Err(e) => {
-			println!("Error: {:?}", e.to_string());
-			std::process::exit(1);
-		}
+/// audit-log <admin-key> [--user <uid>] [--project <name>] [--entity <entity>] [--target <id>]
+///   [--since <time>] [--until <time>] [--json]
+fn run_audit_log<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let since = match args.flag_since {
+		Some(ref time) => Some(try!(utils::parse_time(time))),
+		None => None,
+	};
+	let until = match args.flag_until {
+		Some(ref time) => Some(try!(utils::parse_time(time))),
+		None => None,
 	};
+	let filter = AuditFilter {
+		uid: args.flag_user,
+		project: args.flag_project.clone(),
+		entity: args.flag_entity.clone(),
+		target: args.flag_target.clone(),
+		since: since,
+		until: until
+	};
+
+	let entries = try!(proton_cli::audit_log(&dao, &credentials, &filter));
+	if args.flag_json {
+		return Ok(ProtonReturn::Listing(json::as_pretty_json(&entries).to_string()));
+	}
+	let rows = entries.iter()
+		.map(|entry| vec![
+			utils::format_time(entry.time),
+			entry.uid.to_string(),
+			entry.command.to_owned(),
+			entry.project.clone().unwrap_or_else(|| "-".to_owned()),
+			format!("{} {}", entry.entity, entry.target),
+			entry.before.clone().unwrap_or_else(|| "-".to_owned()),
+			entry.after.clone().unwrap_or_else(|| "-".to_owned())])
+		.collect::<Vec<Vec<String>>>();
+	Ok(ProtonReturn::Listing(format_table(
+		&["TIME (UTC)", "UID", "COMMAND", "PROJECT", "TARGET", "BEFORE", "AFTER"], rows)))
 }
 
+/// checkin-section <admin-key> <secid> <file>
+fn run_checkin_section<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let secid = args.arg_secid.unwrap();
+	let file = args.arg_file.unwrap();
+	let changed = try!(proton_cli::checkin_section(&dao, &credentials, secid, Path::new(&file)));
+	Ok(ProtonReturn::Listing(format!("Checked in section {}: {} channels changed", secid, changed)))
+}
+
+/// checkout-section <admin-key> <secid> <file>
+fn run_checkout_section<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let secid = args.arg_secid.unwrap();
+	let file = args.arg_file.unwrap();
+	let checkout = try!(proton_cli::checkout_section(&dao, &credentials, secid, Path::new(&file)));
+	Ok(ProtonReturn::Listing(format!(
+		"Checked out section {} to {}: {} frames of {} channels, starting at frame {}",
+		secid,
+		file,
+		checkout.num_frames,
+		checkout.channels.len(),
+		checkout.first_frame)))
+}
+
+/// delete-section <admin-key> <secid>
+fn run_delete_section<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let secid = args.arg_secid.unwrap();
+	let section = try!(proton_cli::delete_section(&dao, &credentials, secid));
+	Ok(ProtonReturn::Listing(format!("Deleted section {} of sequence {}", section.secid, section.seqid)))
+}
+
+/// delete-sequence <admin-key> <seqid> [--force] [--dry-run]
 fn run_delete_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let seqid = args.arg_seqid.unwrap();
 	
-	try!(proton_cli::delete_sequence(&dao, &admin_key_path, seqid));
+	let deletion = try!(proton_cli::delete_sequence(
+		&dao,
+		&credentials,
+		seqid,
+		args.flag_force,
+		args.flag_dry_run));
+
+	let (verb, remove_verb) = if args.flag_dry_run {
+		("Would delete", "Would remove")
+	} else {
+		("Deleted", "Removed")
+	};
+	let mut lines = vec![
+		format!("{} sequence {} '{}'", verb, deletion.sequence.seqid, deletion.sequence.name),
+		format!("{} {} channel data rows", verb, deletion.channel_data),
+		format!("{} {} sections", verb, deletion.sections),
+		format!("{} {} permissions", verb, deletion.permissions),
+	];
+	for proj_name in &deletion.projects {
+		lines.push(format!("{} it from the playlist of '{}'", remove_verb, proj_name));
+	}
+	Ok(ProtonReturn::Listing(lines.join("\n")))
+}
+
+/// export-project <admin-key> <proj-name> <bundle> [--music-dir <dir>]
+fn run_export_project<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let proj_name = args.arg_proj_name.unwrap();
+	let bundle = args.arg_bundle.unwrap();
+	try!(proton_cli::export_project(&dao, &credentials, &proj_name, Path::new(&bundle), Path::new(&args.flag_music_dir)));
 	Ok(ProtonReturn::NoReturn)
 }
 
@@ -159,11 +468,17 @@ fn run_get_layout_id<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn,
 	Ok(ProtonReturn::LayoutId(layout_id))
 }
 
-/// get-playlist-data <proj-name>
+/// get-playlist-data <admin-key> <proj-name>
 fn run_get_playlist_data<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let proj_name = args.arg_proj_name.unwrap();
-	let data = try!(proton_cli::get_playlist_data(&dao, &proj_name));
-	Ok(ProtonReturn::PlaylistData(data))
+
+	// Stream straight to stdout instead of building the whole playlist in memory
+	let stdout = io::stdout();
+	let mut out = stdout.lock();
+	try!(proton_cli::write_playlist_data(&dao, &credentials, &proj_name, b"PLAYLIST_DATA:::", &mut out));
+	Ok(ProtonReturn::PlaylistDataWritten)
 }
 
 /// get-project <proj-name>
@@ -173,6 +488,13 @@ fn run_get_project<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, E
 	Ok(ProtonReturn::Project(project))
 }
 
+/// get-section <secid>
+fn run_get_section<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let secid = args.arg_secid.unwrap();
+	let section = try!(proton_cli::get_section(&dao, secid));
+	Ok(ProtonReturn::Section(section))
+}
+
 /// get-sequence <seqid>
 fn run_get_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let seqid = args.arg_seqid.unwrap();
@@ -180,81 +502,196 @@ fn run_get_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn,
 	Ok(ProtonReturn::Sequence(sequence))
 }
 
-/// get-user <public-key>
+/// get-user <admin-key> <public-key>
 fn run_get_user<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let public_key = args.arg_public_key.unwrap();
 	let public_key_path = Path::new(&public_key);
-	let user = try!(proton_cli::get_user(&dao, &public_key_path));
+	let user = try!(proton_cli::get_user(&dao, &credentials, &public_key_path));
 	Ok(ProtonReturn::User(user))
 }
 
+/// import-project <admin-key> <bundle> [<new-name>] [--music-dir <dir>]
+fn run_import_project<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let bundle = args.arg_bundle.unwrap();
+	let report = try!(proton_cli::import_project(
+		&dao,
+		&credentials,
+		Path::new(&bundle),
+		args.arg_new_name.as_ref().map(|name| name.as_str()),
+		Path::new(&args.flag_music_dir)));
+
+	let mut lines = vec![format!("Imported project '{}'", report.project_name)];
+	for (old_id, new_id) in &report.layout_ids {
+		lines.push(format!("Layout {} -> {}", old_id, new_id));
+	}
+	for (old_id, new_id) in &report.sequence_ids {
+		lines.push(format!("Sequence {} -> {}", old_id, new_id));
+	}
+	for (old_id, new_id) in &report.user_ids {
+		lines.push(format!("User {} -> {}", old_id, new_id));
+	}
+	lines.extend(report.notes.iter().map(|note| format!("Note: {}", note)));
+	Ok(ProtonReturn::Listing(lines.join("\n")))
+}
+
 /// insert-sequence <admin-key> <proj-name> <seqid> [<index>]
 fn run_insert_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let proj_name = args.arg_proj_name.unwrap();
 	let seqid = args.arg_seqid.unwrap();
 	let index = args.arg_index;
 
-	let valid_permissions = vec![PermissionEnum::Administrate];
-	let _ = try!(utils::check_valid_permission(
-		&dao,
-		admin_key_path,
-		&valid_permissions));
-	
-	try!(proton_cli::insert_sequence(&dao, &proj_name, seqid, index));
+	try!(proton_cli::insert_sequence(&dao, &credentials, &proj_name, seqid, index));
 	Ok(ProtonReturn::NoReturn)
 }
 
-/// list-permissions <uid>
+/// list-layouts [--name <filter>] [--json]
+fn run_list_layouts<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let layouts = try!(proton_cli::list_layouts(&dao, args.flag_name.as_ref().map(|name| name.as_str())));
+	if args.flag_json {
+		return Ok(ProtonReturn::Listing(json::as_pretty_json(&layouts).to_string()));
+	}
+	let rows = layouts.iter()
+		.map(|layout| vec![
+			layout.layout_id.to_string(),
+			layout.name.to_owned(),
+			layout.fixtures.len().to_string()])
+		.collect::<Vec<Vec<String>>>();
+	Ok(ProtonReturn::Listing(format_table(&["LAYOUT ID", "NAME", "FIXTURES"], rows)))
+}
+
+/// list-permissions <admin-key> <uid>
 fn run_list_permissions<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let uid = args.arg_uid.unwrap();
-	let permissions = try!(proton_cli::get_permissions(&dao, uid));
-	println!("{}", json::as_pretty_json(&permissions));
-	Ok(ProtonReturn::NoReturn)
+	let permissions = try!(proton_cli::get_permissions(&dao, &credentials, uid));
+	Ok(ProtonReturn::Listing(json::as_pretty_json(&permissions).to_string()))
+}
+
+/// list-projects [--name <filter>] [--json]
+fn run_list_projects<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let projects = try!(proton_cli::list_projects(&dao, args.flag_name.as_ref().map(|name| name.as_str())));
+	if args.flag_json {
+		return Ok(ProtonReturn::Listing(json::as_pretty_json(&projects).to_string()));
+	}
+	let rows = projects.iter()
+		.map(|project| vec![
+			project.name.to_owned(),
+			project.layout_id.to_string(),
+			project.playlist.len().to_string()])
+		.collect::<Vec<Vec<String>>>();
+	Ok(ProtonReturn::Listing(format_table(&["NAME", "LAYOUT ID", "SEQUENCES"], rows)))
 }
 
-/// new-layout <layout-file>
+/// list-sections <seqid> [--json]
+fn run_list_sections<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let seqid = args.arg_seqid.unwrap();
+	let sections = try!(proton_cli::list_sections(&dao, seqid));
+	if args.flag_json {
+		return Ok(ProtonReturn::Listing(json::as_pretty_json(&sections).to_string()));
+	}
+	let rows = sections.iter()
+		.map(|section| vec![
+			section.secid.to_string(),
+			section.t_start.to_string(),
+			section.t_end.to_string(),
+			format!("{:?}", section.fixtures)])
+		.collect::<Vec<Vec<String>>>();
+	Ok(ProtonReturn::Listing(format_table(&["SECID", "START MS", "END MS", "FIXTURES"], rows)))
+}
+
+/// list-sequences [--name <filter>] [--json]
+fn run_list_sequences<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let sequences = try!(proton_cli::list_sequences(&dao, args.flag_name.as_ref().map(|name| name.as_str())));
+	if args.flag_json {
+		return Ok(ProtonReturn::Listing(json::as_pretty_json(&sequences).to_string()));
+	}
+	let rows = sequences.iter()
+		.map(|sequence| vec![
+			sequence.seqid.to_string(),
+			sequence.name.to_owned(),
+			sequence.layout_id.to_string(),
+			sequence.num_frames.to_string(),
+			sequence.frame_duration_ms.to_string(),
+			sequence.music_file_name.to_owned()])
+		.collect::<Vec<Vec<String>>>();
+	Ok(ProtonReturn::Listing(format_table(
+		&["SEQID", "NAME", "LAYOUT ID", "FRAMES", "FRAME MS", "MUSIC"], rows)))
+}
+
+/// list-users [--name <filter>] [--json]
+fn run_list_users<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let users = try!(proton_cli::list_users(&dao, args.flag_name.as_ref().map(|name| name.as_str())));
+	if args.flag_json {
+		return Ok(ProtonReturn::Listing(json::as_pretty_json(&users).to_string()));
+	}
+	let rows = users.iter()
+		.map(|user| vec![user.uid.to_string(), user.name.to_owned()])
+		.collect::<Vec<Vec<String>>>();
+	Ok(ProtonReturn::Listing(format_table(&["UID", "NAME"], rows)))
+}
+
+/// new-admin <name> <public-key>
+fn run_new_admin<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let name = args.arg_name.unwrap();
+	let public_key = args.arg_public_key.unwrap();
+	let public_key = try!(utils::file_as_string(Path::new(&public_key)));
+	let uid = try!(proton_cli::new_admin(&dao, &name, &public_key));
+	Ok(ProtonReturn::UserId(uid))
+}
+
+/// new-layout <admin-key> <layout-file>
 fn run_new_layout<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let layout_file = args.arg_layout_file.unwrap();
 	let layout_file_path = Path::new(&layout_file);
-	let layout_id = try!(proton_cli::new_layout(&dao, &layout_file_path));
+	let layout_id = try!(proton_cli::new_layout(&dao, &credentials, &layout_file_path));
 	Ok(ProtonReturn::LayoutId(layout_id))
 }
 
-/// new-project <name> <layout-id>
+/// new-project <admin-key> <name> <layout-id> <root-public-key>
 fn run_new_project<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let name = args.arg_name.unwrap();
 	let layout_id = args.arg_layout_id.unwrap();
-	let root_pub_key = try!(proton_cli::new_project(&dao, &name, layout_id));
-	Ok(ProtonReturn::PublicKey(root_pub_key))
+	let root_public_key = args.arg_root_public_key.unwrap();
+	let root_public_key = try!(utils::file_as_string(Path::new(&root_public_key)));
+	let root_uid = try!(proton_cli::new_project(&dao, &credentials, &name, layout_id, &root_public_key));
+	Ok(ProtonReturn::UserId(root_uid))
 }
 
-/// new-section <admin-key> <t_start> <t_end> <seqid> <fixid>..
-#[allow(unused_variables)]
+/// new-section <admin-key> <t_start> <t_end> <seqid> <fixid>...
 fn run_new_section<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
-	Err(Error::TodoErr)
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let seqid = args.arg_seqid.unwrap();
+	let t_start = args.arg_t_start.unwrap();
+	let t_end = args.arg_t_end.unwrap();
+	let secid = try!(proton_cli::new_section(&dao, &credentials, seqid, t_start, t_end, args.arg_fixid));
+	Ok(ProtonReturn::SectionId(secid))
 }
 
 /// new-sequence <admin-key> <name> <music-file> <seq-duration> <layout-id>
 fn run_new_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let name = args.arg_name.unwrap();
 	let music_file = args.arg_music_file.unwrap();
 	let music_file_path = Path::new(&music_file);
 	let seq_duration = args.arg_seq_duration.unwrap();
 	let layout_id = args.arg_layout_id;
 
-	// Check that the admin has sufficient privileges
-	let valid_permissions = vec![PermissionEnum::Administrate];
-	let _ = try!(utils::check_valid_permission(
-		&dao,
-		admin_key_path,
-		&valid_permissions));
-
 	let seqid = try!(proton_cli::new_sequence(
 		&dao,
+		&credentials,
 		&name,
 		&music_file_path,
 		seq_duration,
@@ -263,27 +700,22 @@ fn run_new_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn,
 	Ok(ProtonReturn::SequenceId(seqid))
 }
 
-/// new-user <admin-key> <name>
+/// new-user <admin-key> <name> <public-key>
 fn run_new_user<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let name = args.arg_name.unwrap();
+	let public_key = args.arg_public_key.unwrap();
+	let public_key = try!(utils::file_as_string(Path::new(&public_key)));
 
-	// See if admin has permission to add user
-	let valid_permissions = vec![PermissionEnum::Administrate];
-	let _ = try!(utils::check_valid_permission(
-		&dao,
-		admin_key_path,
-		&valid_permissions));
-
-	let public_key = try!(proton_cli::new_user(&dao, &name));
-	Ok(ProtonReturn::PublicKey(public_key))
+	let uid = try!(proton_cli::new_user(&dao, &credentials, &name, &public_key));
+	Ok(ProtonReturn::UserId(uid))
 }
 
 /// new-vixen-sequence <admin-key> <name> <music-file> <seq-duration> <frame-duration> <data-file> <layout-id>
 fn run_new_vixen_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let name = args.arg_name.unwrap();
 	let music_file = args.arg_music_file.unwrap();
 	let music_file_path = Path::new(&music_file);
@@ -299,41 +731,30 @@ fn run_new_vixen_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonRe
 		},
 	};
 
-	// Check that the admin has sufficient privileges
-	let valid_permissions = vec![PermissionEnum::Administrate];
-	let _ = try!(utils::check_valid_permission(
-		&dao,
-		admin_key_path,
-		&valid_permissions));
-
 	let seqid = try!(proton_cli::new_vixen_sequence(
 		&dao,
+		&credentials,
 		&name,
 		&music_file_path,
 		seq_duration,
 		frame_duration,
 		&data_file_path,
-		layout_id));
+		layout_id,
+		LengthPolicy::from_flags(args.flag_pad, args.flag_truncate)));
 	Ok(ProtonReturn::SequenceId(seqid))
 }
 
 /// patch-layout <admin-key> <layout-id> <patch-file>
 fn run_patch_layout<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let layout_id = args.arg_layout_id.unwrap();
 	let patch_file = args.arg_patch_file.unwrap();
 	let patch_file_path = Path::new(&patch_file);
 
-	// Check that the admin has sufficient privileges
-	let valid_permissions = vec![PermissionEnum::Administrate];
-	let _ = try!(utils::check_valid_permission(
-		&dao,
-		admin_key_path,
-		&valid_permissions));
-
 	try!(proton_cli::patch_layout(
 		&dao,
+		&credentials,
 		layout_id,
 		&patch_file_path));
 	
@@ -343,47 +764,111 @@ fn run_patch_layout<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn,
 /// remove-sequence <admin-key> <proj-name> <seqid>
 fn run_remove_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let proj_name = args.arg_proj_name.unwrap();
 	let seqid = args.arg_seqid.unwrap();
 
-	// Check that the admin has sufficient privileges
-	let valid_permissions = vec![PermissionEnum::Administrate, PermissionEnum::EditSequence(seqid)];
-	let _ = try!(utils::check_valid_permission(
-		&dao,
-		admin_key_path,
-		&valid_permissions));
-
-	try!(proton_cli::remove_sequence(&dao, &proj_name, seqid));
+	try!(proton_cli::remove_sequence(&dao, &credentials, &proj_name, seqid));
 	Ok(ProtonReturn::NoReturn)
 }
 
-/// remove-user <admin-key> <uid>
+/// recover-root-key <admin-key> <proj-name> <public-key>
+fn run_recover_root_key<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let proj_name = args.arg_proj_name.unwrap();
+	let public_key = args.arg_public_key.unwrap();
+	let public_key = try!(utils::file_as_string(Path::new(&public_key)));
+	let root_uid = try!(proton_cli::recover_root_key(&dao, &credentials, &proj_name, &public_key));
+	Ok(ProtonReturn::UserId(root_uid))
+}
+
+/// remove-user <admin-key> <name> [--uid]
 fn run_remove_user<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let name = args.arg_name.unwrap();
-	try!(proton_cli::remove_user(&dao, &name));
+	let uid = if args.flag_uid {
+		try!(name.parse::<u32>().map_err(|_| Error::UserNotFound))
+	} else {
+		try!(proton_cli::find_user(&dao, &name)).uid
+	};
+
+	let user = try!(proton_cli::remove_user(&dao, &credentials, uid));
+	Ok(ProtonReturn::Listing(format!("Removed user {} '{}'", user.uid, user.name)))
+}
+
+/// resample-sequence <admin-key> <seqid> <frame-duration> [--mode <mode>]
+fn run_resample_sequence<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let seqid = args.arg_seqid.unwrap();
+	let frame_duration = args.arg_frame_duration.unwrap();
+	let mode = try!(ResampleMode::from_name(&args.flag_mode));
+
+	let sequence = try!(proton_cli::resample_sequence(
+		&dao,
+		&credentials,
+		seqid,
+		frame_duration,
+		mode));
+	Ok(ProtonReturn::Sequence(sequence))
+}
+
+/// revoke-key <admin-key> <uid>
+fn run_revoke_key<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let uid = args.arg_uid.unwrap();
+	try!(proton_cli::revoke_key(&dao, &credentials, uid));
+	Ok(ProtonReturn::NoReturn)
+}
+
+/// rotate-key <admin-key> <uid> <public-key>
+fn run_rotate_key<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let admin_key = args.arg_admin_key.unwrap();
+	let credentials = try!(sign_in(&dao, &admin_key));
+	let uid = args.arg_uid.unwrap();
+	let public_key = args.arg_public_key.unwrap();
+	let public_key = try!(utils::file_as_string(Path::new(&public_key)));
+	try!(proton_cli::rotate_key(&dao, &credentials, uid, &public_key));
 	Ok(ProtonReturn::NoReturn)
 }
 
-/// set-permission <admin-key> (add | remove) <uid> Administrate
+/// set-permission <admin-key> (add | remove) <uid> (Administrate | Admin)
+/// set-permission <admin-key> (add | remove) <uid> (Viewer | Sequencer | Operator)
 /// set-permission <admin-key> (add | remove) <uid> EditSequence <target-sequence>
 /// set-permission <admin-key> (add | remove) <uid> EditSection <target-sequence> <target-section>
-/// set-permission <admin-key> (add | remove) <name> EditSeqSec <target-section>
 fn run_set_permission<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
-	let added = env::args().nth(3).unwrap() == "add";
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let uid = args.arg_uid.unwrap();
-	let permission_name = env::args().nth(5).unwrap();
+	// Docopt matched exactly one of the permission names
+	let permission_name = if args.cmd_Administrate {
+		"Administrate"
+	} else if args.cmd_Admin {
+		"Admin"
+	} else if args.cmd_EditSequence {
+		"EditSequence"
+	} else if args.cmd_EditSection {
+		"EditSection"
+	} else if args.cmd_Viewer {
+		"Viewer"
+	} else if args.cmd_Sequencer {
+		"Sequencer"
+	} else {
+		"Operator"
+	};
 	let target_sequence = args.arg_target_sequence;
 	let target_section = args.arg_target_section;
 
 	try!(proton_cli::set_permission(
 		&dao,
-		&admin_key_path,
-		added,
+		&credentials,
+		args.cmd_add,
 		uid,
-		&permission_name,
+		permission_name,
+		args.flag_project.as_ref().map(|name| name.as_str()),
 		target_sequence,
 		target_section));
 	Ok(ProtonReturn::NoReturn)
@@ -392,20 +877,69 @@ fn run_set_permission<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn
 /// set-sequence-layout <admin-key> <seqid> <layout-id>
 fn run_set_sequence_layout<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
 	let admin_key = args.arg_admin_key.unwrap();
-	let admin_key_path = Path::new(&admin_key);
+	let credentials = try!(sign_in(&dao, &admin_key));
 	let seqid = args.arg_seqid.unwrap();
 	let layout_id = args.arg_layout_id.unwrap();
 
-	// Check that the admin has sufficient privileges
-	let valid_permissions = vec![PermissionEnum::Administrate];
-	let _ = try!(utils::check_valid_permission(
-		&dao,
-		admin_key_path,
-		&valid_permissions));
-
 	try!(proton_cli::set_sequence_layout(
 		&dao,
+		&credentials,
 		layout_id,
 		seqid));
 	Ok(ProtonReturn::NoReturn)
 }
+
+/// show-layout <layout-id> [--json]
+fn run_show_layout<PD: ProtonDao>(args: Args, dao: PD) -> Result<ProtonReturn, Error> {
+	let layout_id = args.arg_layout_id.unwrap();
+	let details = try!(proton_cli::show_layout(&dao, layout_id));
+	if args.flag_json {
+		return Ok(ProtonReturn::Listing(json::as_pretty_json(&details).to_string()));
+	}
+
+	let fixture_rows = details.fixtures.iter()
+		.map(|fixture| vec![
+			fixture.fixid.to_string(),
+			fixture.name.to_owned(),
+			format!("{:?}", fixture.location),
+			format!("{:?}", fixture.rotation),
+			fixture.channels.len().to_string()])
+		.collect::<Vec<Vec<String>>>();
+	let channel_rows = details.channels.iter()
+		.map(|channel| vec![
+			channel.channel_dmx.to_string(),
+			channel.channel_internal.to_string(),
+			channel.chanid.to_string(),
+			channel.name.to_owned(),
+			channel.color.to_owned()])
+		.collect::<Vec<Vec<String>>>();
+	let listing = format!("Layout {}: {}\n\n{}\n\n{}",
+		details.layout.layout_id,
+		details.layout.name,
+		format_table(&["FIXID", "NAME", "LOCATION", "ROTATION", "CHANNELS"], fixture_rows),
+		format_table(&["DMX", "INTERNAL", "CHANID", "NAME", "COLOR"], channel_rows));
+	Ok(ProtonReturn::Listing(listing))
+}
+
+/// Lays out rows as a plain text table with a header row and space-padded columns
+fn format_table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+	let header_row = headers.iter()
+		.map(|header| header.to_string())
+		.collect::<Vec<String>>();
+	let mut widths = vec![0; headers.len()];
+	for row in iter::once(&header_row).chain(rows.iter()) {
+		for (width, cell) in widths.iter_mut().zip(row) {
+			*width = cmp::max(*width, cell.chars().count());
+		}
+	}
+	iter::once(&header_row).chain(rows.iter())
+		.map(|row| {
+			let cells = row.iter()
+				.zip(&widths)
+				.map(|(cell, width)| format!("{:<width$}", cell, width = width))
+				.collect::<Vec<String>>();
+			cells.join("  ").trim_right().to_owned()
+		})
+		.collect::<Vec<String>>()
+		.join("\n")
+}
@@ -1,39 +1,117 @@
-use std::path::Path;
-
+use audit;
+use auth;
 use error::Error;
-use project_types::Permission;
+use project_types::{self, Credentials, Permission, PermissionEnum};
 use dao::ProtonDao;
+use utils;
 
 
-/// [INCOMPLETE] Gets the permissions a user has
+/// Gets the permissions a user has. Anyone may list their own permissions;
+/// listing someone else's needs Administrate.
 pub fn get_permissions<PD: ProtonDao> (
     dao: &PD,
+    credentials: &Credentials,
     uid: u32
 ) -> Result<Vec<Permission>, Error> {
 
+    let acting_uid = try!(auth::verify_credentials(dao, credentials));
+    if acting_uid != uid {
+        let valid_permissions = vec![PermissionEnum::Administrate];
+        try!(utils::check_user_permission(dao, acting_uid, None, &valid_permissions));
+    }
+
     dao.get_all_permissions(uid)
 }
 
-/// [INCOMPLETE] Sets a user's permission
-#[allow(unused_variables)]
-pub fn set_permission<P: AsRef<Path>, PD: ProtonDao> (
+/// Grants (add) or revokes a user's permission, within a project if given.
+/// Needs Administrate: within that project, or for sequence and section permissions
+/// outside of any project, within a project whose playlist has the sequence.
+/// Roles (Viewer, Sequencer, Operator) need a project. The project, sequence and
+/// section a granted permission applies to must exist.
+pub fn set_permission<PD: ProtonDao> (
     dao: &PD,
-    admin_key_path: P,
+    credentials: &Credentials,
     add: bool,
     target_uid: u32,
     permission_name: &str,
+    project: Option<&str>,
     target_sequence: Option<u32>,
     target_section: Option<u32>
 ) -> Result<(), Error> {
-    //let admin_uid = try!(utils::get_uid_from_key(&admin_key_path));
 
-    Err(Error::TodoErr)
-    
-    // Only admins (those with GrantPerm permission) can change permissions
-    // Make sure root isn't losing admin privileges
     // Validate and create permission
-    // Set permissions
-    // Commit changes
-    
+    let permission = try!(project_types::get_permission_enum(permission_name, target_sequence, target_section));
+    if permission.is_role() && project.is_none() {
+        return Err(Error::MissingPermissionArg);
+    }
+
+    // Only admins can change permissions
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let admin_uid = match (project, permission.seqid()) {
+        (Some(proj_name), _) => {
+            let _ = try!(dao.get_project(proj_name));
+            try!(utils::check_valid_permission(dao, credentials, Some(proj_name), &valid_permissions))
+        },
+        (None, Some(seqid)) =>
+            try!(utils::check_sequence_permission(dao, credentials, seqid, &valid_permissions)),
+        (None, None) =>
+            try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions)),
+    };
+
+    let user = try!(dao.get_user(target_uid));
+    let has_permission = try!(dao.get_all_permissions(target_uid))
+        .iter()
+        .any(|p| p.permission == permission && p.project.as_ref().map(|name| name.as_str()) == project);
+
+    if add {
+        if has_permission {
+            return Err(Error::DuplicatePermission(target_uid, format!("{:?}", permission)));
+        }
+        try!(validate_targets(dao, &permission));
+        dao.transaction(|| {
+            let _ = try!(dao.add_permission(target_uid, project, &permission));
+            audit::record(dao, admin_uid, "set-permission", project, "user", &target_uid.to_string(),
+                None, Some(describe_permission(&permission, project)))
+        })
+    } else {
+        // Make sure root isn't losing admin privileges
+        if permission == PermissionEnum::Administrate && try!(utils::is_project_root(dao, &user)) {
+            return Err(Error::CannotRevokeRootAdmin(user.name));
+        }
+        // Nor the last administrator outside of any project who can still sign in
+        if permission == PermissionEnum::Administrate && project.is_none()
+            && try!(utils::is_last_working_admin(dao, target_uid)) {
+            return Err(Error::CannotRemoveLastAdmin(user.name));
+        }
+        dao.transaction(|| {
+            try!(dao.revoke_permission(target_uid, project, &permission));
+            audit::record(dao, admin_uid, "set-permission", project, "user", &target_uid.to_string(),
+                Some(describe_permission(&permission, project)), None)
+        })
+    }
+}
+
+/// A permission as shown in the audit log, e.g. "Operator in Winter Show"
+fn describe_permission(permission: &PermissionEnum, project: Option<&str>) -> String {
+    match project {
+        Some(proj_name) => format!("{:?} in {}", permission, proj_name),
+        None => format!("{:?}", permission),
+    }
 }
 
+/// Checks that the sequence and section a permission applies to exist,
+/// and that the section belongs to the sequence
+fn validate_targets<PD: ProtonDao>(dao: &PD, permission: &PermissionEnum) -> Result<(), Error> {
+    if let Some(seqid) = permission.seqid() {
+        if !try!(dao.sequence_exists(seqid)) {
+            return Err(Error::SequenceNotFound(seqid));
+        }
+    }
+    if let Some(secid) = permission.secid() {
+        let section = try!(dao.get_section(secid));
+        if Some(section.seqid) != permission.seqid() {
+            return Err(Error::InvalidSequenceSection(secid));
+        }
+    }
+    Ok(())
+}
@@ -1,35 +1,52 @@
 use rustc_serialize::json;
+use std::io::{self, Write};
 
+use audit;
 use dao::ProtonDao;
 use error::Error;
-use project_types::{Project, SequenceData};
+use project_types::{Credentials, PermissionEnum, Project, SequenceData};
 use utils;
 
+/// Channels in one DMX universe, numbered from 1
+const DMX_UNIVERSE_SIZE: usize = 512;
 
-/// Creates a new Proton project. Returns the public key of the root user.
+
+/// Creates a new Proton project whose root user has the given public key
+/// (generated by its owner, e.g. with keygen). Needs Administrate.
+/// Returns the root user's id.
 pub fn new_project<PD: ProtonDao>(
     dao: &PD,
+    credentials: &Credentials,
     name: &str,
-    layout_id: u32
-) -> Result<String, Error> {
+    layout_id: u32,
+    root_public_key: &str
+) -> Result<u32, Error> {
+
+    // Check that the admin has sufficient privileges
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
 
     // Check that layout exists
     let _ = try!(dao.get_layout(layout_id));
 
-    // Create keys
-    let (root_pub_key, root_private_key) = try!(utils::create_pub_priv_keys());
-
-    // Add project root user
-    let root_uid = try!(dao.add_initial_user(name, &root_private_key, &root_pub_key));
+    // Check the root user's key
+    let root_name = format!("{}_{}", "root", name);
+    try!(utils::check_new_public_key(dao, &root_name, root_public_key));
 
-    // Give initial user admin permissions
-    try!(dao.add_initial_permission(root_uid));
+    // Add the project, its root user and their permissions all or nothing
+    dao.transaction(|| {
+        // Create new project first, so an existing one reports DuplicateProject
+        let _ = try!(dao.new_project(name, layout_id));
 
-    // Create new project
-    let _ = try!(dao.new_project(name, layout_id));
+        // Add project root user
+        let root_uid = try!(dao.add_initial_user(name, root_public_key));
 
-    // Return root user's public key
-    Ok(root_pub_key)
+        // Make the root user the new project's admin
+        try!(dao.add_initial_permission(root_uid, name));
+        try!(audit::record(dao, uid, "new-project", Some(name), "project", name,
+            None, Some(format!("layout {}, root user {}", layout_id, root_uid))));
+        Ok(root_uid)
+    })
 }
 
 /// Fetches and returns a project
@@ -40,6 +57,14 @@ pub fn get_project<PD: ProtonDao>(
     dao.get_project(proj_name)
 }
 
+/// Lists projects, optionally only those whose name contains name_filter
+pub fn list_projects<PD: ProtonDao>(
+    dao: &PD,
+    name_filter: Option<&str>
+) -> Result<Vec<Project>, Error> {
+    dao.list_projects(name_filter)
+}
+
 /// Finds and returns a project's layout id
 pub fn get_layout_id<PD: ProtonDao>(
     dao: &PD,
@@ -58,49 +83,66 @@ pub fn get_layout_id<PD: ProtonDao>(
     Ok(project.layout_id)
 }
 
-/// Gets all sequence data in the project's playlist
+/// Gets all sequence data in the project's playlist as one JSON string.
+/// Prefer write_playlist_data for real shows; this holds everything in memory.
 pub fn get_playlist_data<PD: ProtonDao> (
     dao: &PD,
+    credentials: &Credentials,
     proj_name: &str
 ) -> Result<String, Error> {
+    let mut playlist_data = Vec::new();
+    try!(write_playlist_data(dao, credentials, proj_name, b"", &mut playlist_data));
+    Ok(String::from_utf8(playlist_data).expect("Encoded playlist data not UTF-8"))
+}
+
+/// Writes all sequence data in the project's playlist to the given output as a
+/// JSON array, one sequence at a time. Only one sequence is held in memory at once.
+/// The prefix goes first, but only once the caller is authorized and the project
+/// found, so nothing is written for those errors.
+/// Needs Viewer (or any role that includes it) within the project.
+pub fn write_playlist_data<PD: ProtonDao, W: Write> (
+    dao: &PD,
+    credentials: &Credentials,
+    proj_name: &str,
+    prefix: &[u8],
+    out: &mut W
+) -> Result<(), Error> {
 
     // Check that project exists
     let project = try!(dao.get_project(proj_name));
 
-    let mut playlist_data = Vec::with_capacity(project.playlist.len());
+    // Check that the caller may see the show
+    let valid_permissions = vec![PermissionEnum::Viewer];
+    let _ = try!(utils::check_valid_permission(dao, credentials, Some(proj_name), &valid_permissions));
+
+    try!(out.write_all(prefix).map_err(Error::Io));
+    try!(out.write_all(b"[").map_err(Error::Io));
 
     // Go through each sequence in the playlist
-    for seqid in project.playlist.iter() {
+    for (i, seqid) in project.playlist.iter().enumerate() {
 
-        print!("Getting sequence {}...", seqid);
+        progress(&format!("Getting sequence {}...", seqid));
 
         // Get sequence
         let sequence = try!(dao.get_sequence(seqid.to_owned()));
 
-        println!("Sequence '{}' retrieved", &sequence.name);
-        print!("Getting channel ids...");
+        // Get all of the sequence's data, keyed by dmx channel
+        let chan_data = try!(dao.get_sequence_data(seqid.to_owned()));
 
-        // Get the sequence's channel ids
-        let chan_ids = try!(dao.get_channel_ids(seqid.to_owned()));
-
-        if chan_ids.len() < 1 {
-            // TODO: make error
-            println!("No channels found");
-            panic!("No channels found");
+        if chan_data.is_empty() {
+            return Err(Error::SequenceHasNoChannels(*seqid));
         }
 
-        println!("Channel ids loaded.");
-        print!("Getting data...");
-
         // Create vector for sequence data
         // Up to 512 channels per universe, plus one because DMX starts at 1
-        let mut seq_data = vec![vec![0; sequence.num_frames as usize]; 513];
-
-        // Get each channel's data and put it in the correct vector slot
-        for chanid in chan_ids {
-            let channel = try!(dao.get_channel(chanid));
-            let chan_data = try!(dao.get_data(seqid.to_owned(), chanid.to_owned()));
-            seq_data[channel.channel_dmx as usize] = chan_data;
+        let mut seq_data = vec![vec![0; sequence.num_frames as usize]; DMX_UNIVERSE_SIZE + 1];
+
+        // Put each channel's data in the correct vector slot
+        for (channel_dmx, data) in chan_data {
+            match seq_data.get_mut(channel_dmx as usize) {
+                Some(slot) => *slot = data,
+                None => return Err(Error::DmxChannelOutOfRange(*seqid, channel_dmx)),
+            }
         }
 
         let sequence_data = SequenceData {
@@ -111,11 +153,20 @@ pub fn get_playlist_data<PD: ProtonDao> (
             data: seq_data
         };
 
-        playlist_data.push(sequence_data);
+        // Encode and write this sequence before loading the next
+        if i > 0 {
+            try!(out.write_all(b",").map_err(Error::Io));
+        }
+        let sequence_json = try!(json::encode(&sequence_data).map_err(Error::JsonEncode));
+        try!(out.write_all(sequence_json.as_bytes()).map_err(Error::Io));
 
-        println!("done");
+        progress(&format!("Sequence '{}' done", &sequence_data.name));
     }
 
-    print!("Encoding playlist data..");
-    json::encode(&playlist_data).map_err(Error::JsonEncode)
+    out.write_all(b"]").map_err(Error::Io)
+}
+
+/// Progress messages go to stderr so they never mix with data written to stdout
+fn progress(message: &str) {
+    let _ = writeln!(io::stderr(), "{}", message);
 }
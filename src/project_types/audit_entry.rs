@@ -0,0 +1,37 @@
+/// One recorded change: who changed what, when, and how
+#[derive(Clone, Debug, RustcDecodable, RustcEncodable)]
+pub struct AuditEntry {
+    pub auditid: u32,
+    pub uid: u32, // The acting user
+    pub time: u64, // Seconds since the Unix epoch
+    pub command: String, // e.g. "insert-sequence"
+    pub project: Option<String>, // The project the change was made in, if any
+    pub entity: String, // What kind of thing changed: layout, project, section, sequence or user
+    pub target: String, // Its id (a project's name)
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Which audit entries to list. Fields that aren't set match every entry;
+/// since and until are inclusive.
+#[derive(Clone, Debug, Default)]
+pub struct AuditFilter {
+    pub uid: Option<u32>,
+    pub project: Option<String>,
+    pub entity: Option<String>,
+    pub target: Option<String>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+impl AuditFilter {
+    /// True if the entry passes every filter that is set
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.uid.map_or(true, |uid| entry.uid == uid) &&
+        self.project.as_ref().map_or(true, |project| entry.project.as_ref() == Some(project)) &&
+        self.entity.as_ref().map_or(true, |entity| entry.entity == *entity) &&
+        self.target.as_ref().map_or(true, |target| entry.target == *target) &&
+        self.since.map_or(true, |since| entry.time >= since) &&
+        self.until.map_or(true, |until| entry.time <= until)
+    }
+}
@@ -1,7 +1,12 @@
 
+/// The highest value a DMX channel can be set to. Channels are 8-bit DMX
+/// channels, since that's all the show controller outputs; 16-bit channels would
+/// need a resolution stored per channel, and the schema has none.
+pub const DMX_MAX_VALUE: u16 = 255;
+
 /// Collection of metadata for a channel, including name, channels (internal and external), 
 /// and location/rotation.
-#[derive(Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Channel {
     pub chanid: u32,
     pub name: String,
@@ -12,3 +17,10 @@ pub struct Channel {
     pub location: (Option<i32>, Option<i32>, Option<i32>),
     pub rotation: (Option<i32>, Option<i32>, Option<i32>),
 }
+
+impl Channel {
+    /// The highest value the channel can be set to (see DMX_MAX_VALUE)
+    pub fn max_value(&self) -> u16 {
+        DMX_MAX_VALUE
+    }
+}
@@ -0,0 +1,9 @@
+/// Proof that the caller holds a user's private key: their signature over a
+/// challenge issued by the database. Created with sign_challenge and checked by
+/// check_valid_permission; good for one command only.
+#[derive(Clone, Debug)]
+pub struct Credentials {
+    pub public_key: String, // OpenSSH format, derived from the private key
+    pub challenge: Vec<u8>,
+    pub signature: Vec<u8>,
+}
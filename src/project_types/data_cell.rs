@@ -0,0 +1,7 @@
+/// One frame of one channel's data, as written by a sparse data patch
+#[derive(Clone, Debug, PartialEq, RustcDecodable, RustcEncodable)]
+pub struct DataCell {
+    pub chanid: u32,
+    pub frame: u32,
+    pub value: u16,
+}
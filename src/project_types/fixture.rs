@@ -1,7 +1,7 @@
 
 /// Holds metadata for a Fixture, which is logically a collection of channels 
 /// that can be used as a unit (e.g. sunbursts, net light)
-#[derive(Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Fixture {
     pub fixid: u32,
     pub name: String,
@@ -2,7 +2,7 @@ use dao::FixtureDao;
 use error::Error;
 
 /// Contains a layout (a logical collection of fixtures)
-#[derive(Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Layout {
     pub layout_id: u32,
     pub name: String,
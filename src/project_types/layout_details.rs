@@ -0,0 +1,9 @@
+use project_types::{Channel, Fixture, Layout};
+
+/// A layout along with the fixtures and channels in it
+#[derive(Clone, Debug, RustcEncodable)]
+pub struct LayoutDetails {
+    pub layout: Layout,
+    pub fixtures: Vec<Fixture>, // In the layout's order
+    pub channels: Vec<Channel>, // Ordered by DMX channel
+}
@@ -0,0 +1,40 @@
+/// What to do with imported channel data whose number of frames differs from
+/// the sequence's
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LengthPolicy {
+    /// Reject it
+    Exact,
+    /// Pad short channels with zeros (off)
+    Pad,
+    /// Cut long channels short
+    Truncate,
+    /// Both pad short and cut long channels
+    PadOrTruncate,
+}
+
+impl LengthPolicy {
+    /// The policy for the --pad and --truncate flags
+    pub fn from_flags(pad: bool, truncate: bool) -> LengthPolicy {
+        match (pad, truncate) {
+            (false, false) => LengthPolicy::Exact,
+            (true, false) => LengthPolicy::Pad,
+            (false, true) => LengthPolicy::Truncate,
+            (true, true) => LengthPolicy::PadOrTruncate,
+        }
+    }
+
+    /// Pads or truncates the data to num_frames as far as the policy allows.
+    /// Returns whether the data has num_frames frames now.
+    pub fn fit(&self, data: &mut Vec<u16>, num_frames: u32) -> bool {
+        let num_frames = num_frames as usize;
+        let pad = *self == LengthPolicy::Pad || *self == LengthPolicy::PadOrTruncate;
+        let truncate = *self == LengthPolicy::Truncate || *self == LengthPolicy::PadOrTruncate;
+        if data.len() < num_frames && pad {
+            data.resize(num_frames, 0);
+        }
+        if data.len() > num_frames && truncate {
+            data.truncate(num_frames);
+        }
+        data.len() == num_frames
+    }
+}
@@ -1,28 +1,46 @@
 
+mod audit_entry;
 mod channel;
+mod credentials;
+mod data_cell;
 mod file_layout;
 mod file_patch;
 mod fixture;
 mod layout;
+mod layout_details;
+mod length_policy;
 mod permissions;
 mod permission_enum;
 mod project;
+mod project_bundle;
+mod resample_mode;
 mod section;
+mod section_checkout;
 mod sequence;
 mod sequence_data;
+mod sequence_deletion;
 mod user;
 
-pub use self::channel::Channel;
+pub use self::audit_entry::{AuditEntry, AuditFilter};
+pub use self::channel::{Channel, DMX_MAX_VALUE};
+pub use self::credentials::Credentials;
+pub use self::data_cell::DataCell;
 pub use self::file_patch::{FilePatch, FilePatchRow};
 pub use self::file_layout::FileLayout;
 pub use self::fixture::Fixture;
 pub use self::layout::Layout;
+pub use self::layout_details::LayoutDetails;
+pub use self::length_policy::LengthPolicy;
 pub use self::permissions::Permission;
 pub use self::permission_enum::PermissionEnum;
 pub use self::project::Project;
+pub use self::project_bundle::{BundleChannelData, BundleFile, BundleSequence, ImportReport, ProjectBundle};
+pub use self::resample_mode::ResampleMode;
 pub use self::section::Section;
+pub use self::section_checkout::{SectionChannel, SectionCheckout};
 pub use self::sequence::Sequence;
 pub use self::sequence_data::SequenceData;
+pub use self::sequence_deletion::SequenceDeletion;
 pub use self::user::User;
 
 pub use self::permission_enum::get_permission_enum;
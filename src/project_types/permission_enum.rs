@@ -1,11 +1,75 @@
 use error::Error;
 
-/// [INCOMPLETE] The different permissions a user can have
-#[derive(Debug, PartialEq, RustcDecodable, RustcEncodable)]
+/// The different permissions a user can have.
+/// Viewer, Sequencer and Operator are roles, granted for a project. Administrate
+/// granted for a project is that project's Admin role; granted without one it
+/// covers every project.
+#[derive(Clone, Debug, PartialEq, RustcDecodable, RustcEncodable)]
 pub enum PermissionEnum {
     Administrate,
     EditSequence(u32),
     EditSection(u32, u32),
+    Viewer,
+    Sequencer,
+    Operator,
+}
+
+impl PermissionEnum {
+    /// The name stored for this permission (the inverse of get_permission_enum)
+    pub fn name(&self) -> &'static str {
+        match *self {
+            PermissionEnum::Administrate => "Administrate",
+            PermissionEnum::EditSequence(_) => "EditSequence",
+            PermissionEnum::EditSection(_, _) => "EditSection",
+            PermissionEnum::Viewer => "Viewer",
+            PermissionEnum::Sequencer => "Sequencer",
+            PermissionEnum::Operator => "Operator",
+        }
+    }
+
+    /// True for the roles, which only make sense within a project
+    pub fn is_role(&self) -> bool {
+        match *self {
+            PermissionEnum::Viewer | PermissionEnum::Sequencer | PermissionEnum::Operator => true,
+            _ => false,
+        }
+    }
+
+    /// Whether holding this permission gives the required one. Roles expand to:
+    /// - Viewer: view the project
+    /// - Sequencer: view, and edit any of the project's sequences and their sections
+    /// - Operator: view, and edit the playlist
+    /// - Admin (Administrate): everything
+    pub fn grants(&self, required: &PermissionEnum) -> bool {
+        match (self, required) {
+            (&PermissionEnum::Administrate, _) => true,
+            (&PermissionEnum::Sequencer, &PermissionEnum::Viewer) |
+            (&PermissionEnum::Sequencer, &PermissionEnum::EditSequence(_)) |
+            (&PermissionEnum::Sequencer, &PermissionEnum::EditSection(_, _)) => true,
+            (&PermissionEnum::Operator, &PermissionEnum::Viewer) => true,
+            (&PermissionEnum::EditSequence(seqid), &PermissionEnum::EditSection(sec_seqid, _)) =>
+                seqid == sec_seqid,
+            (held, required) => held == required,
+        }
+    }
+
+    /// The sequence this permission applies to, if any
+    pub fn seqid(&self) -> Option<u32> {
+        match *self {
+            PermissionEnum::Administrate => None,
+            PermissionEnum::EditSequence(seqid) => Some(seqid),
+            PermissionEnum::EditSection(seqid, _) => Some(seqid),
+            _ => None,
+        }
+    }
+
+    /// The section this permission applies to, if any
+    pub fn secid(&self) -> Option<u32> {
+        match *self {
+            PermissionEnum::EditSection(_, secid) => Some(secid),
+            _ => None,
+        }
+    }
 }
 
 /// Gets a permission enum from a string if valid
@@ -16,7 +80,7 @@ pub fn get_permission_enum(
 ) -> Result<PermissionEnum, Error> {
 
     match s {
-        "Administrate" => Ok(PermissionEnum::Administrate),
+        "Administrate" | "Admin" => Ok(PermissionEnum::Administrate),
         "EditSequence" => match seqid {
             Some(seq) => Ok(PermissionEnum::EditSequence(seq)),
             None => Err(Error::MissingPermissionArg)
@@ -28,6 +92,9 @@ pub fn get_permission_enum(
             },
             None => Err(Error::MissingPermissionArg),
         },
+        "Viewer" => Ok(PermissionEnum::Viewer),
+        "Sequencer" => Ok(PermissionEnum::Sequencer),
+        "Operator" => Ok(PermissionEnum::Operator),
         x => Err(Error::InvalidPermissionName(x.to_owned()))
     }
 }
@@ -3,33 +3,44 @@ use dao::PermissionDao;
 use project_types::PermissionEnum;
 
 /// Contains the metadata for a permission (what it is, what it applies to)
-#[derive(Debug, RustcDecodable, RustcEncodable)]
+#[derive(Clone, Debug, RustcDecodable, RustcEncodable)]
 pub struct Permission {
     pub permid: u32,
     pub uid: u32,
+    pub project: Option<String>, // None for permissions that aren't limited to one project
     pub seqid: Option<u32>,
     pub secid: Option<u32>,
     pub permission: PermissionEnum,
 }
 
 impl Permission {    
-    /// Creates a new Permission
-    #[allow(unused_variables)]
+    /// Creates a new Permission that hasn't been stored yet (permid 0).
+    /// seqid and secid must be the ones perm applies to, and roles need a project.
     pub fn new(
         uid: u32,
+        project: Option<&str>,
         seqid: Option<u32>,
         secid: Option<u32>,
         perm: PermissionEnum
     ) -> Result<Permission, Error> {
-        // Make sure user exists
-        // Check seqid and secid existence based on perm
-        Err(Error::TodoErr)
+        if perm.seqid() != seqid || perm.secid() != secid || (perm.is_role() && project.is_none()) {
+            return Err(Error::MissingPermissionArg);
+        }
+        Ok(Permission {
+            permid: 0,
+            uid: uid,
+            project: project.map(|name| name.to_owned()),
+            seqid: seqid,
+            secid: secid,
+            permission: perm
+        })
     }
 
     /// Add a user permission
-    #[allow(unused_variables)]
     pub fn add_permission<T: PermissionDao>(dao: &T, perm: Permission) -> Result<(), Error> {
-        Err(Error::TodoErr)
+        let project = perm.project.as_ref().map(|name| name.as_str());
+        let _ = try!(dao.add_permission(perm.uid, project, &perm.permission));
+        Ok(())
     }
 
 }
@@ -0,0 +1,51 @@
+use std::collections::BTreeMap;
+
+use project_types::{Channel, Fixture, Layout, Permission, Project, Sequence, User};
+
+/// Everything needed to recreate a project in another database: the project, its
+/// layouts, fixtures and channels, the playlist's sequences and their data, the
+/// users with permissions on them, and the music files. Ids are the ones from the
+/// exporting database; importing maps them to new ones.
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct ProjectBundle {
+    pub format_version: u32,
+    pub project: Project,
+    pub layouts: Vec<Layout>,
+    pub fixtures: Vec<Fixture>,
+    pub channels: Vec<Channel>,
+    pub sequences: Vec<BundleSequence>,
+    pub users: Vec<User>, // Public keys only
+    pub permissions: Vec<Permission>,
+    pub music_files: Vec<BundleFile>,
+}
+
+/// A sequence and the data for each of its channels
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct BundleSequence {
+    pub sequence: Sequence,
+    pub channel_data: Vec<BundleChannelData>,
+}
+
+/// One channel's frames, compacted with dao::encode_frames and base64 encoded
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct BundleChannelData {
+    pub chanid: u32,
+    pub frames: String,
+}
+
+/// A file from the music directory, base64 encoded
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct BundleFile {
+    pub name: String,
+    pub contents: String,
+}
+
+/// What importing a bundle created. Id maps go from bundle ids to new ids.
+#[derive(Debug)]
+pub struct ImportReport {
+    pub project_name: String,
+    pub layout_ids: BTreeMap<u32, u32>,
+    pub sequence_ids: BTreeMap<u32, u32>,
+    pub user_ids: BTreeMap<u32, u32>,
+    pub notes: Vec<String>, // Conflicts that were resolved without failing the import
+}
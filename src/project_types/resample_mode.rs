@@ -0,0 +1,70 @@
+use std::cmp;
+
+use error::Error;
+
+/// How resampling picks a frame's value from the frames around its start time
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResampleMode {
+    /// The old frame starting closest to it
+    Nearest,
+    /// The old frame playing when it starts
+    Hold,
+    /// Interpolated linearly between the old frames starting around it
+    Linear,
+}
+
+impl ResampleMode {
+    /// Gets a mode from its name: nearest, hold or linear
+    pub fn from_name(name: &str) -> Result<ResampleMode, Error> {
+        match name {
+            "nearest" => Ok(ResampleMode::Nearest),
+            "hold" => Ok(ResampleMode::Hold),
+            "linear" => Ok(ResampleMode::Linear),
+            _ => Err(Error::InvalidResampleMode(name.to_owned())),
+        }
+    }
+
+    /// The name from_name takes
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ResampleMode::Nearest => "nearest",
+            ResampleMode::Hold => "hold",
+            ResampleMode::Linear => "linear",
+        }
+    }
+
+    /// Resamples a channel's frames from one frame duration to another
+    pub fn resample(
+        &self,
+        data: &[u16],
+        old_duration_ms: u32,
+        new_duration_ms: u32,
+        new_num_frames: u32
+    ) -> Vec<u16> {
+        if data.is_empty() {
+            return vec![0; new_num_frames as usize];
+        }
+        let last = data.len() - 1;
+        let old_duration_ms = old_duration_ms as u64;
+        (0..new_num_frames as u64)
+            .map(|frame| {
+                let t = frame * new_duration_ms as u64;
+                let before = (t / old_duration_ms) as usize;
+                let offset = t % old_duration_ms;
+                match *self {
+                    ResampleMode::Nearest => {
+                        let nearest = ((t + old_duration_ms / 2) / old_duration_ms) as usize;
+                        data[cmp::min(nearest, last)]
+                    },
+                    ResampleMode::Hold => data[cmp::min(before, last)],
+                    ResampleMode::Linear => {
+                        let from = data[cmp::min(before, last)] as u64;
+                        let to = data[cmp::min(before + 1, last)] as u64;
+                        let weighted = from * (old_duration_ms - offset) + to * offset;
+                        ((weighted + old_duration_ms / 2) / old_duration_ms) as u16
+                    },
+                }
+            })
+            .collect()
+    }
+}
@@ -1,36 +1,73 @@
 use error::Error;
+use project_types::{Layout, Sequence};
 
-/// Structure to represent a sequence section, which consists of both 
-/// a section of time in the sequence and a range of channels. This is 
-/// mainly used to make sure only one person can change a section of a 
+/// Structure to represent a sequence section, which consists of both
+/// a section of time in the sequence and a range of channels. This is
+/// mainly used to make sure only one person can change a section of a
 /// sequence at any given time (no merge conflicts).
-#[derive(Debug)]
+#[derive(Clone, Debug, RustcDecodable, RustcEncodable)]
 pub struct Section {
     pub secid: u32,
-    pub t_start: u32,
-    pub t_end: u32,
+    pub t_start: u32, // ms from the start of the sequence, inclusive
+    pub t_end: u32, // ms, exclusive
     pub seqid: u32,
     pub fixtures: Vec<u32>,
 }
 
 impl Section {
 
-    /// Creates a new section
-    #[allow(unused_variables)]
+    /// Creates a new section of the sequence that hasn't been stored yet (secid 0).
+    /// The time range must be non-empty and within the sequence, and the
+    /// fixtures must be in the sequence's layout.
     pub fn new(
-        uid: u32,
-        seqid: u32,
+        sequence: &Sequence,
+        layout: &Layout,
         t_start: u32,
         t_end: u32,
         fixtures: Vec<u32>
     ) -> Result<Section, Error> {
-        // Make sure user has permission to add section
-        // Make sure seqid is a valid sequence
-        // Make sure t_start is not before the end of the sequence
-        // Make sure t_end is >= t_start
-        // Make sure fixtures contains only valid fixtures (and is not empty)
+        let seq_duration_ms = sequence.duration_ms();
+        if t_start >= t_end {
+            return Err(Error::InvalidSection(format!(
+                "start {} ms must come before end {} ms", t_start, t_end)));
+        }
+        if t_end > seq_duration_ms {
+            return Err(Error::InvalidSection(format!(
+                "end {} ms is after the end of the sequence ({} ms)", t_end, seq_duration_ms)));
+        }
+        if fixtures.is_empty() {
+            return Err(Error::InvalidSection("no fixtures given".to_owned()));
+        }
+        for (i, fixid) in fixtures.iter().enumerate() {
+            if !layout.fixtures.contains(fixid) {
+                return Err(Error::InvalidSection(format!(
+                    "fixture {} is not in the sequence's layout {}", fixid, layout.layout_id)));
+            }
+            if fixtures[..i].contains(fixid) {
+                return Err(Error::InvalidSection(format!("fixture {} is given twice", fixid)));
+            }
+        }
 
-        Err(Error::TodoErr)
+        Ok(Section {
+            secid: 0,
+            t_start: t_start,
+            t_end: t_end,
+            seqid: sequence.seqid,
+            fixtures: fixtures
+        })
     }
 
-}
\ No newline at end of file
+    /// The frames [first, end) of the sequence that start within this section.
+    /// Sections that don't share time share no frames.
+    pub fn frame_range(&self, sequence: &Sequence) -> (u32, u32) {
+        sequence.frame_range(self.t_start, self.t_end)
+    }
+
+    /// True if both sections are of the same sequence and share both time and fixtures
+    pub fn overlaps(&self, other: &Section) -> bool {
+        self.seqid == other.seqid &&
+        self.t_start < other.t_end && other.t_start < self.t_end &&
+        self.fixtures.iter().any(|fixid| other.fixtures.contains(fixid))
+    }
+
+}
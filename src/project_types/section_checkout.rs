@@ -0,0 +1,23 @@
+
+/// A section's frames, exported with checkout-section for editing offline and
+/// written back with checkin-section. Only the frames may be edited.
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct SectionCheckout {
+    pub format_version: u32,
+    pub secid: u32,
+    pub seqid: u32,
+    pub frame_duration_ms: u32,
+    pub first_frame: u32, // The frame of the sequence the section starts at
+    pub num_frames: u32,
+    pub channels: Vec<SectionChannel>, // Ordered by DMX channel
+    pub checksum: String, // Of the frames as they were at checkout
+}
+
+/// One of the section's channels and its frames within the section
+#[derive(Debug, RustcEncodable, RustcDecodable)]
+pub struct SectionChannel {
+    pub chanid: u32,
+    pub channel_dmx: u32,
+    pub name: String,
+    pub frames: Vec<u16>,
+}
@@ -2,7 +2,7 @@ use error::Error;
 use project_types::Layout;
 
 /// Structure to hold sequence metadata
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
 pub struct Sequence {
     pub seqid: u32,
     pub name: String,
@@ -26,9 +26,7 @@ impl Sequence {
         
         // Defaults
         let frame_dur_ms = frame_duration_ms.unwrap_or(50);
-        if frame_dur_ms < 25 {
-            return Err(Error::InvalidFrameDuration(frame_dur_ms));
-        }
+        try!(Sequence::check_frame_duration(frame_dur_ms));
 
         // Calculate num_frames
         let num_frames_f32: f32 = seq_duration_ms as f32 / frame_dur_ms as f32;
@@ -53,4 +51,25 @@ impl Sequence {
 
         Ok(sequence)
     }
+
+    /// Checks that frames are long enough (at least 25 ms)
+    pub fn check_frame_duration(frame_duration_ms: u32) -> Result<(), Error> {
+        if frame_duration_ms < 25 {
+            return Err(Error::InvalidFrameDuration(frame_duration_ms));
+        }
+        Ok(())
+    }
+
+    /// Length of the sequence in ms
+    pub fn duration_ms(&self) -> u32 {
+        self.num_frames * self.frame_duration_ms
+    }
+
+    /// The frames [first, end) that start between t_start (inclusive) and
+    /// t_end (exclusive), in ms
+    pub fn frame_range(&self, t_start: u32, t_end: u32) -> (u32, u32) {
+        let first = (t_start + self.frame_duration_ms - 1) / self.frame_duration_ms;
+        let end = (t_end + self.frame_duration_ms - 1) / self.frame_duration_ms;
+        (first, end)
+    }
 }
@@ -0,0 +1,11 @@
+use project_types::Sequence;
+
+/// What deleting a sequence removed (or, in a dry run, would remove)
+#[derive(Debug)]
+pub struct SequenceDeletion {
+    pub sequence: Sequence,
+    pub projects: Vec<String>, // Projects whose playlists contained the sequence
+    pub channel_data: u64,
+    pub sections: u64,
+    pub permissions: u64,
+}
@@ -0,0 +1,84 @@
+//! This module manages sections of sequences, which let several people edit
+//! one sequence without stepping on each other
+
+use audit;
+use dao::ProtonDao;
+use error::Error;
+use project_types::{Credentials, PermissionEnum, Section};
+use utils;
+
+
+/// Creates a section of a sequence: the time from t_start (inclusive) to t_end
+/// (exclusive), in ms, over the given fixtures of the sequence's layout. Sections
+/// of a sequence can't share both time and fixtures. Needs EditSequence on the
+/// sequence (Sequencer and Administrate include it); the creator is granted
+/// EditSection on the new section. Returns the new section's id.
+pub fn new_section<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    seqid: u32,
+    t_start: u32,
+    t_end: u32,
+    fixtures: Vec<u32>
+) -> Result<u32, Error> {
+
+    let valid_permissions = vec![PermissionEnum::EditSequence(seqid)];
+    let uid = try!(utils::check_sequence_permission(dao, credentials, seqid, &valid_permissions));
+
+    let sequence = try!(dao.get_sequence(seqid));
+    let layout = try!(dao.get_layout(sequence.layout_id));
+    let section = try!(Section::new(&sequence, &layout, t_start, t_end, fixtures));
+
+    // Check for overlaps and add the section all or nothing. Listing in the
+    // transaction holds off concurrent creates until this one commits.
+    dao.transaction(|| {
+        for existing in try!(dao.list_sections(seqid)) {
+            if section.overlaps(&existing) {
+                return Err(Error::SectionOverlap(existing.secid));
+            }
+        }
+        let new_section = try!(dao.new_section(&section));
+        let _ = try!(dao.add_permission(uid, None, &PermissionEnum::EditSection(seqid, new_section.secid)));
+        try!(audit::record_for_sequence(dao, uid, "new-section", seqid, "section", &new_section.secid.to_string(),
+            None, Some(describe_section(&new_section))));
+        Ok(new_section.secid)
+    })
+}
+
+/// Fetches and returns a section
+pub fn get_section<PD: ProtonDao>(dao: &PD, secid: u32) -> Result<Section, Error> {
+    dao.get_section(secid)
+}
+
+/// Lists a sequence's sections by start time
+pub fn list_sections<PD: ProtonDao>(dao: &PD, seqid: u32) -> Result<Vec<Section>, Error> {
+    let _ = try!(dao.get_sequence(seqid));
+    dao.list_sections(seqid)
+}
+
+/// Deletes a section along with the permissions to edit it. Needs EditSection
+/// on the section (EditSequence, Sequencer and Administrate include it).
+/// Returns the deleted section.
+pub fn delete_section<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    secid: u32
+) -> Result<Section, Error> {
+
+    let section = try!(dao.get_section(secid));
+    let valid_permissions = vec![PermissionEnum::EditSection(section.seqid, secid)];
+    let uid = try!(utils::check_sequence_permission(dao, credentials, section.seqid, &valid_permissions));
+
+    try!(dao.transaction(|| {
+        try!(dao.delete_section(secid));
+        audit::record_for_sequence(dao, uid, "delete-section", section.seqid, "section", &secid.to_string(),
+            Some(describe_section(&section)), None)
+    }));
+    Ok(section)
+}
+
+/// A section as shown in the audit log, e.g. "sequence 3, 1000-2000 ms, fixtures [1, 2]"
+fn describe_section(section: &Section) -> String {
+    format!("sequence {}, {}-{} ms, fixtures {:?}",
+        section.seqid, section.t_start, section.t_end, section.fixtures)
+}
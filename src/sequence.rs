@@ -6,21 +6,30 @@ use std::path::Path;
 use sfml::audio::Music;
 
 use error::Error;
-use project_types::{Sequence};
+use project_types::{Credentials, LengthPolicy, PermissionEnum, ResampleMode, Sequence, SequenceDeletion};
 use dao::ProtonDao;
+use audit;
 use utils;
 
-/// Creates a new sequence based on proton-vixen-converter data
+/// Creates a new sequence based on proton-vixen-converter data. Needs Administrate.
+/// Each channel's data must have as many frames as the sequence, unless the
+/// length policy allows padding or truncating it.
 pub fn new_vixen_sequence<P: AsRef<Path>, PD: ProtonDao>(
     dao: &PD,
+    credentials: &Credentials,
     name: &str,
     music_file_path: P,
     seq_duration_ms: u32,
     frame_duration_ms: u32,
     data_file_path: P,
-    layout_id: u32
+    layout_id: u32,
+    length_policy: LengthPolicy
 ) -> Result<u32, Error> {
 
+    // Check that the admin has sufficient privileges
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
+
     // Get layout (also checks if it exists)
     let layout = try!(dao.get_layout(layout_id));
 
@@ -45,35 +54,52 @@ pub fn new_vixen_sequence<P: AsRef<Path>, PD: ProtonDao>(
         )
     );
 
-    // Try to add sequence
-    let seq = try!(dao.new_sequence(&sequence));
-
-    // Get sequence channel ids to match up dmx channels with given data
-    let chan_ids = try!(dao.get_channel_ids(seq.seqid));
-
     // Read in vixen sequence data
     let vixen_data_str = try!(utils::file_as_string(data_file_path.as_ref()));
     let vixen_data: Vec<Vec<u16>> = try!(json::decode(&vixen_data_str).map_err(Error::JsonDecode));
 
-    // Make sure the number of channels matches with the layout
-    if chan_ids.len() != vixen_data.len() {
-        println!("layout: {} vs data: {}", chan_ids.len(), vixen_data.len());
-        return Err(Error::InvalidVixenData("Number of channels not the same as the given layout".to_string()));
-    }
-    
-    // For each channel the sequence created, update its data based on vixen_data
-    for chanid in chan_ids {
-        let channel = try!(dao.get_channel(chanid));
-        let ref chan_data = vixen_data[channel.channel_internal as usize - 1]; // TODO, check out of bounds
-        try!(dao.new_data(seq.seqid, chanid, chan_data));
-    }
+    // Add sequence and all of its data all or nothing
+    dao.transaction(|| {
+        // Try to add sequence
+        let seq = try!(dao.new_sequence(&sequence));
+
+        // Get sequence channel ids to match up dmx channels with given data
+        let chan_ids = try!(dao.get_channel_ids(seq.seqid));
+
+        // Make sure the number of channels matches with the layout
+        if chan_ids.len() != vixen_data.len() {
+            return Err(Error::InvalidVixenData(format!(
+                "the layout has {} channels, but the data has {}", chan_ids.len(), vixen_data.len())));
+        }
+
+        // For each channel the sequence created, update its data based on vixen_data
+        for chanid in chan_ids {
+            let channel = try!(dao.get_channel(chanid));
+            let internal = channel.channel_internal as usize;
+            if internal < 1 || internal > vixen_data.len() {
+                return Err(Error::InvalidVixenData(format!(
+                    "channel {} is internal channel {}, but the data only has channels 1 to {}",
+                    chanid, internal, vixen_data.len())));
+            }
+            let mut chan_data = vixen_data[internal - 1].clone();
+            if !length_policy.fit(&mut chan_data, seq.num_frames) {
+                return Err(Error::InvalidVixenData(format!(
+                    "internal channel {} has {} frames, but the sequence has {}",
+                    internal, chan_data.len(), seq.num_frames)));
+            }
+            try!(dao.new_data(seq.seqid, chanid, &chan_data));
+        }
 
-    Ok(seq.seqid)
+        try!(audit::record(dao, uid, "new-vixen-sequence", None, "sequence", &seq.seqid.to_string(),
+            None, Some(format!("'{}' with {} frames", seq.name, seq.num_frames))));
+        Ok(seq.seqid)
+    })
 }
 
-/// Creates a new sequence
+/// Creates a new sequence. Needs Administrate.
 pub fn new_sequence<P: AsRef<Path>, PD: ProtonDao>(
     dao: &PD,
+    credentials: &Credentials,
     name: &str,
     music_file_path: P,
     seq_duration_ms: u32,
@@ -81,6 +107,10 @@ pub fn new_sequence<P: AsRef<Path>, PD: ProtonDao>(
     layout_id: Option<u32>
 ) -> Result<u32, Error> {
 
+    // Check that the admin has sufficient privileges
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
+
     // Get layout (also checks if it exists)
     let lid = match layout_id {
         Some(id) => id,
@@ -113,29 +143,44 @@ pub fn new_sequence<P: AsRef<Path>, PD: ProtonDao>(
         )
     );
 
-    // Try to add sequence
-    let seq = try!(dao.new_sequence(&sequence));
+    // Add sequence and its empty data all or nothing
+    dao.transaction(|| {
+        // Try to add sequence
+        let seq = try!(dao.new_sequence(&sequence));
 
-    // Get list of channel ids in seq, sorted by dmx channel
-    let channel_ids = try!(dao.get_channel_ids(seq.seqid));
+        // Get list of channel ids in seq, sorted by dmx channel
+        let channel_ids = try!(dao.get_channel_ids(seq.seqid));
 
-    // Try to add empty sequence data
-    let seq_data = vec![0; sequence.num_frames as usize];
-    let _ = try!(dao.new_data_default(seq.seqid, channel_ids, seq_data));
+        // Try to add empty sequence data
+        let seq_data = vec![0; sequence.num_frames as usize];
+        let _ = try!(dao.new_data_default(seq.seqid, channel_ids, seq_data));
 
-    Ok(seq.seqid)
+        try!(audit::record(dao, uid, "new-sequence", None, "sequence", &seq.seqid.to_string(),
+            None, Some(format!("'{}' with {} frames", seq.name, seq.num_frames))));
+        Ok(seq.seqid)
+    })
 }
 
-/// Adds a sequence to the project's playlist at the given index
+/// Adds a sequence to the project's playlist at the given index.
+/// Needs Operator (or Administrate) within the project, and EditSequence or
+/// Operator on the sequence itself. Otherwise putting someone else's sequence
+/// in a playlist would extend the project's roles to it.
 pub fn insert_sequence<PD: ProtonDao> (
     dao: &PD,
+    credentials: &Credentials,
     proj_name: &str,
     seqid: u32,
     index: Option<u32>
 ) -> Result<(), Error> {
 
-    // Check that seqid exists
+    // Check that the admin has sufficient privileges
+    let valid_permissions = vec![PermissionEnum::Operator];
+    let uid = try!(utils::check_valid_permission(dao, credentials, Some(proj_name), &valid_permissions));
+
+    // Check that seqid exists and that the user may already act on it
     let _ = try!(dao.get_sequence(seqid));
+    let sequence_permissions = vec![PermissionEnum::Operator, PermissionEnum::EditSequence(seqid)];
+    try!(utils::check_user_sequence_permission(dao, uid, seqid, &sequence_permissions));
 
     // Get project
     let project = try!(dao.get_project(proj_name));
@@ -144,38 +189,131 @@ pub fn insert_sequence<PD: ProtonDao> (
     let offset = index.unwrap_or(project.playlist.len() as u32);
 
     // Add sequence to project's playlist
+    let before = format!("playlist {:?}", project.playlist);
     let new_project = try!(project.insert_sequence(seqid, offset));
-    dao.update_project(new_project)
+    let after = format!("playlist {:?}", new_project.playlist);
+    dao.transaction(|| {
+        try!(dao.update_project(new_project));
+        audit::record(dao, uid, "insert-sequence", Some(proj_name), "project", proj_name, Some(before), Some(after))
+    })
 }
 
-/// Removes a sequence from a project
+/// Removes a sequence from a project's playlist.
+/// Needs Operator or EditSequence(seqid) within the project.
 pub fn remove_sequence<PD: ProtonDao> (
     dao: &PD,
+    credentials: &Credentials,
     proj_name: &str,
     seqid: u32
 ) -> Result<(), Error> {
 
+    // Check that the admin has sufficient privileges
+    let valid_permissions = vec![PermissionEnum::Operator, PermissionEnum::EditSequence(seqid)];
+    let uid = try!(utils::check_valid_permission(dao, credentials, Some(proj_name), &valid_permissions));
+
     // Remove sequence from project's playlist
     let project = try!(dao.get_project(proj_name));
+    let before = format!("playlist {:?}", project.playlist);
     let new_project = try!(project.remove_sequence(seqid));
-    dao.update_project(new_project)
+    let after = format!("playlist {:?}", new_project.playlist);
+    dao.transaction(|| {
+        try!(dao.update_project(new_project));
+        audit::record(dao, uid, "remove-sequence", Some(proj_name), "project", proj_name, Some(before), Some(after))
+    })
 
     // TODO: Remove sequence's music file if not used elsewhere in playlist
 
 }
 
-/// Deletes sequence from storage
-#[allow(unused_variables)]
-pub fn delete_sequence<P: AsRef<Path>, PD: ProtonDao> (
+/// Deletes a sequence with its channel data, sections and the permissions that
+/// target it. Needs Administrate or EditSequence(seqid), which the Sequencer role of
+/// a project whose playlist has the sequence also grants. A sequence still in a
+/// playlist is only deleted if force is set, and is then removed from those playlists.
+/// A dry run reports what would be deleted without deleting anything.
+pub fn delete_sequence<PD: ProtonDao> (
     dao: &PD,
-    admin_key_path: P,
-    seqid: u32
-) -> Result<(), Error> {
+    credentials: &Credentials,
+    seqid: u32,
+    force: bool,
+    dry_run: bool
+) -> Result<SequenceDeletion, Error> {
 
     // Check admin permission
-    // Check that sequence exists
-    // Try to delete sequence
-    Err(Error::TodoErr)
+    let valid_permissions = vec![PermissionEnum::Administrate, PermissionEnum::EditSequence(seqid)];
+    let uid = try!(utils::check_sequence_permission(dao, credentials, seqid, &valid_permissions));
+
+    // Delete everything in one transaction; a dry run always rolls it back
+    try!(dao.begin_transaction());
+    let result = dao.delete_sequence(seqid)
+        .and_then(|deletion| {
+            if !deletion.projects.is_empty() && !force && !dry_run {
+                Err(Error::SequenceInPlaylist(seqid, deletion.projects))
+            } else {
+                Ok(deletion)
+            }
+        })
+        .and_then(|deletion| {
+            if dry_run {
+                return Ok(deletion);
+            }
+            let before = format!("'{}' in {} playlists", deletion.sequence.name, deletion.projects.len());
+            audit::record_in_projects(dao, uid, "delete-sequence", &deletion.projects, "sequence", &seqid.to_string(),
+                Some(before), None)
+                .map(|_| deletion)
+        });
+    match result {
+        Ok(deletion) => {
+            if dry_run {
+                try!(dao.rollback_transaction());
+            } else {
+                try!(dao.commit_transaction());
+            }
+            Ok(deletion)
+        },
+        Err(e) => {
+            let _ = dao.rollback_transaction();
+            Err(e)
+        },
+    }
+}
+
+/// Changes a sequence's frame duration, rewriting every channel's data with the
+/// given mode and updating its number of frames, all or nothing. The sequence keeps
+/// at least its length; sections are in ms and stay as they are.
+/// Needs EditSequence on the sequence (Sequencer and Administrate include it).
+/// Returns the resampled sequence.
+pub fn resample_sequence<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    seqid: u32,
+    frame_duration_ms: u32,
+    mode: ResampleMode
+) -> Result<Sequence, Error> {
+
+    let valid_permissions = vec![PermissionEnum::EditSequence(seqid)];
+    let uid = try!(utils::check_sequence_permission(dao, credentials, seqid, &valid_permissions));
+
+    try!(Sequence::check_frame_duration(frame_duration_ms));
+    let sequence = try!(dao.get_sequence(seqid));
+    let num_frames = (sequence.duration_ms() + frame_duration_ms - 1) / frame_duration_ms;
+
+    dao.transaction(|| {
+        // Read everything while it still matches the old frame count, then store
+        // the new count, which has to come before data of that length can be
+        let chan_ids = try!(dao.get_channel_ids(seqid));
+        let seq_data = try!(dao.get_data_range(seqid, &chan_ids, 0, sequence.num_frames));
+        try!(dao.set_frames(seqid, frame_duration_ms, num_frames));
+        for (chanid, data) in &seq_data {
+            let resampled = mode.resample(data, sequence.frame_duration_ms, frame_duration_ms, num_frames);
+            try!(dao.update_data(seqid, *chanid, &resampled));
+        }
+
+        let before = format!("{} frames of {} ms", sequence.num_frames, sequence.frame_duration_ms);
+        let after = format!("{} frames of {} ms ({})", num_frames, frame_duration_ms, mode.name());
+        try!(audit::record_for_sequence(dao, uid, "resample-sequence", seqid, "sequence", &seqid.to_string(),
+            Some(before), Some(after)));
+        dao.get_sequence(seqid)
+    })
 }
 
 /// Fetches and returns a sequence
@@ -183,6 +321,14 @@ pub fn get_sequence<PD: ProtonDao>(dao: &PD, seqid: u32) -> Result<Sequence, Err
     dao.get_sequence(seqid)
 }
 
+/// Lists sequences, optionally only those whose name contains name_filter
+pub fn list_sequences<PD: ProtonDao>(
+    dao: &PD,
+    name_filter: Option<&str>
+) -> Result<Vec<Sequence>, Error> {
+    dao.list_sequences(name_filter)
+}
+
 /// Check that the music file is a valid format
 /// Full list of supported formats can be found at
 /// http://www.rust-sfml.org/doc/rsfml/audio/struct.Music.html
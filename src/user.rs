@@ -1,22 +1,29 @@
 //! This module manages project users
 use std::path::Path;
 
+use audit;
+use auth;
 use dao::{ProtonDao};
 use error::Error;
-use project_types::User;
+use keys;
+use project_types::{Credentials, PermissionEnum, User};
 use utils;
 
 
-/// Lookup and return a user from a public key
+/// Lookup and return a user from a public key. Needs any valid credentials.
 pub fn get_user<P: AsRef<Path>, PD: ProtonDao> (
     dao: &PD,
+    credentials: &Credentials,
     public_key_path: P
 ) -> Result<User, Error> {
+
+    // Only known users can look others up
+    let _ = try!(auth::verify_credentials(dao, credentials));
     
     let public_key_str = try!(utils::file_as_string(public_key_path.as_ref()));
 
-    // Throw error if the given key is not a valid RSA public key
-    if !utils::validate_rsa_pub_key(&public_key_str) {
+    // Throw error if the given key is not a valid public key
+    if !utils::validate_pub_key(&public_key_str) {
         return Err(Error::InvalidPublicKey(public_key_str));
     }
 
@@ -29,31 +36,240 @@ pub fn get_user<P: AsRef<Path>, PD: ProtonDao> (
     Ok(user)
 }
 
+/// Lists users, optionally only those whose name contains name_filter
+pub fn list_users<PD: ProtonDao> (
+    dao: &PD,
+    name_filter: Option<&str>
+) -> Result<Vec<User>, Error> {
+    dao.list_users(name_filter)
+}
+
+/// Registers a new user with a public key they generated themselves
+/// (e.g. with keygen). Needs Administrate. Returns the new user's id.
 pub fn new_user<PD: ProtonDao> (
     dao: &PD,
-    name: &str
-) -> Result<String, Error> {
+    credentials: &Credentials,
+    name: &str,
+    public_key: &str
+) -> Result<u32, Error> {
 
-    // Create keys
-    let (user_pub_key, user_private_key) = try!(utils::create_pub_priv_keys());
+    // See if admin has permission to add user
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let admin_uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
+
+    try!(utils::check_new_public_key(dao, name, public_key));
+    dao.transaction(|| {
+        let uid = try!(dao.add_user(name, public_key));
+        try!(audit::record(dao, admin_uid, "new-user", None, "user", &uid.to_string(),
+            None, Some(format!("'{}'", name))));
+        Ok(uid)
+    })
+}
 
-    // Add user
-    let _ = try!(dao.add_user(name, &user_private_key, &user_pub_key));
+/// Registers the first administrator, who holds Administrate outside of any project.
+/// Needs no credentials, so it only works once on a new database (or after
+/// allow_new_admin) and while nobody holds that permission. Returns the new user's id.
+pub fn new_admin<PD: ProtonDao> (
+    dao: &PD,
+    name: &str,
+    public_key: &str
+) -> Result<u32, Error> {
 
-    // Return public key
-    Ok(user_pub_key)
+    if try!(utils::has_global_admin(dao)) {
+        return Err(Error::AdminExists);
+    }
+    if !try!(dao.is_new_admin_open()) {
+        return Err(Error::NewAdminClosed);
+    }
+    try!(utils::check_new_public_key(dao, name, public_key));
+
+    // Add the user and their permission all or nothing
+    dao.transaction(|| {
+        try!(dao.set_new_admin_open(false));
+        let uid = try!(dao.add_user(name, public_key));
+        let _ = try!(dao.add_permission(uid, None, &PermissionEnum::Administrate));
+        try!(audit::record(dao, uid, "new-admin", None, "user", &uid.to_string(),
+            None, Some(format!("'{}' with Administrate", name))));
+        Ok(uid)
+    })
 }
 
-/// Removes a user
-#[allow(unused_variables)]
-pub fn remove_user<PD: ProtonDao> (
+/// Lets new_admin register one administrator in an existing database without one,
+/// such as a database migrated from a version whose administrators were all
+/// project roots. Needs access to the database itself rather than credentials.
+pub fn allow_new_admin<PD: ProtonDao> (dao: &PD) -> Result<(), Error> {
+    if try!(utils::has_global_admin(dao)) {
+        return Err(Error::AdminExists);
+    }
+    dao.set_new_admin_open(true)
+}
+
+/// Finds the one user with the given name, or with the given uid if name is a number.
+/// A numeric name can match one user by name and another by uid, which is an error.
+pub fn find_user<PD: ProtonDao> (
     dao: &PD,
     name: &str
-) -> Result<(), Error> {
+) -> Result<User, Error> {
+
+    let mut users = try!(dao.get_users_by_name(name));
+    if let Ok(uid) = name.parse::<u32>() {
+        match dao.get_user(uid) {
+            Ok(user) => if !users.iter().any(|u| u.uid == uid) {
+                users.push(user);
+            },
+            Err(Error::UserNotFound) => {},
+            Err(e) => return Err(e),
+        }
+    }
+
+    match users.len() {
+        0 => Err(Error::UserNotFound),
+        1 => Ok(users.remove(0)),
+        _ => Err(Error::AmbiguousUser(name.to_owned(), users.iter().map(|user| user.uid).collect())),
+    }
+}
+
+/// Removes a user and all of their permissions. Needs Administrate.
+/// A project's root user and the last administrator outside of any project
+/// with a working key can't be removed. Returns the removed user.
+pub fn remove_user<PD: ProtonDao> (
+    dao: &PD,
+    credentials: &Credentials,
+    uid: u32
+) -> Result<User, Error> {
+
+    // See if admin has permission to remove user
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let admin_uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
 
-    Err(Error::TodoErr)
-    // See if admin has permission to add user
     // Can't remove root
+    let user = try!(dao.get_user(uid));
+    if try!(utils::is_project_root(dao, &user)) {
+        return Err(Error::CannotRemoveRoot(user.name));
+    }
+    if try!(utils::is_last_working_admin(dao, uid)) {
+        return Err(Error::CannotRemoveLastAdmin(user.name));
+    }
+
     // Remove user
+    try!(dao.transaction(|| {
+        try!(dao.delete_user(uid));
+        audit::record(dao, admin_uid, "remove-user", None, "user", &uid.to_string(),
+            Some(format!("'{}'", user.name)), None)
+    }));
+    Ok(user)
+}
+
+/// Replaces a user's public key, keeping their uid and permissions. The old key is
+/// revoked. Users may rotate their own key; rotating anyone else's, such as a key
+/// that was lost, needs Administrate outside of any project.
+pub fn rotate_key<PD: ProtonDao> (
+    dao: &PD,
+    credentials: &Credentials,
+    uid: u32,
+    public_key: &str
+) -> Result<(), Error> {
+
+    let acting_uid = try!(check_key_permission(dao, credentials, uid));
+    replace_key(dao, acting_uid, "rotate-key", None, uid, public_key)
+}
+
+/// Revokes a user's public key without removing the user, who can't sign in again
+/// until given a new key with rotate_key. Users may revoke their own key; revoking
+/// anyone else's needs Administrate outside of any project. The last such
+/// administrator with a working key can't be locked out.
+pub fn revoke_key<PD: ProtonDao> (
+    dao: &PD,
+    credentials: &Credentials,
+    uid: u32
+) -> Result<(), Error> {
+
+    let acting_uid = try!(check_key_permission(dao, credentials, uid));
+    let user = try!(dao.get_user(uid));
+    let fingerprint = try!(keys::fingerprint(&user.public_key));
+    if try!(dao.is_key_revoked(&user.public_key)) {
+        return Err(Error::KeyRevoked(fingerprint));
+    }
+    if try!(utils::is_last_working_admin(dao, uid)) {
+        return Err(Error::CannotRevokeLastAdmin(user.name));
+    }
+
+    dao.transaction(|| {
+        try!(dao.revoke_key(uid, &user.public_key));
+        audit::record(dao, acting_uid, "revoke-key", None, "user", &uid.to_string(),
+            Some(fingerprint), None)
+    })
+}
 
-}
\ No newline at end of file
+/// Gives a project's root user a new public key when theirs is lost or compromised,
+/// revoking the old one. Needs Administrate outside of any project.
+/// Returns the root user's id.
+pub fn recover_root_key<PD: ProtonDao> (
+    dao: &PD,
+    credentials: &Credentials,
+    proj_name: &str,
+    public_key: &str
+) -> Result<u32, Error> {
+
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    let admin_uid = try!(utils::check_valid_permission(dao, credentials, None, &valid_permissions));
+
+    // Root is the root_<project> user holding Administrate within the project
+    let _ = try!(dao.get_project(proj_name));
+    let root_name = format!("{}_{}", "root", proj_name);
+    let mut roots = vec![];
+    for user in try!(dao.get_users_by_name(&root_name)) {
+        let permissions = try!(dao.get_all_permissions(user.uid));
+        if permissions.iter().any(|p| {
+            p.permission == PermissionEnum::Administrate && p.project.as_ref().map(|name| name.as_str()) == Some(proj_name)
+        }) {
+            roots.push(user.uid);
+        }
+    }
+    let root_uid = match roots.len() {
+        0 => return Err(Error::UserNotFound),
+        1 => roots[0],
+        _ => return Err(Error::AmbiguousUser(root_name, roots)),
+    };
+
+    try!(replace_key(dao, admin_uid, "recover-root-key", Some(proj_name), root_uid, public_key));
+    Ok(root_uid)
+}
+
+/// Users may manage their own key; managing anyone else's needs Administrate
+/// outside of any project. Returns the acting user's id.
+fn check_key_permission<PD: ProtonDao>(dao: &PD, credentials: &Credentials, uid: u32) -> Result<u32, Error> {
+    let acting_uid = try!(auth::verify_credentials(dao, credentials));
+    if acting_uid == uid {
+        return Ok(acting_uid);
+    }
+    let valid_permissions = vec![PermissionEnum::Administrate];
+    try!(utils::check_user_permission(dao, acting_uid, None, &valid_permissions));
+    Ok(acting_uid)
+}
+
+/// Gives the user a new public key and revokes their old one, if it isn't already
+fn replace_key<PD: ProtonDao>(
+    dao: &PD,
+    acting_uid: u32,
+    command: &str,
+    project: Option<&str>,
+    uid: u32,
+    public_key: &str
+) -> Result<(), Error> {
+
+    let user = try!(dao.get_user(uid));
+    try!(utils::check_new_public_key(dao, &user.name, public_key));
+    let old_fingerprint = try!(keys::fingerprint(&user.public_key));
+    let new_fingerprint = try!(keys::fingerprint(public_key));
+    let old_revoked = try!(dao.is_key_revoked(&user.public_key));
+
+    dao.transaction(|| {
+        if !old_revoked {
+            try!(dao.revoke_key(uid, &user.public_key));
+        }
+        try!(dao.set_public_key(uid, public_key));
+        audit::record(dao, acting_uid, command, project, "user", &uid.to_string(),
+            Some(old_fingerprint), Some(new_fingerprint))
+    })
+}
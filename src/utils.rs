@@ -1,13 +1,16 @@
 use std::fs::{self, File};
 use std::io::Read;
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use openssl::rsa;
 use openssl::pkey;
 use rustc_serialize::json;
 
+use auth;
+use keys::{self, PublicKey};
 use dao::ProtonDao;
-use project_types::PermissionEnum;
+use project_types::{Credentials, PermissionEnum, User};
 use error::Error;
 
 /// Converts a JSON 2d sequence array into a Vec<Vec<u16>>
@@ -38,35 +41,155 @@ pub fn create_pub_priv_keys() -> Result<(String, String), Error> {
     Ok((public_key_str, private_key_str))
 }
 
-/// Checks that the given string is a valid RSA public key
-pub fn validate_rsa_pub_key(pub_key: &str) -> bool {
-    rsa::Rsa::public_key_from_pem(&pub_key.bytes().collect::<Vec<u8>>())
-        .is_ok()
+/// Checks that a public key can be given to a new user: it must be a valid
+/// public key that no other user has, in any format, and that hasn't been revoked
+pub fn check_new_public_key<PD: ProtonDao>(dao: &PD, name: &str, public_key: &str) -> Result<(), Error> {
+    if !validate_pub_key(public_key) {
+        return Err(Error::InvalidPublicKey(public_key.to_owned()));
+    }
+    if try!(dao.is_key_revoked(public_key)) {
+        return Err(Error::KeyRevoked(try!(keys::fingerprint(public_key))));
+    }
+    match dao.get_user_id(public_key) {
+        Ok(_) => Err(Error::DuplicateUser(public_key.to_owned(), name.to_owned())),
+        Err(Error::PublicKeyNotFound(_)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Checks that the given string is a valid PEM RSA or OpenSSH public key
+pub fn validate_pub_key(pub_key: &str) -> bool {
+    PublicKey::parse(pub_key).is_ok()
 }
 
-/// Checks if the user with a public key at the given path has
-/// one of the given valid permissions
-/// Returns this user if found and has permission, else error
-pub fn check_valid_permission<P: AsRef<Path>, PD: ProtonDao>(
+/// Checks that the credentials are valid and that their user holds a permission
+/// granting one of the given valid permissions, either within the project the
+/// command acts on or outside of any project.
+/// Returns this user's id if so, else error
+pub fn check_valid_permission<PD: ProtonDao>(
     dao: &PD,
-    public_key_path: P,
+    credentials: &Credentials,
+    project: Option<&str>,
     valid_permissions: &Vec<PermissionEnum>
 ) -> Result<u32, Error> {
+
+    let uid = try!(auth::verify_credentials(dao, credentials));
+    try!(check_user_permission(dao, uid, project, valid_permissions));
+    Ok(uid)
+}
+
+/// Like check_valid_permission, for a user whose credentials were already verified.
+/// Credentials only work once, so commands verify them once and check by uid after.
+pub fn check_user_permission<PD: ProtonDao>(
+    dao: &PD,
+    uid: u32,
+    project: Option<&str>,
+    valid_permissions: &Vec<PermissionEnum>
+) -> Result<(), Error> {
+
+    let projects = project.iter().map(|name| name.to_string()).collect::<Vec<String>>();
+    check_permission_in_projects(dao, uid, &projects, valid_permissions)
+}
+
+/// Like check_valid_permission, for commands acting on a sequence: permissions
+/// within any project whose playlist has the sequence count
+pub fn check_sequence_permission<PD: ProtonDao>(
+    dao: &PD,
+    credentials: &Credentials,
+    seqid: u32,
+    valid_permissions: &Vec<PermissionEnum>
+) -> Result<u32, Error> {
+
+    let uid = try!(auth::verify_credentials(dao, credentials));
+    try!(check_user_sequence_permission(dao, uid, seqid, valid_permissions));
+    Ok(uid)
+}
+
+/// Like check_sequence_permission, for a user whose credentials were already verified
+pub fn check_user_sequence_permission<PD: ProtonDao>(
+    dao: &PD,
+    uid: u32,
+    seqid: u32,
+    valid_permissions: &Vec<PermissionEnum>
+) -> Result<(), Error> {
+
+    let projects = try!(sequence_projects(dao, seqid));
+    check_permission_in_projects(dao, uid, &projects, valid_permissions)
+}
+
+/// Names of the projects whose playlist has the sequence
+pub fn sequence_projects<PD: ProtonDao>(dao: &PD, seqid: u32) -> Result<Vec<String>, Error> {
+    let projects = try!(dao.list_projects(None))
+        .into_iter()
+        .filter(|project| project.playlist.contains(&seqid))
+        .map(|project| project.name)
+        .collect::<Vec<String>>();
+    Ok(projects)
+}
+
+fn check_permission_in_projects<PD: ProtonDao>(
+    dao: &PD,
+    uid: u32,
+    projects: &[String],
+    valid_permissions: &Vec<PermissionEnum>
+) -> Result<(), Error> {
     
-    if valid_permissions.len() > 0 {
-        let public_key = try!(file_as_string(public_key_path));
-        let uid = try!(dao.get_user_id(&public_key));
-        let permissions = try!(dao.get_all_permissions(uid));
-        for permission in permissions {
-            if valid_permissions.contains(&permission.permission) {
-                return Ok(uid);
-            }
+    let permissions = try!(dao.get_all_permissions(uid));
+    for permission in permissions {
+        let in_scope = match permission.project {
+            Some(ref name) => projects.contains(name),
+            None => true,
+        };
+        if in_scope && valid_permissions.iter().any(|valid| permission.permission.grants(valid)) {
+            return Ok(());
         }
     }
 
     Err(Error::UnauthorizedAction)
 }
 
+/// True if some user holds Administrate outside of any project
+pub fn has_global_admin<PD: ProtonDao>(dao: &PD) -> Result<bool, Error> {
+    for user in try!(dao.list_users(None)) {
+        if try!(is_global_admin(dao, user.uid)) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// True if the user holds Administrate outside of any project
+pub fn is_global_admin<PD: ProtonDao>(dao: &PD, uid: u32) -> Result<bool, Error> {
+    let permissions = try!(dao.get_all_permissions(uid));
+    Ok(permissions.iter().any(|p| p.project.is_none() && p.permission == PermissionEnum::Administrate))
+}
+
+/// True if the user is the only one holding Administrate outside of any project
+/// whose key still works
+pub fn is_last_working_admin<PD: ProtonDao>(dao: &PD, uid: u32) -> Result<bool, Error> {
+    if !try!(is_global_admin(dao, uid)) {
+        return Ok(false);
+    }
+    for user in try!(dao.list_users(None)) {
+        if user.uid != uid && try!(is_global_admin(dao, user.uid)) && !try!(dao.is_key_revoked(&user.public_key)) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// True if the user is the root user of an existing project (root_<project>)
+pub fn is_project_root<PD: ProtonDao>(dao: &PD, user: &User) -> Result<bool, Error> {
+    if !user.name.starts_with("root_") {
+        return Ok(false);
+    }
+    match dao.get_project(&user.name["root_".len()..]) {
+        Ok(_) => Ok(true),
+        Err(Error::ProjectNotFound(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
 /// Returns the last part of the path, the file name, if no problems arise
 /// Raises errors if the file name is invalid or cannot be converted to UTF-8
 pub fn file_name_from_path<P: AsRef<Path>>(path: P) -> Result<String, Error> {
@@ -114,3 +237,100 @@ pub fn file_as_string<P: AsRef<Path>>(path: P) -> Result<String, Error> {
         })
         .map_err(Error::Io)
 }
+
+/// Seconds since the Unix epoch
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Parses a UTC time given as seconds since the Unix epoch or as
+/// YYYY-MM-DD, optionally followed by THH:MM or THH:MM:SS (a space works too)
+pub fn parse_time(text: &str) -> Result<u64, Error> {
+    let invalid = || Error::InvalidTime(text.to_owned());
+    if let Ok(secs) = text.parse::<u64>() {
+        return Ok(secs);
+    }
+
+    let (date, time) = match text.find(|c: char| c == 'T' || c == ' ') {
+        Some(i) => (&text[..i], &text[i + 1..]),
+        None => (text, ""),
+    };
+    let date_parts = try!(date.split('-')
+        .map(|part| part.parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|_| invalid()));
+    let time_parts = if time.is_empty() {
+        vec![]
+    } else {
+        try!(time.split(':')
+            .map(|part| part.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|_| invalid()))
+    };
+    if date_parts.len() != 3 || time_parts.len() == 1 || time_parts.len() > 3 {
+        return Err(invalid());
+    }
+    let (year, month, day) = (date_parts[0], date_parts[1], date_parts[2]);
+    let hour = time_parts.get(0).cloned().unwrap_or(0);
+    let minute = time_parts.get(1).cloned().unwrap_or(0);
+    let second = time_parts.get(2).cloned().unwrap_or(0);
+    if year < 1970 || month < 1 || month > 12 || day < 1 || day > days_in_month(year, month) ||
+        hour > 23 || minute > 59 || second > 59 {
+        return Err(invalid());
+    }
+
+    let days = days_from_civil(year, month, day);
+    Ok(days * 86400 + hour as u64 * 3600 + minute as u64 * 60 + second as u64)
+}
+
+/// Formats seconds since the Unix epoch as YYYY-MM-DD HH:MM:SS (UTC)
+pub fn format_time(secs: u64) -> String {
+    let (year, month, day) = civil_from_days(secs / 86400);
+    let secs_of_day = secs % 86400;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day,
+        secs_of_day / 3600, secs_of_day % 3600 / 60, secs_of_day % 60)
+}
+
+fn is_leap_year(year: u32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 => if is_leap_year(year) { 29 } else { 28 },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days from 1970-01-01 to the given date (not before 1970)
+fn days_from_civil(year: u32, month: u32, day: u32) -> u64 {
+    let mut days = (1970..year)
+        .map(|y| if is_leap_year(y) { 366 } else { 365 })
+        .sum::<u64>();
+    days += (1..month).map(|m| days_in_month(year, m) as u64).sum::<u64>();
+    days + day as u64 - 1
+}
+
+/// The date days after 1970-01-01
+fn civil_from_days(mut days: u64) -> (u32, u32, u32) {
+    let mut year = 1970;
+    loop {
+        let year_days = if is_leap_year(year) { 366 } else { 365 };
+        if days < year_days {
+            break;
+        }
+        days -= year_days;
+        year += 1;
+    }
+    let mut month = 1;
+    while days >= days_in_month(year, month) as u64 {
+        days -= days_in_month(year, month) as u64;
+        month += 1;
+    }
+    (year, month, days as u32 + 1)
+}
@@ -0,0 +1,197 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{DaoMemory, PermissionDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{AuditFilter, LengthPolicy, PermissionEnum, ResampleMode};
+use proton_cli::utils;
+
+
+/// Creates "Test Show" (root has the good test key) with one sequence not yet
+/// in its playlist, and an operator with the second test key.
+/// Returns the sequence id and the operator's uid.
+fn create_show(dao: &DaoMemory) -> (u32, u32) {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_id = proton_cli::new_layout(dao, &admin(), common::get_test_file_path(TestFile::SmallLayout))
+        .expect("Error creating layout");
+    let root_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub))
+        .expect("Error reading key");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &root_key)
+        .expect("Error creating project");
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        common::get_test_file_path(TestFile::TestMusic),
+        1000,
+        50,
+        common::get_test_file_path(TestFile::SmallVixenData),
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+
+    let operator_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKey2Pub))
+        .expect("Error reading key");
+    let operator_uid = dao.add_user("operator", &operator_key).expect("Error adding user");
+    let _ = dao.add_permission(operator_uid, Some("Test Show"), &PermissionEnum::Operator)
+        .expect("Error adding permission");
+    let _ = dao.add_permission(operator_uid, None, &PermissionEnum::EditSequence(seqid))
+        .expect("Error adding permission");
+    (seqid, operator_uid)
+}
+
+#[test]
+fn playlist_changes_record_who_and_what() {
+    let dao = DaoMemory::new();
+    let (seqid, operator_uid) = create_show(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let operator = common::sign_in(&dao, TestKey::GoodKey2Pem);
+    let admin_uid = dao.get_users_by_name("admin").expect("Error getting admin")[0].uid;
+
+    proton_cli::insert_sequence(&dao, &operator, "Test Show", seqid, None).expect("Error inserting sequence");
+    proton_cli::remove_sequence(&dao, &admin(), "Test Show", seqid).expect("Error removing sequence");
+
+    let filter = AuditFilter {
+        project: Some("Test Show".to_owned()),
+        ..AuditFilter::default()
+    };
+    let entries = proton_cli::audit_log(&dao, &admin(), &filter).expect("Error reading audit log");
+    let commands = entries.iter().map(|entry| entry.command.as_str()).collect::<Vec<&str>>();
+    assert_eq!(vec!["new-project", "insert-sequence", "remove-sequence"], commands);
+
+    let insert = &entries[1];
+    assert_eq!(operator_uid, insert.uid);
+    assert_eq!("project", insert.entity);
+    assert_eq!("Test Show", insert.target);
+    assert_eq!(Some("playlist []".to_owned()), insert.before);
+    assert_eq!(Some(format!("playlist [{}]", seqid)), insert.after);
+
+    let remove = &entries[2];
+    assert_eq!(admin_uid, remove.uid);
+    assert_eq!(Some(format!("playlist [{}]", seqid)), remove.before);
+    assert_eq!(Some("playlist []".to_owned()), remove.after);
+    assert!(remove.time >= insert.time);
+}
+
+#[test]
+fn sequence_changes_are_recorded_in_its_projects() {
+    let dao = DaoMemory::new();
+    let (seqid, _) = create_show(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let project_filter = AuditFilter {
+        project: Some("Test Show".to_owned()),
+        ..AuditFilter::default()
+    };
+
+    // Not in a playlist yet, so not in the project
+    let _ = proton_cli::resample_sequence(&dao, &admin(), seqid, 25, ResampleMode::Hold).expect("Error resampling sequence");
+    proton_cli::insert_sequence(&dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+    let _ = proton_cli::resample_sequence(&dao, &admin(), seqid, 50, ResampleMode::Hold).expect("Error resampling sequence");
+
+    let entries = proton_cli::audit_log(&dao, &admin(), &project_filter).expect("Error reading audit log");
+    let commands = entries.iter().map(|entry| entry.command.as_str()).collect::<Vec<&str>>();
+    assert_eq!(vec!["new-project", "insert-sequence", "resample-sequence"], commands);
+    assert_eq!(Some("20 frames of 50 ms (hold)".to_owned()), entries[2].after);
+}
+
+#[test]
+fn filters_by_user_entity_and_time() {
+    let dao = DaoMemory::new();
+    let (seqid, operator_uid) = create_show(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let operator = common::sign_in(&dao, TestKey::GoodKey2Pem);
+    proton_cli::insert_sequence(&dao, &operator, "Test Show", seqid, None).expect("Error inserting sequence");
+
+    let all = proton_cli::audit_log(&dao, &admin(), &AuditFilter::default()).expect("Error reading audit log");
+    assert_eq!(5, all.len());
+
+    let by_operator = AuditFilter { uid: Some(operator_uid), ..AuditFilter::default() };
+    let entries = proton_cli::audit_log(&dao, &admin(), &by_operator).expect("Error reading audit log");
+    assert_eq!(1, entries.len());
+    assert_eq!("insert-sequence", entries[0].command);
+
+    let sequences = AuditFilter {
+        entity: Some("sequence".to_owned()),
+        target: Some(seqid.to_string()),
+        ..AuditFilter::default()
+    };
+    let entries = proton_cli::audit_log(&dao, &admin(), &sequences).expect("Error reading audit log");
+    assert_eq!(1, entries.len());
+    assert_eq!("new-vixen-sequence", entries[0].command);
+
+    let now = utils::now_secs();
+    let future = AuditFilter { since: Some(now + 3600), ..AuditFilter::default() };
+    assert!(proton_cli::audit_log(&dao, &admin(), &future).expect("Error reading audit log").is_empty());
+    let past = AuditFilter { until: Some(now - 3600), ..AuditFilter::default() };
+    assert!(proton_cli::audit_log(&dao, &admin(), &past).expect("Error reading audit log").is_empty());
+    let window = AuditFilter { since: Some(now - 3600), until: Some(now + 3600), ..AuditFilter::default() };
+    assert_eq!(5, proton_cli::audit_log(&dao, &admin(), &window).expect("Error reading audit log").len());
+}
+
+#[test]
+fn reading_needs_administrate() {
+    let dao = DaoMemory::new();
+    let _ = create_show(&dao);
+    let project_filter = AuditFilter {
+        project: Some("Test Show".to_owned()),
+        ..AuditFilter::default()
+    };
+
+    let operator = common::sign_in(&dao, TestKey::GoodKey2Pem);
+    match proton_cli::audit_log(&dao, &operator, &project_filter) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for an operator"),
+    }
+
+    // The project's root sees its own project, but not the whole log
+    let root = || common::sign_in(&dao, TestKey::GoodKeyPem);
+    let entries = proton_cli::audit_log(&dao, &root(), &project_filter).expect("Error reading audit log");
+    assert_eq!(1, entries.len());
+    match proton_cli::audit_log(&dao, &root(), &AuditFilter::default()) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for the whole log"),
+    }
+}
+
+#[test]
+fn permission_changes_are_recorded() {
+    let dao = DaoMemory::new();
+    let (_, operator_uid) = create_show(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+
+    proton_cli::set_permission(&dao, &admin(), false, operator_uid, "Operator", Some("Test Show"), None, None)
+        .expect("Error revoking permission");
+
+    let filter = AuditFilter {
+        entity: Some("user".to_owned()),
+        target: Some(operator_uid.to_string()),
+        ..AuditFilter::default()
+    };
+    let entries = proton_cli::audit_log(&dao, &admin(), &filter).expect("Error reading audit log");
+    assert_eq!(1, entries.len());
+    assert_eq!("set-permission", entries[0].command);
+    assert_eq!(Some("Test Show".to_owned()), entries[0].project);
+    assert_eq!(Some("Operator in Test Show".to_owned()), entries[0].before);
+    assert_eq!(None, entries[0].after);
+}
+
+#[test]
+fn times_parse_and_format() {
+    assert_eq!(1488603967, utils::parse_time("2017-03-04T05:06:07").expect("Error parsing time"));
+    assert_eq!(1488603967, utils::parse_time("2017-03-04 05:06:07").expect("Error parsing time"));
+    assert_eq!(1488603960, utils::parse_time("2017-03-04T05:06").expect("Error parsing time"));
+    assert_eq!(1488603967, utils::parse_time("1488603967").expect("Error parsing time"));
+    assert_eq!(1456704000, utils::parse_time("2016-02-29").expect("Error parsing time"));
+    assert_eq!("2017-03-04 05:06:07", utils::format_time(1488603967));
+    assert_eq!("2016-02-29 00:00:00", utils::format_time(1456704000));
+
+    for text in &["2017-02-29", "2017-13-01", "2017-03-04T25:00", "2017-03-04T05", "yesterday"] {
+        match utils::parse_time(text) {
+            Err(Error::InvalidTime(_)) => {},
+            _ => panic!("Expected InvalidTime for {}", text),
+        }
+    }
+}
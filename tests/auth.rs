@@ -0,0 +1,96 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::TestKey;
+
+use proton_cli::dao::{DaoMemory, UserDao};
+use proton_cli::error::Error;
+use proton_cli::utils;
+
+
+/// Adds a user with the good test key. Returns their uid.
+fn add_good_user(dao: &DaoMemory) -> u32 {
+    let public_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub))
+        .expect("Error reading key");
+    dao.add_user("good", &public_key).expect("Error adding user")
+}
+
+#[test]
+fn accepts_signature_from_private_key() {
+    let dao = DaoMemory::new();
+    let uid = add_good_user(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    assert_eq!(uid, proton_cli::verify_credentials(&dao, &credentials).expect("Error verifying"));
+}
+
+#[test]
+fn rejects_signature_from_another_key() {
+    let dao = DaoMemory::new();
+    let _ = add_good_user(&dao);
+    let mut credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    let forged = common::sign_in(&dao, TestKey::GoodKey2Pem);
+    credentials.challenge = forged.challenge;
+    credentials.signature = forged.signature;
+
+    match proton_cli::verify_credentials(&dao, &credentials) {
+        Err(Error::InvalidSignature) => {},
+        _ => panic!("Expected InvalidSignature"),
+    }
+}
+
+#[test]
+fn rejects_old_challenges() {
+    let dao = DaoMemory::new();
+    let _ = add_good_user(&dao);
+    let challenge = vec![7; 32];
+    dao.add_challenge(&challenge, 0).expect("Error adding challenge");
+    let key_path = common::get_key_file_path(TestKey::GoodKeyPem);
+    let credentials = proton_cli::sign_challenge(&key_path, &challenge).expect("Error signing challenge");
+
+    match proton_cli::verify_credentials(&dao, &credentials) {
+        Err(Error::ExpiredChallenge) => {},
+        _ => panic!("Expected ExpiredChallenge"),
+    }
+}
+
+#[test]
+fn rejects_replayed_credentials() {
+    let dao = DaoMemory::new();
+    let uid = add_good_user(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    assert_eq!(uid, proton_cli::verify_credentials(&dao, &credentials).expect("Error verifying"));
+
+    match proton_cli::verify_credentials(&dao, &credentials) {
+        Err(Error::UnknownChallenge) => {},
+        _ => panic!("Expected UnknownChallenge"),
+    }
+}
+
+#[test]
+fn rejects_challenges_not_issued() {
+    let dao = DaoMemory::new();
+    let _ = add_good_user(&dao);
+    let key_path = common::get_key_file_path(TestKey::GoodKeyPem);
+    let credentials = proton_cli::sign_challenge(&key_path, &[7; 32]).expect("Error signing challenge");
+
+    match proton_cli::verify_credentials(&dao, &credentials) {
+        Err(Error::UnknownChallenge) => {},
+        _ => panic!("Expected UnknownChallenge"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "PublicKeyNotFound")]
+fn rejects_unknown_keys() {
+    let dao = DaoMemory::new();
+    let _ = add_good_user(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKey2Pem);
+    let _ = proton_cli::verify_credentials(&dao, &credentials).expect("Error verifying");
+}
+
+#[test]
+#[should_panic(expected = "InvalidPrivateKey")]
+fn public_key_cannot_sign() {
+    let _ = common::sign_in(&DaoMemory::new(), TestKey::GoodKeyPub);
+}
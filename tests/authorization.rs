@@ -0,0 +1,131 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{DaoMemory, LayoutDao, PermissionDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::PermissionEnum;
+use proton_cli::utils;
+
+
+/// Creates "Test Show" whose root has the good test key
+fn create_show(dao: &DaoMemory) {
+    common::new_admin(dao);
+    let layout = dao.new_layout("default", vec![]).expect("Error creating layout");
+    let root_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub))
+        .expect("Error reading key");
+    let _ = proton_cli::new_project(dao, &common::sign_in(dao, TestKey::AdminPem), "Test Show", layout.layout_id, &root_key)
+        .expect("Error creating project");
+}
+
+/// Adds a user with the second test key
+fn add_member(dao: &DaoMemory) -> u32 {
+    let key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKey2Pub))
+        .expect("Error reading key");
+    dao.add_user("alice", &key).expect("Error adding user")
+}
+
+#[test]
+fn only_one_admin_bootstraps() {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+
+    let uid = dao.get_users_by_name("admin").expect("Error getting admin")[0].uid;
+    let permissions = dao.get_all_permissions(uid).expect("Error getting permissions");
+    assert_eq!(1, permissions.len());
+    assert_eq!(PermissionEnum::Administrate, permissions[0].permission);
+    assert_eq!(None, permissions[0].project);
+
+    match proton_cli::new_admin(&dao, "mallory", &common::new_public_key()) {
+        Err(Error::AdminExists) => {},
+        _ => panic!("Expected AdminExists"),
+    }
+}
+
+#[test]
+fn new_admin_must_be_allowed_on_existing_databases() {
+    let dao = DaoMemory::new();
+    // Like a database migrated from a version whose administrators were all project roots
+    dao.set_new_admin_open(false).expect("Error closing new-admin");
+    match proton_cli::new_admin(&dao, "mallory", &common::new_public_key()) {
+        Err(Error::NewAdminClosed) => {},
+        _ => panic!("Expected NewAdminClosed"),
+    }
+    assert!(dao.list_users(None).expect("Error listing users").is_empty());
+
+    proton_cli::allow_new_admin(&dao).expect("Error allowing new-admin");
+    common::new_admin(&dao);
+    assert!(!dao.is_new_admin_open().expect("Error checking new-admin"));
+    match proton_cli::allow_new_admin(&dao) {
+        Err(Error::AdminExists) => {},
+        _ => panic!("Expected AdminExists"),
+    }
+}
+
+#[test]
+fn project_root_cannot_create_projects_or_layouts() {
+    let dao = DaoMemory::new();
+    create_show(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    match proton_cli::new_layout(&dao, &credentials(), common::get_test_file_path(TestFile::SmallLayout)) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for new_layout"),
+    }
+    match proton_cli::new_project(&dao, &credentials(), "Other Show", 1, &common::new_public_key()) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for new_project"),
+    }
+    match proton_cli::new_user(&dao, &credentials(), "bob", &common::new_public_key()) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for new_user"),
+    }
+}
+
+#[test]
+fn playlist_data_needs_viewer() {
+    let dao = DaoMemory::new();
+    create_show(&dao);
+    let uid = add_member(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKey2Pem);
+
+    match proton_cli::get_playlist_data(&dao, &credentials(), "Test Show") {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction"),
+    }
+
+    let _ = dao.add_permission(uid, Some("Test Show"), &PermissionEnum::Viewer).expect("Error adding permission");
+    let data = proton_cli::get_playlist_data(&dao, &credentials(), "Test Show").expect("Error getting playlist data");
+    assert_eq!("[]", data);
+}
+
+#[test]
+fn lists_only_own_permissions_without_administrate() {
+    let dao = DaoMemory::new();
+    create_show(&dao);
+    let uid = add_member(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKey2Pem);
+
+    assert!(proton_cli::get_permissions(&dao, &credentials(), uid).expect("Error listing permissions").is_empty());
+    let root_uid = dao.get_users_by_name("root_Test Show").expect("Error getting root")[0].uid;
+    match proton_cli::get_permissions(&dao, &credentials(), root_uid) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction"),
+    }
+
+    let admin = common::sign_in(&dao, TestKey::AdminPem);
+    let permissions = proton_cli::get_permissions(&dao, &admin, root_uid).expect("Error listing permissions");
+    assert_eq!(1, permissions.len());
+}
+
+#[test]
+#[should_panic(expected = "PublicKeyNotFound")]
+fn get_user_needs_known_key() {
+    let dao = DaoMemory::new();
+    create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKey2Pem);
+    let _ = proton_cli::get_user(&dao, &credentials, common::get_key_file_path(TestKey::GoodKeyPub))
+        .expect("Error getting user");
+}
@@ -0,0 +1,204 @@
+extern crate proton_cli;
+extern crate tempdir;
+
+mod common;
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use common::{TestFile, TestKey};
+use tempdir::TempDir;
+
+use proton_cli::dao::{ChannelDao, DaoFile, DaoMemory, DataDao, LayoutDao, PermissionDao, ProjectDao, SequenceDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{LengthPolicy, PermissionEnum};
+
+
+/// Creates "Test Show" with one vixen sequence in its playlist. Returns the root public key.
+fn create_show(dao: &DaoMemory) -> String {
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let music_path = common::get_test_file_path(TestFile::TestMusic);
+    let data_path = common::get_test_file_path(TestFile::SmallVixenData);
+
+    let layout_id = proton_cli::new_layout(dao, &admin(), &layout_path).expect("Error creating layout");
+    let root_pub_key = common::new_public_key();
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &root_pub_key).expect("Error creating project");
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        music_path,
+        1000,
+        50,
+        data_path,
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+    proton_cli::insert_sequence(dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+    root_pub_key
+}
+
+fn music_dir() -> std::path::PathBuf {
+    common::get_test_file_path(TestFile::TestMusic)
+        .parent()
+        .expect("Error getting music directory")
+        .to_path_buf()
+}
+
+#[test]
+fn round_trips_into_another_backend() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let bundle_path = root.path().join("show.pbundle");
+    let import_music_dir = root.path().join("Music");
+
+    let source = DaoMemory::new();
+    common::new_admin(&source);
+    let root_pub_key = create_show(&source);
+    let _ = source.add_user("Filler", "pub").expect("Error adding user");
+    proton_cli::export_project(&source, &common::sign_in(&source, TestKey::AdminPem), "Test Show", &bundle_path, music_dir()).expect("Error exporting project");
+
+    let target = DaoFile::open(root.path().join("show.pdb")).expect("Error creating project database");
+    common::new_admin(&target);
+    let report = proton_cli::import_project(&target, &common::sign_in(&target, TestKey::AdminPem), &bundle_path, None, &import_music_dir)
+        .expect("Error importing project");
+    assert_eq!("Test Show", report.project_name);
+    assert!(report.notes.is_empty());
+
+    // Project, sequence and data
+    let project = target.get_project("Test Show").expect("Error getting project");
+    assert_eq!(1, project.playlist.len());
+    let seqid = project.playlist[0];
+    let sequence = target.get_sequence(seqid).expect("Error getting sequence");
+    assert_eq!("Test Sequence", sequence.name);
+    assert_eq!(project.layout_id, sequence.layout_id);
+    let chan_ids = target.get_channel_ids(seqid).expect("Error getting channel ids");
+    assert_eq!(3, chan_ids.len());
+    let mut dmx_data = chan_ids.iter()
+        .map(|chanid| {
+            let channel = target.get_channel(*chanid).expect("Error getting channel");
+            let data = target.get_data(seqid, *chanid).expect("Error getting data");
+            (channel.channel_dmx, data[0])
+        })
+        .collect::<Vec<(u32, u16)>>();
+    dmx_data.sort();
+    assert_eq!(vec![(1, 10), (2, 20), (3, 30)], dmx_data);
+
+    // Root user and their permissions come along; unrelated users don't
+    // (the target's admin was there before)
+    let root_uid = target.get_user_id(&root_pub_key).expect("Error getting root uid");
+    let permissions = target.get_all_permissions(root_uid).expect("Error getting permissions");
+    assert_eq!(1, permissions.len());
+    assert_eq!(PermissionEnum::Administrate, permissions[0].permission);
+    assert_eq!(Some("Test Show".to_owned()), permissions[0].project);
+    assert_eq!(2, target.list_users(None).expect("Error listing users").len());
+
+    // Music file
+    let music_name = sequence.music_file_name;
+    assert_eq!(
+        fs::metadata(music_dir().join(&music_name)).expect("Error reading music").len(),
+        fs::metadata(import_music_dir.join(&music_name)).expect("Error reading imported music").len());
+}
+
+#[test]
+fn import_under_new_name_reuses_users() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let bundle_path = root.path().join("show.pbundle");
+
+    let source = DaoMemory::new();
+    common::new_admin(&source);
+    let root_pub_key = create_show(&source);
+    proton_cli::export_project(&source, &common::sign_in(&source, TestKey::AdminPem), "Test Show", &bundle_path, music_dir())
+        .expect("Error exporting project");
+
+    // The target already knows the root's key, under another name
+    let target = DaoMemory::new();
+    common::new_admin(&target);
+    let admin = || common::sign_in(&target, TestKey::AdminPem);
+    let root_uid = target.add_user("show root", &root_pub_key).expect("Error adding user");
+    let report = proton_cli::import_project(&target, &admin(), &bundle_path, Some("Test Show Copy"), root.path().join("Music"))
+        .expect("Error importing project");
+    assert_eq!(1, report.notes.len());
+    assert_eq!(2, target.list_users(None).expect("Error listing users").len());
+
+    // The reused root administers the copy
+    let projects = target.get_all_permissions(root_uid).expect("Error getting permissions")
+        .into_iter()
+        .map(|permission| permission.project)
+        .collect::<Vec<Option<String>>>();
+    assert_eq!(vec![Some("Test Show Copy".to_owned())], projects);
+    assert_eq!(1, target.get_project("Test Show Copy").expect("Error getting copy").playlist.len());
+}
+
+#[test]
+fn import_into_same_database_reuses_layout() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let bundle_path = root.path().join("show.pbundle");
+
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let _ = create_show(&dao);
+    proton_cli::export_project(&dao, &admin(), "Test Show", &bundle_path, music_dir()).expect("Error exporting project");
+
+    let report = proton_cli::import_project(&dao, &admin(), &bundle_path, Some("Test Show Copy"), music_dir())
+        .expect("Error importing project");
+    // The layout and the root user are reused
+    assert_eq!(2, report.notes.len());
+    assert!(report.notes.iter().any(|note| note.starts_with("Layout 'Small Layout' already exists")));
+
+    let original = dao.get_project("Test Show").expect("Error getting project");
+    let copy = dao.get_project("Test Show Copy").expect("Error getting copy");
+    assert_eq!(original.layout_id, copy.layout_id);
+    assert_eq!(1, copy.playlist.len());
+    assert!(copy.playlist[0] != original.playlist[0]);
+    let sequence = dao.get_sequence(copy.playlist[0]).expect("Error getting sequence");
+    assert_eq!(original.layout_id, sequence.layout_id);
+}
+
+#[test]
+fn conflicts_are_reported_together() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let bundle_path = root.path().join("show.pbundle");
+    let import_music_dir = root.path().join("Music");
+
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let _ = create_show(&dao);
+    let sequence = dao.list_sequences(None).expect("Error listing sequences").remove(0);
+    proton_cli::export_project(&dao, &admin(), "Test Show", &bundle_path, music_dir()).expect("Error exporting project");
+
+    // Same name, different song
+    fs::create_dir(&import_music_dir).expect("Error creating music dir");
+    let mut music_file = File::create(import_music_dir.join(&sequence.music_file_name))
+        .expect("Error creating music file");
+    music_file.write_all(b"not the same song").expect("Error writing music file");
+
+    // A layout of the same name that has since been repatched is not reused
+    let _ = dao.patch_channel(sequence.layout_id, 3, 30).expect("Error patching channel");
+
+    let num_sequences = dao.list_sequences(None).expect("Error listing sequences").len();
+    match proton_cli::import_project(&dao, &admin(), &bundle_path, None, &import_music_dir) {
+        Err(Error::ImportConflicts(conflicts)) => {
+            // Project, music file, layout and both fixtures
+            assert_eq!(5, conflicts.len());
+            assert!(conflicts.contains(&"layout 'Small Layout' already exists with other fixtures".to_owned()));
+            assert!(conflicts.contains(&"fixture 'Megatree' already exists".to_owned()));
+        },
+        Err(e) => panic!("Wrong error: {}", e),
+        Ok(_) => panic!("Imported over an existing project"),
+    }
+    assert_eq!(num_sequences, dao.list_sequences(None).expect("Error listing sequences").len());
+}
+
+#[test]
+#[should_panic(expected = "FileNotFound")]
+fn export_fails_without_music() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let _ = create_show(&dao);
+    proton_cli::export_project(&dao, &common::sign_in(&dao, TestKey::AdminPem), "Test Show", root.path().join("show.pbundle"), Path::new("no_such_dir"))
+        .expect("Error exporting project");
+}
@@ -0,0 +1,160 @@
+extern crate proton_cli;
+extern crate rustc_serialize;
+extern crate tempdir;
+
+mod common;
+
+use std::fs::File;
+use std::io::{Read, Write};
+
+use common::{TestFile, TestKey};
+use rustc_serialize::json;
+use tempdir::TempDir;
+
+use proton_cli::dao::{DaoMemory, DataDao, FixtureDao, LayoutDao, PermissionDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{LengthPolicy, PermissionEnum, SectionCheckout};
+use proton_cli::utils;
+
+
+/// Creates "Test Show" playing a one second sequence of the small layout (20 frames
+/// of 10, 20 and 30), a sequencer of the show with the good test key, and a section
+/// from 250 to 500 ms over the Megatree. Returns the sequence id, the section id
+/// and the Megatree's channel ids.
+fn create_section(dao: &DaoMemory) -> (u32, u32, Vec<u32>) {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_id = proton_cli::new_layout(dao, &admin(), common::get_test_file_path(TestFile::SmallLayout))
+        .expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &common::new_public_key())
+        .expect("Error creating project");
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        common::get_test_file_path(TestFile::TestMusic),
+        1000,
+        50,
+        common::get_test_file_path(TestFile::SmallVixenData),
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+    proton_cli::insert_sequence(dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+
+    let key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub)).expect("Error reading key");
+    let uid = dao.add_user("sequencer", &key).expect("Error adding user");
+    let _ = dao.add_permission(uid, Some("Test Show"), &PermissionEnum::Sequencer).expect("Error adding permission");
+
+    let megatree = dao.get_layout(layout_id).expect("Error getting layout").fixtures.iter()
+        .map(|fixid| dao.get_fixture(*fixid).expect("Error getting fixture"))
+        .find(|fixture| fixture.name == "Megatree")
+        .expect("No Megatree fixture");
+    let secid = proton_cli::new_section(dao, &common::sign_in(dao, TestKey::GoodKeyPem), seqid, 250, 500, vec![megatree.fixid])
+        .expect("Error creating section");
+    (seqid, secid, megatree.channels)
+}
+
+#[test]
+fn checkin_writes_back_only_the_section() {
+    let dao = DaoMemory::new();
+    let (seqid, secid, chan_ids) = create_section(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let checkout_path = root.path().join("section.json");
+
+    let _ = proton_cli::checkout_section(&dao, &credentials(), secid, &checkout_path).expect("Error checking out");
+    let mut checkout_json = String::new();
+    let _ = File::open(&checkout_path).and_then(|mut file| file.read_to_string(&mut checkout_json))
+        .expect("Error reading checkout");
+    let mut checkout: SectionCheckout = json::decode(&checkout_json).expect("Error decoding checkout");
+    assert_eq!((5, 5), (checkout.first_frame, checkout.num_frames));
+    assert_eq!(2, checkout.channels.len());
+    assert_eq!(vec![10; 5], checkout.channels[0].frames);
+    assert_eq!(vec![20; 5], checkout.channels[1].frames);
+
+    // Edit the red channel
+    checkout.channels[0].frames = vec![99; 5];
+    let _ = File::create(&checkout_path)
+        .and_then(|mut file| file.write_all(json::encode(&checkout).expect("Error encoding").as_bytes()))
+        .expect("Error writing checkout");
+    let changed = proton_cli::checkin_section(&dao, &credentials(), secid, &checkout_path).expect("Error checking in");
+    assert_eq!(1, changed);
+
+    let mut expected = vec![10; 20];
+    for frame in &mut expected[5..10] {
+        *frame = 99;
+    }
+    assert_eq!(expected, dao.get_data(seqid, checkout.channels[0].chanid).expect("Error getting data"));
+    let green = chan_ids.iter().find(|chanid| **chanid != checkout.channels[0].chanid).expect("No green channel");
+    assert_eq!(vec![20; 20], dao.get_data(seqid, *green).expect("Error getting data"));
+}
+
+#[test]
+fn checkin_refused_after_section_changed() {
+    let dao = DaoMemory::new();
+    let (seqid, secid, chan_ids) = create_section(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+    let mut stale = proton_cli::create_checkout(&dao, &credentials(), secid).expect("Error checking out");
+
+    // Changes outside the section don't matter
+    let mut data = dao.get_data(seqid, chan_ids[0]).expect("Error getting data");
+    data[0] = 0;
+    dao.update_data(seqid, chan_ids[0], &data).expect("Error updating data");
+    let mut first = proton_cli::create_checkout(&dao, &credentials(), secid).expect("Error checking out");
+    assert_eq!(stale.checksum, first.checksum);
+
+    first.channels[1].frames[0] = 1;
+    let _ = proton_cli::apply_checkin(&dao, &credentials(), secid, &first).expect("Error checking in");
+
+    stale.channels[0].frames[0] = 2;
+    match proton_cli::apply_checkin(&dao, &credentials(), secid, &stale) {
+        Err(Error::SectionChanged(id)) => assert_eq!(secid, id),
+        _ => panic!("Expected SectionChanged"),
+    }
+    let stale_chanid = stale.channels[0].chanid;
+    assert_eq!(10, dao.get_data(seqid, stale_chanid).expect("Error getting data")[5]);
+}
+
+#[test]
+fn checkin_needs_edit_section() {
+    let dao = DaoMemory::new();
+    let (_, secid, _) = create_section(&dao);
+    let checkout = proton_cli::create_checkout(&dao, &common::sign_in(&dao, TestKey::GoodKeyPem), secid)
+        .expect("Error checking out");
+
+    let key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKey2Pub)).expect("Error reading key");
+    let uid = dao.add_user("viewer", &key).expect("Error adding user");
+    let _ = dao.add_permission(uid, Some("Test Show"), &PermissionEnum::Viewer).expect("Error adding permission");
+    let viewer = || common::sign_in(&dao, TestKey::GoodKey2Pem);
+    match proton_cli::create_checkout(&dao, &viewer(), secid) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for create_checkout"),
+    }
+    match proton_cli::apply_checkin(&dao, &viewer(), secid, &checkout) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for apply_checkin"),
+    }
+}
+
+#[test]
+fn checkin_checks_frame_and_channel_shape() {
+    let dao = DaoMemory::new();
+    let (_, secid, _) = create_section(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+    let checkout = || proton_cli::create_checkout(&dao, &credentials(), secid).expect("Error checking out");
+
+    let mut short_frames = checkout();
+    short_frames.channels[0].frames.pop();
+    let mut missing_channel = checkout();
+    missing_channel.channels.pop();
+    let mut unknown_channel = checkout();
+    unknown_channel.channels[1].chanid = 999;
+    let mut other_section = checkout();
+    other_section.secid = secid + 1;
+
+    for invalid in &[short_frames, missing_channel, unknown_channel, other_section] {
+        match proton_cli::apply_checkin(&dao, &credentials(), secid, invalid) {
+            Err(Error::InvalidCheckout(_)) => {},
+            _ => panic!("Expected InvalidCheckout"),
+        }
+    }
+}
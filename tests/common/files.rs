@@ -0,0 +1,42 @@
+use std::env;
+use std::path::PathBuf;
+
+
+#[allow(dead_code)]
+pub enum TestFile {
+	SmallLayout,
+	SmallPatch,
+	ShortVixenData,
+	SmallVixenData,
+	TestMusic,
+}
+
+#[allow(dead_code)]
+fn get_tests_dir_path() -> PathBuf {
+	let mut curr_dir = PathBuf::from(env::current_dir()
+		.expect("Error getting current directory"));
+	curr_dir.push("tests");
+	curr_dir
+}
+
+#[allow(dead_code)]
+pub fn get_test_file_path(file: TestFile) -> PathBuf {
+	let file_name = match file {
+		TestFile::SmallLayout => SMALL_LAYOUT,
+		TestFile::SmallPatch => SMALL_PATCH,
+		TestFile::ShortVixenData => SHORT_VIXEN_DATA,
+		TestFile::SmallVixenData => SMALL_VIXEN_DATA,
+		TestFile::TestMusic => TEST_MUSIC,
+	};
+
+	let mut file_path = get_tests_dir_path();
+	file_path.push(file_name);
+
+	file_path
+}
+
+const SMALL_LAYOUT: &'static str = "layouts/small_layout.json";
+const SMALL_PATCH: &'static str = "patches/small_patch.json";
+const SHORT_VIXEN_DATA: &'static str = "vixen/short_data.json";
+const SMALL_VIXEN_DATA: &'static str = "vixen/small_data.json";
+const TEST_MUSIC: &'static str = "music/test_1sec.ogg";
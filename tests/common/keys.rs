@@ -3,12 +3,29 @@ extern crate proton_cli;
 use std::env;
 use std::path::PathBuf;
 
+use proton_cli::dao::ProtonDao;
+use proton_cli::project_types::Credentials;
 
+
+#[allow(dead_code)]
 pub enum Key {
+	AdminPub,
+	AdminPem,
 	GoodKeyPub,
-	GoodKeyPem
+	GoodKeyPem,
+	GoodKey2Pub,
+	GoodKey2Pem,
+	GoodKeyOpenSshPub,
+	Ed25519Pub,
+	Ed25519Priv,
+	Ed25519EncryptedPriv,
+	EcdsaPub,
+	EcdsaPriv,
+	RsaOpenSshPub,
+	RsaOpenSshPriv
 }
 
+#[allow(dead_code)]
 fn get_tests_dir_path() -> PathBuf {
 	let mut curr_dir = PathBuf::from(env::current_dir()
 		.expect("Error getting current directory"));
@@ -16,10 +33,23 @@ fn get_tests_dir_path() -> PathBuf {
 	curr_dir
 }
 
+#[allow(dead_code)]
 pub fn get_key_file_path(key: Key) -> PathBuf {
 	let key_path = match key {
+		Key::AdminPub => ADMIN_PUB,
+		Key::AdminPem => ADMIN_PEM,
 		Key::GoodKeyPub => GOOD_KEY_PUB,
 		Key::GoodKeyPem => GOOD_KEY_PEM,
+		Key::GoodKey2Pub => GOOD_KEY2_PUB,
+		Key::GoodKey2Pem => GOOD_KEY2_PEM,
+		Key::GoodKeyOpenSshPub => GOOD_KEY_OPENSSH_PUB,
+		Key::Ed25519Pub => ED25519_PUB,
+		Key::Ed25519Priv => ED25519_PRIV,
+		Key::Ed25519EncryptedPriv => ED25519_ENCRYPTED_PRIV,
+		Key::EcdsaPub => ECDSA_PUB,
+		Key::EcdsaPriv => ECDSA_PRIV,
+		Key::RsaOpenSshPub => RSA_OPENSSH_PUB,
+		Key::RsaOpenSshPriv => RSA_OPENSSH_PRIV,
 	};
 
 	let mut file_path = get_tests_dir_path();
@@ -28,5 +58,40 @@ pub fn get_key_file_path(key: Key) -> PathBuf {
 	file_path
 }
 
+/// Signs a challenge issued by the dao with a private key, as the CLI does with <admin-key>.
+/// The credentials work for one command.
+#[allow(dead_code)]
+pub fn sign_in<PD: ProtonDao>(dao: &PD, key: Key) -> Credentials {
+	let challenge = proton_cli::new_challenge(dao).expect("Error creating challenge");
+	proton_cli::sign_challenge(get_key_file_path(key), &challenge).expect("Error signing challenge")
+}
+
+/// Registers the administrator of a fresh database, whose key is Key::AdminPem
+#[allow(dead_code)]
+pub fn new_admin<PD: ProtonDao>(dao: &PD) {
+	let public_key = proton_cli::utils::file_as_string(get_key_file_path(Key::AdminPub))
+		.expect("Error reading admin key");
+	let _ = proton_cli::new_admin(dao, "admin", &public_key).expect("Error adding admin");
+}
+
+/// A freshly generated public key, for users whose private key a test never needs
+#[allow(dead_code)]
+pub fn new_public_key() -> String {
+	let (public_key, _) = proton_cli::utils::create_pub_priv_keys().expect("Error creating keys");
+	public_key
+}
+
+const ADMIN_PUB: &'static str = "rsa_keys/root.pub";
+const ADMIN_PEM: &'static str = "rsa_keys/root.pem";
 const GOOD_KEY_PUB: &'static str = "rsa_keys/good_key.pub";
 const GOOD_KEY_PEM: &'static str = "rsa_keys/good_key.pem";
+const GOOD_KEY2_PUB: &'static str = "rsa_keys/good_key2.pub";
+const GOOD_KEY2_PEM: &'static str = "rsa_keys/good_key2.pem";
+const GOOD_KEY_OPENSSH_PUB: &'static str = "ssh_keys/good_key_openssh.pub";
+const ED25519_PUB: &'static str = "ssh_keys/id_ed25519.pub";
+const ED25519_PRIV: &'static str = "ssh_keys/id_ed25519";
+const ED25519_ENCRYPTED_PRIV: &'static str = "ssh_keys/id_ed25519_encrypted";
+const ECDSA_PUB: &'static str = "ssh_keys/id_ecdsa.pub";
+const ECDSA_PRIV: &'static str = "ssh_keys/id_ecdsa";
+const RSA_OPENSSH_PUB: &'static str = "ssh_keys/id_rsa.pub";
+const RSA_OPENSSH_PRIV: &'static str = "ssh_keys/id_rsa";
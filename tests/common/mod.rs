@@ -1,5 +1,10 @@
-
+mod files;
 mod keys;
 
+pub use self::files::TestFile;
+pub use self::files::get_test_file_path;
 pub use self::keys::Key as TestKey;
 pub use self::keys::get_key_file_path;
+pub use self::keys::sign_in;
+pub use self::keys::new_admin;
+pub use self::keys::new_public_key;
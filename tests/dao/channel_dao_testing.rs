@@ -22,6 +22,7 @@ pub struct ChannelDaoTesting {
         (Option<i32>, Option<i32>, Option<i32>)) -> Result<Channel, Error>>,
 	pub get_channel_fn: Box<Fn(u32) -> Result<Channel, Error>>,
 	pub get_last_channel_fn: Box<Fn(String) -> Result<Channel, Error>>,
+	pub list_channels_fn: Box<Fn(u32) -> Result<Vec<Channel>, Error>>,
 }
 
 
@@ -32,7 +33,8 @@ impl ChannelDaoTesting {
 		ChannelDaoTesting {
 			new_channel_fn: Box::new(|_, _, _, _, _, _, _, _| -> Result<Channel, Error> { Err(Error::TodoErr) }),
 			get_channel_fn: Box::new(|_| -> Result<Channel, Error> { Err(Error::TodoErr) }),
-			get_last_channel_fn: Box::new(|_| -> Result<Channel, Error> { Err(Error::TodoErr) })
+			get_last_channel_fn: Box::new(|_| -> Result<Channel, Error> { Err(Error::TodoErr) }),
+			list_channels_fn: Box::new(|_| -> Result<Vec<Channel>, Error> { Err(Error::TodoErr) })
 		}
 	}
 }
@@ -70,4 +72,8 @@ impl ChannelDao for ChannelDaoTesting {
     fn get_last_channel(&self, name: &str) -> Result<Channel, Error> {
     	(self.get_last_channel_fn)(name.to_owned())
     }
+
+    fn list_channels(&self, layoutid: u32) -> Result<Vec<Channel>, Error> {
+    	(self.list_channels_fn)(layoutid)
+    }
 }
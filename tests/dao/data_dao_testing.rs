@@ -1,7 +1,10 @@
 extern crate proton_cli;
 
+use std::collections::BTreeMap;
+
 use proton_cli::dao::DataDao;
 use proton_cli::error::Error;
+use proton_cli::project_types::DataCell;
 
 
 /// Implementation of DataDao for testing purposes. Uses given functions to return values.
@@ -13,6 +16,9 @@ pub struct DataDaoTesting {
 	pub new_data_default_fn: Box<Fn(u32, Vec<u32>, Vec<u16>) -> Result<(), Error>>,
 	pub new_data_fn: Box<Fn(u32, u32, Vec<u16>) -> Result<(), Error>>,	
 	pub get_data_fn: Box<Fn(u32, u32) -> Result<Vec<u16>, Error>>,
+	pub get_sequence_data_fn: Box<Fn(u32) -> Result<BTreeMap<u32, Vec<u16>>, Error>>,
+	pub get_data_range_fn: Box<Fn(u32, Vec<u32>, u32, u32) -> Result<BTreeMap<u32, Vec<u16>>, Error>>,
+	pub patch_data_fn: Box<Fn(u32, Vec<DataCell>) -> Result<(), Error>>,
 	pub update_data_fn: Box<Fn(u32, u32, Vec<u16>) -> Result<(), Error>>
 }
 
@@ -25,6 +31,9 @@ impl DataDaoTesting {
 			new_data_default_fn: Box::new(|_, _, _| -> Result<(), Error> { Err(Error::TodoErr) }),
 			new_data_fn: Box::new(|_, _, _| -> Result<(), Error>  { Err(Error::TodoErr) }),
 			get_data_fn: Box::new(|_, _| -> Result<Vec<u16>, Error> { Err(Error::TodoErr) }),
+			get_sequence_data_fn: Box::new(|_| -> Result<BTreeMap<u32, Vec<u16>>, Error> { Err(Error::TodoErr) }),
+			get_data_range_fn: Box::new(|_, _, _, _| -> Result<BTreeMap<u32, Vec<u16>>, Error> { Err(Error::TodoErr) }),
+			patch_data_fn: Box::new(|_, _| -> Result<(), Error> { Err(Error::TodoErr) }),
 			update_data_fn: Box::new(|_, _, _| -> Result<(), Error> { Err(Error::TodoErr) }),
 		}
 	}
@@ -54,6 +63,24 @@ impl DataDao for DataDaoTesting {
     	(self.get_data_fn)(seqid, chanid)
     }
     
+    fn get_sequence_data(&self, seqid: u32) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+    	(self.get_sequence_data_fn)(seqid)
+    }
+
+    fn get_data_range(
+        &self,
+        seqid: u32,
+        chan_ids: &[u32],
+        start_frame: u32,
+        end_frame: u32
+    ) -> Result<BTreeMap<u32, Vec<u16>>, Error> {
+    	(self.get_data_range_fn)(seqid, chan_ids.to_vec(), start_frame, end_frame)
+    }
+
+    fn patch_data(&self, seqid: u32, cells: &[DataCell]) -> Result<(), Error> {
+    	(self.patch_data_fn)(seqid, cells.to_vec())
+    }
+    
     fn update_data<'a>(&'a self, seqid: u32, chanid: u32, new_data: &'a Vec<u16>) -> Result<(), Error> {
     	(self.update_data_fn)(seqid, chanid, new_data.to_owned())
     }
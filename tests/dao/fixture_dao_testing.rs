@@ -16,6 +16,7 @@ pub struct FixtureDaoTesting {
 	pub get_last_fixture_fn: Box<Fn(String) -> Result<Fixture, Error>>,
 	pub get_num_channels_fn: Box<Fn(u32) -> Result<u32, Error>>,
 	pub fixture_exists_fn: Box<Fn(u32) -> Result<bool, Error>>,
+	pub list_fixtures_fn: Box<Fn(u32) -> Result<Vec<Fixture>, Error>>,
 }
 
 
@@ -29,6 +30,7 @@ impl FixtureDaoTesting {
 			get_last_fixture_fn: Box::new(|_| -> Result<Fixture, Error>  { Err(Error::TodoErr) }),
 			get_num_channels_fn: Box::new(|_| -> Result<u32, Error>  { Err(Error::TodoErr) }),
 			fixture_exists_fn: Box::new(|_| -> Result<bool, Error>  { Err(Error::TodoErr) }),
+			list_fixtures_fn: Box::new(|_| -> Result<Vec<Fixture>, Error> { Err(Error::TodoErr) })
 		}
 	}
 }
@@ -61,4 +63,7 @@ impl FixtureDao for FixtureDaoTesting {
     	(self.fixture_exists_fn)(fixid)
     }
 
+    fn list_fixtures(&self, layoutid: u32) -> Result<Vec<Fixture>, Error> {
+    	(self.list_fixtures_fn)(layoutid)
+    }
 }
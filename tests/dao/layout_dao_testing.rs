@@ -17,6 +17,7 @@ pub struct LayoutDaoTesting {
 	pub get_last_layout_fn: Box<Fn(String) -> Result<Layout, Error>>,
 	pub layout_exists_fn: Box<Fn(u32) -> Result<bool, Error>>,
 	pub patch_channel_fn: Box<Fn(u32, u32, u32) -> Result<u64, Error>>,
+	pub list_layouts_fn: Box<Fn(Option<String>) -> Result<Vec<Layout>, Error>>,
 }
 
 
@@ -30,7 +31,8 @@ impl LayoutDaoTesting {
 			get_layout_fn: Box::new(|_| -> Result<Layout, Error> { Err(Error::TodoErr) }),
 			get_last_layout_fn: Box::new(|_| -> Result<Layout, Error> { Err(Error::TodoErr) }),
 			layout_exists_fn: Box::new(|_| -> Result<bool, Error> { Err(Error::TodoErr) }),
-			patch_channel_fn: Box::new(|_, _, _| -> Result<u64, Error> { Err(Error::TodoErr) })
+			patch_channel_fn: Box::new(|_, _, _| -> Result<u64, Error> { Err(Error::TodoErr) }),
+			list_layouts_fn: Box::new(|_| -> Result<Vec<Layout>, Error> { Err(Error::TodoErr) })
 		}
 	}
 }
@@ -66,4 +68,7 @@ impl LayoutDao for LayoutDaoTesting {
     	(self.patch_channel_fn)(layoutid, channel_internal, channel_dmx)
     }
 
+    fn list_layouts(&self, name_filter: Option<&str>) -> Result<Vec<Layout>, Error> {
+    	(self.list_layouts_fn)(name_filter.map(|filter| filter.to_owned()))
+    }
 }
@@ -2,7 +2,7 @@ extern crate proton_cli;
 
 use proton_cli::dao::PermissionDao;
 use proton_cli::error::Error;
-use proton_cli::project_types::Permission;
+use proton_cli::project_types::{Permission, PermissionEnum};
 
 
 /// Implementation of PermissionDao for testing purposes. Uses given functions to return values.
@@ -11,7 +11,9 @@ use proton_cli::project_types::Permission;
 /// &str references are converted to Strings so we don't have to deal with lifetime headaches (bookdude13 tried on 12/25/16)
 #[allow(dead_code)]
 pub struct PermissionDaoTesting {
-	pub add_initial_permission_fn: Box<Fn(u32) -> Result<(), Error>>,
+	pub add_initial_permission_fn: Box<Fn(u32, String) -> Result<(), Error>>,
+	pub add_permission_fn: Box<Fn(u32, Option<String>, PermissionEnum) -> Result<Permission, Error>>,
+	pub revoke_permission_fn: Box<Fn(u32, Option<String>, PermissionEnum) -> Result<(), Error>>,
 	pub get_all_permissions_fn: Box<Fn(u32) -> Result<Vec<Permission>, Error>>,
 	pub get_permission_fn: Box<Fn(u32) -> Result<Permission, Error>>,
 }
@@ -22,7 +24,9 @@ impl PermissionDaoTesting {
 	#[allow(dead_code)]
 	pub fn new() -> PermissionDaoTesting {
 		PermissionDaoTesting {
-			add_initial_permission_fn: Box::new(|_| -> Result<(), Error> { Err(Error::TodoErr) }),
+			add_initial_permission_fn: Box::new(|_, _| -> Result<(), Error> { Err(Error::TodoErr) }),
+			add_permission_fn: Box::new(|_, _, _| -> Result<Permission, Error> { Err(Error::TodoErr) }),
+			revoke_permission_fn: Box::new(|_, _, _| -> Result<(), Error> { Err(Error::TodoErr) }),
 			get_all_permissions_fn: Box::new(|_| -> Result<Vec<Permission>, Error>  { Err(Error::TodoErr) }),
 			get_permission_fn: Box::new(|_| -> Result<Permission, Error> { Err(Error::TodoErr) }),
 		}
@@ -31,10 +35,18 @@ impl PermissionDaoTesting {
 
 /// The Dao implementation simply calls the corresponding stored function
 impl PermissionDao for PermissionDaoTesting {
-	fn add_initial_permission(&self, root_uid: u32) -> Result<(), Error> {
-		(self.add_initial_permission_fn)(root_uid)
+	fn add_initial_permission(&self, root_uid: u32, proj_name: &str) -> Result<(), Error> {
+		(self.add_initial_permission_fn)(root_uid, proj_name.to_owned())
 	}
 
+    fn add_permission(&self, uid: u32, project: Option<&str>, permission: &PermissionEnum) -> Result<Permission, Error> {
+    	(self.add_permission_fn)(uid, project.map(|name| name.to_owned()), permission.clone())
+    }
+
+    fn revoke_permission(&self, uid: u32, project: Option<&str>, permission: &PermissionEnum) -> Result<(), Error> {
+    	(self.revoke_permission_fn)(uid, project.map(|name| name.to_owned()), permission.clone())
+    }
+
     fn get_all_permissions(&self, uid: u32) -> Result<Vec<Permission>, Error> {
     	(self.get_all_permissions_fn)(uid)
     }
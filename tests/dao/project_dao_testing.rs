@@ -14,6 +14,7 @@ pub struct ProjectDaoTesting {
 	pub new_project_fn: Box<Fn(String, u32) -> Result<Project, Error>>,
 	pub get_project_fn: Box<Fn(String) -> Result<Project, Error>>,
 	pub update_project_fn: Box<Fn(Project) -> Result<(), Error>>,
+	pub list_projects_fn: Box<Fn(Option<String>) -> Result<Vec<Project>, Error>>,
 }
 
 
@@ -24,7 +25,8 @@ impl ProjectDaoTesting {
 		ProjectDaoTesting {
 			new_project_fn: Box::new(|_, _| -> Result<Project, Error> { Err(Error::TodoErr) }),
 			get_project_fn: Box::new(|_| -> Result<Project, Error> { Err(Error::TodoErr) }),
-			update_project_fn: Box::new(|_| -> Result<(), Error> { Err(Error::TodoErr) })
+			update_project_fn: Box::new(|_| -> Result<(), Error> { Err(Error::TodoErr) }),
+			list_projects_fn: Box::new(|_| -> Result<Vec<Project>, Error> { Err(Error::TodoErr) })
 		}
 	}
 }
@@ -42,4 +44,8 @@ impl ProjectDao for ProjectDaoTesting {
     fn update_project(&self, new_project: Project) -> Result<(), Error> {
     	(self.update_project_fn)(new_project)
     }
+
+    fn list_projects(&self, name_filter: Option<&str>) -> Result<Vec<Project>, Error> {
+    	(self.list_projects_fn)(name_filter.map(|filter| filter.to_owned()))
+    }
 }
@@ -11,7 +11,10 @@ use proton_cli::project_types::Section;
 /// &str references are converted to Strings so we don't have to deal with lifetime headaches (bookdude13 tried on 12/25/16)
 #[allow(dead_code)]
 pub struct SectionDaoTesting {
+	pub new_section_fn: Box<Fn(Section) -> Result<Section, Error>>,
 	pub get_section_fn: Box<Fn(u32) -> Result<Section, Error>>,
+	pub list_sections_fn: Box<Fn(u32) -> Result<Vec<Section>, Error>>,
+	pub delete_section_fn: Box<Fn(u32) -> Result<(), Error>>,
 }
 
 
@@ -20,15 +23,30 @@ impl SectionDaoTesting {
 	#[allow(dead_code)]
 	pub fn new() -> SectionDaoTesting {
 		SectionDaoTesting {
-			get_section_fn: Box::new(|_| -> Result<Section, Error> { Err(Error::TodoErr) })
+			new_section_fn: Box::new(|_| -> Result<Section, Error> { Err(Error::TodoErr) }),
+			get_section_fn: Box::new(|_| -> Result<Section, Error> { Err(Error::TodoErr) }),
+			list_sections_fn: Box::new(|_| -> Result<Vec<Section>, Error> { Err(Error::TodoErr) }),
+			delete_section_fn: Box::new(|_| -> Result<(), Error> { Err(Error::TodoErr) })
 		}
 	}
 }
 
 /// The Dao implementation simply calls the corresponding stored function
 impl SectionDao for SectionDaoTesting {
+	fn new_section(&self, section: &Section) -> Result<Section, Error> {
+		(self.new_section_fn)(section.clone())
+	}
+
 	fn get_section(&self, secid: u32) -> Result<Section, Error> {
 		(self.get_section_fn)(secid)
 	}
 
+	fn list_sections(&self, seqid: u32) -> Result<Vec<Section>, Error> {
+		(self.list_sections_fn)(seqid)
+	}
+
+	fn delete_section(&self, secid: u32) -> Result<(), Error> {
+		(self.delete_section_fn)(secid)
+	}
+
 }
@@ -2,7 +2,7 @@ extern crate proton_cli;
 
 use proton_cli::dao::SequenceDao;
 use proton_cli::error::Error;
-use proton_cli::project_types::Sequence;
+use proton_cli::project_types::{Sequence, SequenceDeletion};
 
 
 /// Implementation of SequenceDao for testing purposes. Uses given functions to return values.
@@ -15,8 +15,11 @@ pub struct SequenceDaoTesting {
 	pub get_last_sequence_fn: Box<Fn(String) -> Result<Sequence, Error>>,
 	pub new_sequence_fn: Box<Fn(Sequence) -> Result<Sequence, Error>>,
 	pub set_layout_fn: Box<Fn(u32, u32) -> Result<(), Error>>,
+	pub set_frames_fn: Box<Fn(u32, u32, u32) -> Result<(), Error>>,
 	pub sequence_exists_fn: Box<Fn(u32) -> Result<bool, Error>>,
 	pub get_channel_ids_fn: Box<Fn(u32) -> Result<Vec<u32>, Error>>,
+	pub list_sequences_fn: Box<Fn(Option<String>) -> Result<Vec<Sequence>, Error>>,
+	pub delete_sequence_fn: Box<Fn(u32) -> Result<SequenceDeletion, Error>>,
 }
 
 
@@ -29,8 +32,11 @@ impl SequenceDaoTesting {
 			get_last_sequence_fn: Box::new(|_| -> Result<Sequence, Error>  { Err(Error::TodoErr) }),
 			new_sequence_fn: Box::new(|_| -> Result<Sequence, Error> { Err(Error::TodoErr) }),
 			set_layout_fn: Box::new(|_, _| -> Result<(), Error> { Err(Error::TodoErr) }),
+			set_frames_fn: Box::new(|_, _, _| -> Result<(), Error> { Err(Error::TodoErr) }),
 			sequence_exists_fn: Box::new(|_| -> Result<bool, Error> { Err(Error::TodoErr) }),
-			get_channel_ids_fn: Box::new(|_| -> Result<Vec<u32>, Error> { Err(Error::TodoErr) })
+			get_channel_ids_fn: Box::new(|_| -> Result<Vec<u32>, Error> { Err(Error::TodoErr) }),
+			list_sequences_fn: Box::new(|_| -> Result<Vec<Sequence>, Error> { Err(Error::TodoErr) }),
+			delete_sequence_fn: Box::new(|_| -> Result<SequenceDeletion, Error> { Err(Error::TodoErr) })
 		}
 	}
 }
@@ -53,6 +59,10 @@ impl SequenceDao for SequenceDaoTesting {
     	(self.set_layout_fn)(seqid, layout_id)
     }
 
+    fn set_frames(&self, seqid: u32, frame_duration_ms: u32, num_frames: u32) -> Result<(), Error> {
+    	(self.set_frames_fn)(seqid, frame_duration_ms, num_frames)
+    }
+
     fn sequence_exists(&self, seqid: u32) -> Result<bool, Error> {
     	(self.sequence_exists_fn)(seqid)
     }
@@ -61,4 +71,11 @@ impl SequenceDao for SequenceDaoTesting {
     	(self.get_channel_ids_fn)(seqid)
     }
 
+    fn list_sequences(&self, name_filter: Option<&str>) -> Result<Vec<Sequence>, Error> {
+    	(self.list_sequences_fn)(name_filter.map(|filter| filter.to_owned()))
+    }
+
+    fn delete_sequence(&self, seqid: u32) -> Result<SequenceDeletion, Error> {
+    	(self.delete_sequence_fn)(seqid)
+    }
 }
@@ -11,10 +11,21 @@ use proton_cli::project_types::User;
 /// &str references are converted to Strings so we don't have to deal with lifetime headaches (bookdude13 tried on 12/25/16)
 #[allow(dead_code)]
 pub struct UserDaoTesting {
-	pub add_initial_user_fn: Box<Fn(String, String, String) -> Result<u32, Error>>,
-	pub add_user_fn: Box<Fn(String, String, String) -> Result<u32, Error>>,
+	pub add_initial_user_fn: Box<Fn(String, String) -> Result<u32, Error>>,
+	pub add_user_fn: Box<Fn(String, String) -> Result<u32, Error>>,
 	pub get_user_fn: Box<Fn(u32) -> Result<User, Error>>,
 	pub get_user_id_fn: Box<Fn(String) -> Result<u32, Error>>,
+	pub list_users_fn: Box<Fn(Option<String>) -> Result<Vec<User>, Error>>,
+	pub get_users_by_name_fn: Box<Fn(String) -> Result<Vec<User>, Error>>,
+	pub delete_user_fn: Box<Fn(u32) -> Result<(), Error>>,
+	pub set_public_key_fn: Box<Fn(u32, String) -> Result<(), Error>>,
+	pub revoke_key_fn: Box<Fn(u32, String) -> Result<(), Error>>,
+	pub is_key_revoked_fn: Box<Fn(String) -> Result<bool, Error>>,
+	pub is_new_admin_open_fn: Box<Fn() -> Result<bool, Error>>,
+	pub set_new_admin_open_fn: Box<Fn(bool) -> Result<(), Error>>,
+	pub add_challenge_fn: Box<Fn(Vec<u8>, u64) -> Result<(), Error>>,
+	pub take_challenge_fn: Box<Fn(Vec<u8>) -> Result<Option<u64>, Error>>,
+	pub delete_challenges_before_fn: Box<Fn(u64) -> Result<(), Error>>,
 }
 
 
@@ -23,22 +34,33 @@ impl UserDaoTesting {
 	#[allow(dead_code)]
 	pub fn new() -> UserDaoTesting {
 		UserDaoTesting {
-			add_initial_user_fn: Box::new(|_, _, _| -> Result<u32, Error> { Err(Error::TodoErr) }),
-			add_user_fn: Box::new(|_, _, _| -> Result<u32, Error>  { Err(Error::TodoErr) }),
+			add_initial_user_fn: Box::new(|_, _| -> Result<u32, Error> { Err(Error::TodoErr) }),
+			add_user_fn: Box::new(|_, _| -> Result<u32, Error>  { Err(Error::TodoErr) }),
 			get_user_fn: Box::new(|_| -> Result<User, Error> { Err(Error::TodoErr) }),
 			get_user_id_fn: Box::new(|_| -> Result<u32, Error> { Err(Error::TodoErr) }),
+			list_users_fn: Box::new(|_| -> Result<Vec<User>, Error> { Err(Error::TodoErr) }),
+			get_users_by_name_fn: Box::new(|_| -> Result<Vec<User>, Error> { Err(Error::TodoErr) }),
+			delete_user_fn: Box::new(|_| -> Result<(), Error> { Err(Error::TodoErr) }),
+			set_public_key_fn: Box::new(|_, _| -> Result<(), Error> { Err(Error::TodoErr) }),
+			revoke_key_fn: Box::new(|_, _| -> Result<(), Error> { Err(Error::TodoErr) }),
+			is_key_revoked_fn: Box::new(|_| -> Result<bool, Error> { Err(Error::TodoErr) }),
+			is_new_admin_open_fn: Box::new(|| -> Result<bool, Error> { Err(Error::TodoErr) }),
+			set_new_admin_open_fn: Box::new(|_| -> Result<(), Error> { Err(Error::TodoErr) }),
+			add_challenge_fn: Box::new(|_, _| -> Result<(), Error> { Err(Error::TodoErr) }),
+			take_challenge_fn: Box::new(|_| -> Result<Option<u64>, Error> { Err(Error::TodoErr) }),
+			delete_challenges_before_fn: Box::new(|_| -> Result<(), Error> { Err(Error::TodoErr) })
 		}
 	}
 }
 
 /// The Dao implementation simply calls the corresponding stored function
 impl UserDao for UserDaoTesting {
-	fn add_initial_user(&self, proj_name: &str, private_key: &str, public_key: &str) -> Result<u32, Error> {
-		(self.add_initial_user_fn)(proj_name.to_owned(), private_key.to_owned(), public_key.to_owned())
+	fn add_initial_user(&self, proj_name: &str, public_key: &str) -> Result<u32, Error> {
+		(self.add_initial_user_fn)(proj_name.to_owned(), public_key.to_owned())
 	}
 
-    fn add_user(&self, name: &str, private_key: &str, public_key: &str) -> Result<u32, Error> {
-    	(self.add_user_fn)(name.to_owned(), private_key.to_owned(), public_key.to_owned())
+    fn add_user(&self, name: &str, public_key: &str) -> Result<u32, Error> {
+    	(self.add_user_fn)(name.to_owned(), public_key.to_owned())
     }
 
     fn get_user(&self, uid: u32) -> Result<User, Error> {
@@ -49,4 +71,47 @@ impl UserDao for UserDaoTesting {
     	(self.get_user_id_fn)(public_key.to_owned())
     }
 
+    fn list_users(&self, name_filter: Option<&str>) -> Result<Vec<User>, Error> {
+    	(self.list_users_fn)(name_filter.map(|filter| filter.to_owned()))
+    }
+
+    fn get_users_by_name(&self, name: &str) -> Result<Vec<User>, Error> {
+    	(self.get_users_by_name_fn)(name.to_owned())
+    }
+
+    fn delete_user(&self, uid: u32) -> Result<(), Error> {
+    	(self.delete_user_fn)(uid)
+    }
+
+    fn set_public_key(&self, uid: u32, public_key: &str) -> Result<(), Error> {
+    	(self.set_public_key_fn)(uid, public_key.to_owned())
+    }
+
+    fn revoke_key(&self, uid: u32, public_key: &str) -> Result<(), Error> {
+    	(self.revoke_key_fn)(uid, public_key.to_owned())
+    }
+
+    fn is_key_revoked(&self, public_key: &str) -> Result<bool, Error> {
+    	(self.is_key_revoked_fn)(public_key.to_owned())
+    }
+
+    fn is_new_admin_open(&self) -> Result<bool, Error> {
+    	(self.is_new_admin_open_fn)()
+    }
+
+    fn set_new_admin_open(&self, open: bool) -> Result<(), Error> {
+    	(self.set_new_admin_open_fn)(open)
+    }
+
+    fn add_challenge(&self, challenge: &[u8], issued_at: u64) -> Result<(), Error> {
+    	(self.add_challenge_fn)(challenge.to_vec(), issued_at)
+    }
+
+    fn take_challenge(&self, challenge: &[u8]) -> Result<Option<u64>, Error> {
+    	(self.take_challenge_fn)(challenge.to_vec())
+    }
+
+    fn delete_challenges_before(&self, issued_before: u64) -> Result<(), Error> {
+    	(self.delete_challenges_before_fn)(issued_before)
+    }
 }
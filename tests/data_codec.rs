@@ -0,0 +1,81 @@
+extern crate proton_cli;
+
+use proton_cli::dao::{encode_frames, decode_frames, MAX_FRAMES};
+use proton_cli::error::Error;
+
+
+#[test]
+fn round_trips_frames() {
+    let frames = vec![0, 0, 0, 255, 255, 10, 20, 30, 40, 65535, 0, 1, 3, 65535, 65534];
+    let encoded = encode_frames(&frames);
+    let decoded = decode_frames(&encoded).expect("Error decoding frames");
+    assert_eq!(decoded, frames);
+}
+
+#[test]
+fn round_trips_empty_data() {
+    let encoded = encode_frames(&[]);
+    let decoded = decode_frames(&encoded).expect("Error decoding frames");
+    assert!(decoded.is_empty());
+}
+
+#[test]
+fn holds_and_fades_compress() {
+    let mut frames = vec![0u16; 4000];
+    frames.extend((0..255).map(|val| val as u16));
+    frames.extend(vec![255u16; 4000]);
+    let encoded = encode_frames(&frames);
+    // Three runs: silence, a fade up, and a hold
+    assert!(encoded.len() < 20, "Encoded to {} bytes", encoded.len());
+    assert_eq!(decode_frames(&encoded).expect("Error decoding frames"), frames);
+}
+
+#[test]
+fn rejects_corrupt_data() {
+    let encoded = encode_frames(&[1, 2, 3, 100, 50]);
+
+    match decode_frames(&[]) {
+        Err(Error::CorruptChannelData(_)) => (),
+        _ => panic!("Decoded empty input"),
+    }
+    match decode_frames(&encoded[..encoded.len() - 1]) {
+        Err(Error::CorruptChannelData(_)) => (),
+        _ => panic!("Decoded truncated input"),
+    }
+    let mut wrong_version = encoded.clone();
+    wrong_version[0] = 99;
+    match decode_frames(&wrong_version) {
+        Err(Error::CorruptChannelData(_)) => (),
+        _ => panic!("Decoded unknown format version"),
+    }
+    // A single run taking the value below zero
+    match decode_frames(&[1, 1, 1, 1]) {
+        Err(Error::CorruptChannelData(_)) => (),
+        _ => panic!("Decoded out of range frame"),
+    }
+}
+
+#[test]
+fn rejects_impossible_frame_counts() {
+    // Declares u64::MAX frames in one run
+    let huge = [1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0];
+    match decode_frames(&huge) {
+        Err(Error::CorruptChannelData(_)) => (),
+        _ => panic!("Decoded u64::MAX frames"),
+    }
+    // One frame more than any sequence can have, in a single run
+    let mut too_long = vec![1];
+    for _ in 0..2 {
+        let mut n = MAX_FRAMES as u64 + 1;
+        while n >= 0x80 {
+            too_long.push((n as u8 & 0x7f) | 0x80);
+            n >>= 7;
+        }
+        too_long.push(n as u8);
+    }
+    too_long.push(0);
+    match decode_frames(&too_long) {
+        Err(Error::CorruptChannelData(_)) => (),
+        _ => panic!("Decoded more than MAX_FRAMES frames"),
+    }
+}
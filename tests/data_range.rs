@@ -0,0 +1,146 @@
+extern crate proton_cli;
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{ChannelDao, DaoMemory, DataDao, PermissionDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{DataCell, LengthPolicy, PermissionEnum};
+use proton_cli::utils;
+
+
+/// Creates "Test Show" playing a one second sequence of the small layout (20 frames
+/// of 10, 20 and 30). Returns the sequence id and the channel ids by DMX channel.
+fn create_sequence(dao: &DaoMemory) -> (u32, Vec<u32>) {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_id = proton_cli::new_layout(dao, &admin(), common::get_test_file_path(TestFile::SmallLayout))
+        .expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &common::new_public_key())
+        .expect("Error creating project");
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        common::get_test_file_path(TestFile::TestMusic),
+        1000,
+        50,
+        common::get_test_file_path(TestFile::SmallVixenData),
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+    proton_cli::insert_sequence(dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+    let chan_ids = dao.list_channels(layout_id).expect("Error listing channels").iter()
+        .map(|channel| channel.chanid)
+        .collect::<Vec<u32>>();
+    (seqid, chan_ids)
+}
+
+#[test]
+fn get_data_range_reads_frames_starting_in_range() {
+    let dao = DaoMemory::new();
+    let (seqid, chan_ids) = create_sequence(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+
+    // Frames 2 to 6 start from 100 ms up to 325 ms
+    let range_data = proton_cli::get_data_range(&dao, &admin(), seqid, &[chan_ids[0], chan_ids[2]], 100, 325)
+        .expect("Error getting data range");
+    assert_eq!(2, range_data.len());
+    assert_eq!(vec![10; 5], range_data[&chan_ids[0]]);
+    assert_eq!(vec![30; 5], range_data[&chan_ids[2]]);
+
+    let empty = proton_cli::get_data_range(&dao, &admin(), seqid, &[chan_ids[1]], 500, 500)
+        .expect("Error getting empty data range");
+    assert!(empty[&chan_ids[1]].is_empty());
+}
+
+#[test]
+fn set_data_range_changes_only_given_frames() {
+    let dao = DaoMemory::new();
+    let (seqid, chan_ids) = create_sequence(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+
+    let mut chan_data = BTreeMap::new();
+    chan_data.insert(chan_ids[0], vec![1, 2, 3]);
+    chan_data.insert(chan_ids[1], vec![20, 99]);
+    let changed = proton_cli::set_data_range(&dao, &admin(), seqid, 100, &chan_data).expect("Error setting data range");
+    assert_eq!(4, changed);
+
+    let mut red = vec![10; 20];
+    red[2] = 1;
+    red[3] = 2;
+    red[4] = 3;
+    let mut green = vec![20; 20];
+    green[3] = 99;
+    assert_eq!(red, dao.get_data(seqid, chan_ids[0]).expect("Error getting data"));
+    assert_eq!(green, dao.get_data(seqid, chan_ids[1]).expect("Error getting data"));
+    assert_eq!(vec![30; 20], dao.get_data(seqid, chan_ids[2]).expect("Error getting data"));
+
+    // Writing back what was read changes nothing
+    let range_data = proton_cli::get_data_range(&dao, &admin(), seqid, &chan_ids, 75, 400)
+        .expect("Error getting data range");
+    assert_eq!(0, proton_cli::set_data_range(&dao, &admin(), seqid, 75, &range_data).expect("Error setting data range"));
+}
+
+#[test]
+fn ranges_must_be_within_data() {
+    let dao = DaoMemory::new();
+    let (seqid, chan_ids) = create_sequence(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+
+    match proton_cli::get_data_range(&dao, &admin(), seqid, &chan_ids, 900, 1050) {
+        Err(Error::InvalidFrameRange(_)) => {},
+        _ => panic!("Expected InvalidFrameRange for get_data_range"),
+    }
+    let mut chan_data = BTreeMap::new();
+    chan_data.insert(chan_ids[0], vec![0, 0, 0]);
+    match proton_cli::set_data_range(&dao, &admin(), seqid, 900, &chan_data) {
+        Err(Error::InvalidFrameRange(_)) => {},
+        _ => panic!("Expected InvalidFrameRange for set_data_range"),
+    }
+    // Ends past the largest time in ms instead of overflowing
+    match proton_cli::set_data_range(&dao, &admin(), seqid, u32::max_value() - 10, &chan_data) {
+        Err(Error::InvalidFrameRange(_)) => {},
+        _ => panic!("Expected InvalidFrameRange for a range ending past u32::MAX ms"),
+    }
+    match dao.get_data_range(seqid, &chan_ids, 15, 21) {
+        Err(Error::InvalidFrameRange(_)) => {},
+        _ => panic!("Expected InvalidFrameRange for DataDao::get_data_range"),
+    }
+
+    // A patch is written all or nothing
+    let cells = vec![
+        DataCell { chanid: chan_ids[0], frame: 0, value: 0 },
+        DataCell { chanid: chan_ids[1], frame: 20, value: 0 },
+    ];
+    match dao.patch_data(seqid, &cells) {
+        Err(Error::InvalidFrameRange(_)) => {},
+        _ => panic!("Expected InvalidFrameRange for DataDao::patch_data"),
+    }
+    assert_eq!(vec![10; 20], dao.get_data(seqid, chan_ids[0]).expect("Error getting data"));
+}
+
+#[test]
+fn viewers_can_read_but_not_write() {
+    let dao = DaoMemory::new();
+    let (seqid, chan_ids) = create_sequence(&dao);
+    let key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub)).expect("Error reading key");
+    let uid = dao.add_user("viewer", &key).expect("Error adding user");
+    let viewer = || common::sign_in(&dao, TestKey::GoodKeyPem);
+    let mut chan_data = BTreeMap::new();
+    chan_data.insert(chan_ids[0], vec![0]);
+
+    match proton_cli::get_data_range(&dao, &viewer(), seqid, &chan_ids, 0, 100) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction without a role"),
+    }
+
+    let _ = dao.add_permission(uid, Some("Test Show"), &PermissionEnum::Viewer).expect("Error adding permission");
+    let _ = proton_cli::get_data_range(&dao, &viewer(), seqid, &chan_ids, 0, 100).expect("Error getting data range");
+    match proton_cli::set_data_range(&dao, &viewer(), seqid, 0, &chan_data) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for set_data_range"),
+    }
+}
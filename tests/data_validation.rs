@@ -0,0 +1,138 @@
+extern crate proton_cli;
+extern crate tempdir;
+
+mod common;
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use common::{TestFile, TestKey};
+use tempdir::TempDir;
+
+use proton_cli::dao::{ChannelDao, DaoMemory, DataDao, SequenceDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{DataCell, LengthPolicy};
+
+
+/// Creates the small layout with the admin key. Returns the layout's id.
+fn create_layout(dao: &DaoMemory) -> u32 {
+    common::new_admin(dao);
+    proton_cli::new_layout(dao, &common::sign_in(dao, TestKey::AdminPem), common::get_test_file_path(TestFile::SmallLayout))
+        .expect("Error creating layout")
+}
+
+/// Imports a one second sequence (20 frames) of the small layout from the Vixen data
+fn import_vixen(
+    dao: &DaoMemory,
+    layout_id: u32,
+    data_path: PathBuf,
+    length_policy: LengthPolicy
+) -> Result<u32, Error> {
+    proton_cli::new_vixen_sequence(
+        dao,
+        &common::sign_in(dao, TestKey::AdminPem),
+        "Test Sequence",
+        common::get_test_file_path(TestFile::TestMusic),
+        1000,
+        50,
+        data_path,
+        layout_id,
+        length_policy)
+}
+
+/// Writes Vixen data with three channels of num_frames frames of the given value
+fn write_vixen_data(root: &TempDir, num_frames: usize, value: u16) -> PathBuf {
+    let data_path = root.path().join(format!("data_{}_{}.json", num_frames, value));
+    let channel = format!("[{}]", vec![value.to_string(); num_frames].join(", "));
+    let data = format!("[{}, {}, {}]", channel, channel, channel);
+    let _ = File::create(&data_path)
+        .and_then(|mut file| file.write_all(data.as_bytes()))
+        .expect("Error writing Vixen data");
+    data_path
+}
+
+#[test]
+fn writes_need_one_value_per_frame() {
+    let dao = DaoMemory::new();
+    let layout_id = create_layout(&dao);
+    let seqid = import_vixen(&dao, layout_id, common::get_test_file_path(TestFile::SmallVixenData), LengthPolicy::Exact)
+        .expect("Error creating vixen sequence");
+    let chanid = dao.list_channels(layout_id).expect("Error listing channels")[0].chanid;
+
+    match dao.update_data(seqid, chanid, &vec![0; 19]) {
+        Err(Error::InvalidFrameCount(_, id, found, expected)) => assert_eq!((chanid, 19, 20), (id, found, expected)),
+        _ => panic!("Expected InvalidFrameCount for update_data"),
+    }
+    match dao.new_data_default(seqid, vec![chanid], vec![0; 21]) {
+        Err(Error::InvalidFrameCount(_, _, found, _)) => assert_eq!(21, found),
+        _ => panic!("Expected InvalidFrameCount for new_data_default"),
+    }
+    assert_eq!(vec![10; 20], dao.get_data(seqid, chanid).expect("Error getting data"));
+}
+
+#[test]
+fn writes_need_values_within_channel_resolution() {
+    let dao = DaoMemory::new();
+    let layout_id = create_layout(&dao);
+    let seqid = import_vixen(&dao, layout_id, common::get_test_file_path(TestFile::SmallVixenData), LengthPolicy::Exact)
+        .expect("Error creating vixen sequence");
+    let chanid = dao.list_channels(layout_id).expect("Error listing channels")[0].chanid;
+
+    let mut data = vec![255; 20];
+    dao.update_data(seqid, chanid, &data).expect("Error updating data to the maximum");
+    data[7] = 256;
+    match dao.update_data(seqid, chanid, &data) {
+        Err(Error::InvalidFrameValue(id, frame, value, max)) => assert_eq!((chanid, 7, 256, 255), (id, frame, value, max)),
+        _ => panic!("Expected InvalidFrameValue for update_data"),
+    }
+    match dao.patch_data(seqid, &[DataCell { chanid: chanid, frame: 3, value: 1000 }]) {
+        Err(Error::InvalidFrameValue(_, frame, _, _)) => assert_eq!(3, frame),
+        _ => panic!("Expected InvalidFrameValue for patch_data"),
+    }
+    assert_eq!(vec![255; 20], dao.get_data(seqid, chanid).expect("Error getting data"));
+}
+
+#[test]
+fn vixen_import_checks_data() {
+    let dao = DaoMemory::new();
+    let layout_id = create_layout(&dao);
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+
+    let invalid = vec![
+        (write_vixen_data(&root, 15, 10), LengthPolicy::Exact),
+        (write_vixen_data(&root, 15, 10), LengthPolicy::Truncate),
+        (write_vixen_data(&root, 25, 10), LengthPolicy::Exact),
+        (write_vixen_data(&root, 25, 10), LengthPolicy::Pad),
+    ];
+    for (data_path, length_policy) in invalid {
+        match import_vixen(&dao, layout_id, data_path, length_policy) {
+            Err(Error::InvalidVixenData(_)) => {},
+            _ => panic!("Expected InvalidVixenData for {:?}", length_policy),
+        }
+    }
+    match import_vixen(&dao, layout_id, write_vixen_data(&root, 20, 300), LengthPolicy::Exact) {
+        Err(Error::InvalidFrameValue(_, _, value, _)) => assert_eq!(300, value),
+        _ => panic!("Expected InvalidFrameValue"),
+    }
+    assert!(dao.get_last_sequence("Test Sequence").is_err());
+}
+
+#[test]
+fn vixen_import_pads_and_truncates() {
+    let dao = DaoMemory::new();
+    let layout_id = create_layout(&dao);
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let chanid = dao.list_channels(layout_id).expect("Error listing channels")[0].chanid;
+
+    let padded = import_vixen(&dao, layout_id, write_vixen_data(&root, 15, 10), LengthPolicy::Pad)
+        .expect("Error importing short data");
+    let mut expected = vec![10; 15];
+    expected.extend_from_slice(&[0; 5]);
+    assert_eq!(expected, dao.get_data(padded, chanid).expect("Error getting data"));
+
+    let truncated = import_vixen(&dao, layout_id, write_vixen_data(&root, 25, 10), LengthPolicy::PadOrTruncate)
+        .expect("Error importing long data");
+    assert_eq!(vec![10; 20], dao.get_data(truncated, chanid).expect("Error getting data"));
+    assert_eq!(20, dao.get_sequence(truncated).expect("Error getting sequence").num_frames);
+}
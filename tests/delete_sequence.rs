@@ -0,0 +1,121 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{DaoMemory, DataDao, PermissionDao, ProjectDao, SequenceDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{LengthPolicy, PermissionEnum};
+use proton_cli::utils;
+
+
+/// Creates "Test Show" playing one vixen sequence, edited by the user with the
+/// good test key. Returns the sequence id and the editor's uid.
+fn create_show(dao: &DaoMemory) -> (u32, u32) {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let music_path = common::get_test_file_path(TestFile::TestMusic);
+    let data_path = common::get_test_file_path(TestFile::SmallVixenData);
+
+    let layout_id = proton_cli::new_layout(dao, &admin(), &layout_path).expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &common::new_public_key()).expect("Error creating project");
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        music_path,
+        1000,
+        50,
+        data_path,
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+    proton_cli::insert_sequence(dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+
+    let editor_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub))
+        .expect("Error reading key");
+    let editor_uid = dao.add_user("editor", &editor_key).expect("Error adding user");
+    let _ = dao.add_permission(editor_uid, None, &PermissionEnum::EditSequence(seqid))
+        .expect("Error adding permission");
+    (seqid, editor_uid)
+}
+
+#[test]
+fn dry_run_deletes_nothing() {
+    let dao = DaoMemory::new();
+    let (seqid, editor_uid) = create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    let deletion = proton_cli::delete_sequence(&dao, &credentials, seqid, false, true)
+        .expect("Error in dry run");
+    assert_eq!(seqid, deletion.sequence.seqid);
+    assert_eq!(vec!["Test Show".to_string()], deletion.projects);
+    assert_eq!(3, deletion.channel_data);
+    assert_eq!(1, deletion.permissions);
+
+    assert!(dao.sequence_exists(seqid).expect("Error checking sequence"));
+    assert_eq!(vec![seqid], dao.get_project("Test Show").expect("Error getting project").playlist);
+    assert_eq!(1, dao.get_all_permissions(editor_uid).expect("Error getting permissions").len());
+}
+
+#[test]
+fn refuses_sequence_in_playlist_without_force() {
+    let dao = DaoMemory::new();
+    let (seqid, _) = create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    match proton_cli::delete_sequence(&dao, &credentials, seqid, false, false) {
+        Err(Error::SequenceInPlaylist(id, projects)) => {
+            assert_eq!(seqid, id);
+            assert_eq!(vec!["Test Show".to_string()], projects);
+        },
+        Err(e) => panic!("Wrong error: {}", e),
+        Ok(_) => panic!("Deleted a sequence still in a playlist"),
+    }
+    assert!(dao.sequence_exists(seqid).expect("Error checking sequence"));
+}
+
+#[test]
+fn force_deletes_everything() {
+    let dao = DaoMemory::new();
+    let (seqid, editor_uid) = create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    let chan_ids = dao.get_channel_ids(seqid).expect("Error getting channel ids");
+
+    let _ = proton_cli::delete_sequence(&dao, &credentials, seqid, true, false)
+        .expect("Error deleting sequence");
+
+    assert!(!dao.sequence_exists(seqid).expect("Error checking sequence"));
+    assert!(dao.get_project("Test Show").expect("Error getting project").playlist.is_empty());
+    assert!(dao.get_all_permissions(editor_uid).expect("Error getting permissions").is_empty());
+    match dao.get_data(seqid, chan_ids[0]) {
+        Err(Error::ChannelDataNotFound(_, _)) => {},
+        _ => panic!("Channel data was not deleted"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "UnauthorizedAction")]
+fn fails_without_permission_for_sequence() {
+    let dao = DaoMemory::new();
+    let (seqid, editor_uid) = create_show(&dao);
+    let admin = common::sign_in(&dao, TestKey::AdminPem);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    let other_seqid = proton_cli::new_vixen_sequence(
+        &dao,
+        &admin,
+        "Other Sequence",
+        common::get_test_file_path(TestFile::TestMusic),
+        1000,
+        50,
+        common::get_test_file_path(TestFile::SmallVixenData),
+        dao.get_sequence(seqid).expect("Error getting sequence").layout_id,
+        LengthPolicy::Exact)
+        .expect("Error creating vixen sequence");
+    assert!(other_seqid != seqid);
+    assert_eq!(1, dao.get_all_permissions(editor_uid).expect("Error getting permissions").len());
+
+    let _ = proton_cli::delete_sequence(&dao, &credentials, other_seqid, true, false)
+        .expect("Error deleting sequence");
+}
@@ -0,0 +1,72 @@
+extern crate proton_cli;
+extern crate tempdir;
+
+mod common;
+
+use common::{TestFile, TestKey};
+use tempdir::TempDir;
+
+use proton_cli::dao::{DaoFile, DataDao, LayoutDao, ProjectDao, SequenceDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::LengthPolicy;
+
+
+#[test]
+fn state_survives_reopening() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let db_path = root.path().join("show.pdb");
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let music_path = common::get_test_file_path(TestFile::TestMusic);
+    let data_path = common::get_test_file_path(TestFile::SmallVixenData);
+
+    let (layout_id, seqid) = {
+        let dao = DaoFile::open(&db_path).expect("Error creating project database");
+        common::new_admin(&dao);
+        let admin = || common::sign_in(&dao, TestKey::AdminPem);
+        let layout_id = proton_cli::new_layout(&dao, &admin(), &layout_path).expect("Error creating layout");
+        let _ = proton_cli::new_project(&dao, &admin(), "Test Show", layout_id, &common::new_public_key()).expect("Error creating project");
+        let seqid = proton_cli::new_vixen_sequence(
+            &dao,
+            &admin(),
+            "Test Sequence",
+            music_path,
+            1000,
+            50,
+            data_path,
+            layout_id,
+            LengthPolicy::Exact).expect("Error creating vixen sequence");
+        proton_cli::insert_sequence(&dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+        (layout_id, seqid)
+    };
+
+    let dao = DaoFile::open(&db_path).expect("Error reopening project database");
+    let layout = dao.get_layout(layout_id).expect("Error getting layout");
+    assert_eq!("Small Layout", layout.name);
+    let project = dao.get_project("Test Show").expect("Error getting project");
+    assert_eq!(vec![seqid], project.playlist);
+    let chan_ids = dao.get_channel_ids(seqid).expect("Error getting channel ids");
+    assert_eq!(3, chan_ids.len());
+    let data = dao.get_data(seqid, chan_ids[0]).expect("Error getting data");
+    assert_eq!(vec![10; 20], data);
+
+    // Ids keep counting from where they left off
+    let new_layout = dao.new_layout("Back Yard", vec![]).expect("Error creating layout");
+    assert_eq!(layout_id + 1, new_layout.layout_id);
+}
+
+#[test]
+fn one_open_at_a_time() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let db_path = root.path().join("show.pdb");
+
+    {
+        let _dao = DaoFile::open(&db_path).expect("Error creating project database");
+        match DaoFile::open(&db_path) {
+            Err(Error::DatabaseLocked(_)) => {},
+            _ => panic!("Expected DatabaseLocked"),
+        }
+    }
+
+    // Closing releases the lock
+    let _ = DaoFile::open(&db_path).expect("Error reopening project database");
+}
@@ -0,0 +1,112 @@
+extern crate proton_cli;
+extern crate rustc_serialize;
+
+mod common;
+
+use common::{TestFile, TestKey};
+use rustc_serialize::json;
+
+use proton_cli::dao::{DaoMemory, DataDao, LayoutDao, SequenceDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{LengthPolicy, SequenceData};
+
+
+/// Creates the small test layout, a project using it, and a 1 second vixen
+/// sequence (50ms frames) in the project's playlist. Returns the seqid.
+fn setup_project(dao: &DaoMemory) -> u32 {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let layout_id = proton_cli::new_layout(dao, &admin(), &layout_path).expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &common::new_public_key()).expect("Error creating project");
+
+    let music_path = common::get_test_file_path(TestFile::TestMusic);
+    let data_path = common::get_test_file_path(TestFile::SmallVixenData);
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        music_path,
+        1000,
+        50,
+        data_path,
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+    proton_cli::insert_sequence(dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+
+    seqid
+}
+
+#[test]
+fn vixen_data_stored_per_channel() {
+    let dao = DaoMemory::new();
+    let seqid = setup_project(&dao);
+
+    let sequence = dao.get_sequence(seqid).expect("Error getting sequence");
+    assert_eq!(20, sequence.num_frames);
+
+    let chan_ids = dao.get_channel_ids(seqid).expect("Error getting channel ids");
+    assert_eq!(3, chan_ids.len());
+    for (i, chanid) in chan_ids.iter().enumerate() {
+        let data = dao.get_data(seqid, *chanid).expect("Error getting data");
+        assert_eq!(vec![(i as u16 + 1) * 10; 20], data);
+    }
+}
+
+#[test]
+fn playlist_data_placed_at_dmx_channels() {
+    let dao = DaoMemory::new();
+    let seqid = setup_project(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let sequence = dao.get_sequence(seqid).expect("Error getting sequence");
+    let patch_path = common::get_test_file_path(TestFile::SmallPatch);
+    proton_cli::patch_layout(&dao, &admin(), sequence.layout_id, &patch_path).expect("Error patching layout");
+
+    let data_json = proton_cli::get_playlist_data(&dao, &admin(), "Test Show").expect("Error getting playlist data");
+    let playlist_data: Vec<SequenceData> = json::decode(&data_json).expect("Error decoding playlist data");
+
+    assert_eq!(1, playlist_data.len());
+    let seq_data = &playlist_data[0];
+    assert_eq!("Test Sequence", seq_data.name);
+    assert_eq!("test_1sec.ogg", seq_data.music_file);
+    assert_eq!(50, seq_data.frame_dur_ms);
+    assert_eq!(vec![10; 20], seq_data.data[10]);
+    assert_eq!(vec![20; 20], seq_data.data[2]);
+    assert_eq!(vec![30; 20], seq_data.data[12]);
+    assert_eq!(vec![0; 20], seq_data.data[1]);
+}
+
+#[test]
+fn prefix_written_only_once_authorized() {
+    let dao = DaoMemory::new();
+    let _ = setup_project(&dao);
+
+    let mut out = Vec::new();
+    match proton_cli::write_playlist_data(&dao, &common::sign_in(&dao, TestKey::GoodKeyPem), "Test Show", b"DATA:", &mut out) {
+        Err(Error::PublicKeyNotFound(_)) => {},
+        _ => panic!("Expected PublicKeyNotFound"),
+    }
+    match proton_cli::write_playlist_data(&dao, &common::sign_in(&dao, TestKey::AdminPem), "Other Show", b"DATA:", &mut out) {
+        Err(Error::ProjectNotFound(_)) => {},
+        _ => panic!("Expected ProjectNotFound"),
+    }
+    assert!(out.is_empty());
+
+    proton_cli::write_playlist_data(&dao, &common::sign_in(&dao, TestKey::AdminPem), "Test Show", b"DATA:", &mut out)
+        .expect("Error writing playlist data");
+    assert!(out.starts_with(b"DATA:["));
+}
+
+#[test]
+fn fails_on_dmx_channel_outside_universe() {
+    let dao = DaoMemory::new();
+    let seqid = setup_project(&dao);
+    let sequence = dao.get_sequence(seqid).expect("Error getting sequence");
+    let _ = dao.patch_channel(sequence.layout_id, 3, 600).expect("Error patching channel");
+
+    let mut out = vec![];
+    match proton_cli::write_playlist_data(&dao, &common::sign_in(&dao, TestKey::AdminPem), "Test Show", b"", &mut out) {
+        Err(Error::DmxChannelOutOfRange(id, 600)) => assert_eq!(seqid, id),
+        _ => panic!("Expected DmxChannelOutOfRange"),
+    }
+}
@@ -0,0 +1,140 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::TestKey;
+
+use proton_cli::dao::{DaoMemory, LayoutDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::PermissionEnum;
+use proton_cli::utils;
+
+
+fn read_key(key: TestKey) -> String {
+    utils::file_as_string(common::get_key_file_path(key)).expect("Error reading key")
+}
+
+/// Creates "Test Show" whose root has the good test key, and an operator of it
+/// with the second test key. Returns the operator's uid.
+fn create_show(dao: &DaoMemory) -> u32 {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout = dao.new_layout("default", vec![]).expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout.layout_id, &read_key(TestKey::GoodKeyPub))
+        .expect("Error creating project");
+    let uid = proton_cli::new_user(dao, &admin(), "operator", &read_key(TestKey::GoodKey2Pub))
+        .expect("Error adding user");
+    proton_cli::set_permission(dao, &admin(), true, uid, "Operator", Some("Test Show"), None, None)
+        .expect("Error adding permission");
+    uid
+}
+
+#[test]
+fn rotated_key_keeps_uid_and_permissions() {
+    let dao = DaoMemory::new();
+    let uid = create_show(&dao);
+    let old_credentials = common::sign_in(&dao, TestKey::GoodKey2Pem);
+
+    proton_cli::rotate_key(&dao, &old_credentials, uid, &read_key(TestKey::Ed25519Pub))
+        .expect("Error rotating key");
+    assert_eq!(uid, dao.get_user_id(&read_key(TestKey::Ed25519Pub)).expect("Error getting uid"));
+
+    let credentials = common::sign_in(&dao, TestKey::Ed25519Priv);
+    let permissions = proton_cli::get_permissions(&dao, &credentials, uid).expect("Error listing permissions");
+    assert_eq!(1, permissions.len());
+    assert_eq!(PermissionEnum::Operator, permissions[0].permission);
+
+    // The old key is revoked, not just forgotten
+    match proton_cli::get_permissions(&dao, &common::sign_in(&dao, TestKey::GoodKey2Pem), uid) {
+        Err(Error::KeyRevoked(_)) => {},
+        _ => panic!("Expected KeyRevoked"),
+    }
+}
+
+#[test]
+fn revoked_key_is_rejected_until_rotated() {
+    let dao = DaoMemory::new();
+    let uid = create_show(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+
+    proton_cli::revoke_key(&dao, &admin(), uid).expect("Error revoking key");
+    assert_eq!("operator", dao.get_user(uid).expect("Error getting user").name);
+    match proton_cli::insert_sequence(&dao, &common::sign_in(&dao, TestKey::GoodKey2Pem), "Test Show", 1, None) {
+        Err(Error::KeyRevoked(_)) => {},
+        _ => panic!("Expected KeyRevoked"),
+    }
+    match proton_cli::revoke_key(&dao, &admin(), uid) {
+        Err(Error::KeyRevoked(_)) => {},
+        _ => panic!("Expected KeyRevoked when revoking twice"),
+    }
+
+    // A revoked key can't be registered again
+    match proton_cli::new_user(&dao, &admin(), "mallory", &read_key(TestKey::GoodKey2Pub)) {
+        Err(Error::KeyRevoked(_)) => {},
+        _ => panic!("Expected KeyRevoked for new_user"),
+    }
+
+    proton_cli::rotate_key(&dao, &admin(), uid, &read_key(TestKey::EcdsaPub)).expect("Error rotating key");
+    let credentials = common::sign_in(&dao, TestKey::EcdsaPriv);
+    assert_eq!(1, proton_cli::get_permissions(&dao, &credentials, uid).expect("Error listing permissions").len());
+}
+
+#[test]
+fn others_keys_need_global_admin() {
+    let dao = DaoMemory::new();
+    let uid = create_show(&dao);
+    let root = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    match proton_cli::rotate_key(&dao, &root(), uid, &read_key(TestKey::Ed25519Pub)) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for rotate_key"),
+    }
+    match proton_cli::revoke_key(&dao, &root(), uid) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for revoke_key"),
+    }
+    match proton_cli::recover_root_key(&dao, &root(), "Test Show", &read_key(TestKey::Ed25519Pub)) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for recover_root_key"),
+    }
+}
+
+#[test]
+fn last_admin_key_cannot_be_revoked() {
+    let dao = DaoMemory::new();
+    let _ = create_show(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let admin_uid = dao.get_users_by_name("admin").expect("Error getting admin")[0].uid;
+
+    match proton_cli::revoke_key(&dao, &admin(), admin_uid) {
+        Err(Error::CannotRevokeLastAdmin(_)) => {},
+        _ => panic!("Expected CannotRevokeLastAdmin"),
+    }
+
+    // Possible once another administrator can still sign in
+    let other_uid = proton_cli::new_user(&dao, &admin(), "other", &read_key(TestKey::Ed25519Pub))
+        .expect("Error adding user");
+    proton_cli::set_permission(&dao, &admin(), true, other_uid, "Administrate", None, None, None)
+        .expect("Error adding permission");
+    proton_cli::revoke_key(&dao, &admin(), admin_uid).expect("Error revoking key");
+}
+
+#[test]
+fn admin_recovers_root_key() {
+    let dao = DaoMemory::new();
+    let _ = create_show(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let root_uid = dao.get_users_by_name("root_Test Show").expect("Error getting root")[0].uid;
+
+    assert_eq!(root_uid, proton_cli::recover_root_key(&dao, &admin(), "Test Show", &read_key(TestKey::Ed25519Pub))
+        .expect("Error recovering root key"));
+
+    let root = common::sign_in(&dao, TestKey::Ed25519Priv);
+    let permissions = proton_cli::get_permissions(&dao, &root, root_uid).expect("Error listing permissions");
+    assert_eq!(PermissionEnum::Administrate, permissions[0].permission);
+    assert_eq!(Some("Test Show".to_owned()), permissions[0].project);
+    match proton_cli::get_permissions(&dao, &common::sign_in(&dao, TestKey::GoodKeyPem), root_uid) {
+        Err(Error::KeyRevoked(_)) => {},
+        _ => panic!("Expected KeyRevoked"),
+    }
+}
@@ -0,0 +1,65 @@
+extern crate proton_cli;
+extern crate tempdir;
+
+mod common;
+
+use common::TestKey;
+use tempdir::TempDir;
+
+use proton_cli::dao::{DaoMemory, UserDao};
+use proton_cli::utils;
+
+
+#[test]
+fn generated_key_signs_in_as_registered_user() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let key_path = root.path().join("alice");
+    let public_key_path = proton_cli::generate_key_pair(&key_path).expect("Error generating keys");
+    assert_eq!(root.path().join("alice.pub"), public_key_path);
+
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let public_key = utils::file_as_string(&public_key_path).expect("Error reading public key");
+    let uid = proton_cli::new_user(&dao, &common::sign_in(&dao, TestKey::AdminPem), "alice", &public_key).expect("Error adding user");
+    assert_eq!("alice", dao.get_user(uid).expect("Error getting user").name);
+
+    let challenge = proton_cli::new_challenge(&dao).expect("Error creating challenge");
+    let credentials = proton_cli::sign_challenge(&key_path, &challenge).expect("Error signing");
+    assert_eq!(uid, proton_cli::verify_credentials(&dao, &credentials).expect("Error verifying"));
+}
+
+#[test]
+#[cfg(unix)]
+fn private_key_is_owner_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let key_path = root.path().join("alice");
+    let _ = proton_cli::generate_key_pair(&key_path).expect("Error generating keys");
+    let mode = key_path.metadata().expect("Error reading metadata").permissions().mode();
+    assert_eq!(0o600, mode & 0o777);
+}
+
+#[test]
+#[should_panic(expected = "already exists")]
+fn fails_if_key_file_exists() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let key_path = root.path().join("alice");
+    let _ = proton_cli::generate_key_pair(&key_path).expect("Error generating first keys");
+    let _ = proton_cli::generate_key_pair(&key_path).expect("Error generating second keys");
+}
+
+#[test]
+#[should_panic(expected = "DuplicateUser")]
+fn new_user_fails_if_key_taken() {
+    let root = TempDir::new("proton_cli_tests").expect("Error creating temp dir");
+    let key_path = root.path().join("alice");
+    let public_key_path = proton_cli::generate_key_pair(&key_path).expect("Error generating keys");
+    let public_key = utils::file_as_string(&public_key_path).expect("Error reading public key");
+
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let _ = proton_cli::new_user(&dao, &admin(), "alice", &public_key).expect("Error adding first user");
+    let _ = proton_cli::new_user(&dao, &admin(), "bob", &public_key).expect("Error adding second user");
+}
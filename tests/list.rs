@@ -0,0 +1,80 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{DaoMemory, LayoutDao, UserDao};
+
+
+#[test]
+fn lists_projects_by_name() {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let layout = dao.new_layout("default", vec![]).expect("Error creating layout");
+    let _ = proton_cli::new_project(&dao, &admin(), "Winter Show", layout.layout_id, &common::new_public_key()).expect("Error creating project");
+    let _ = proton_cli::new_project(&dao, &admin(), "Summer Show", layout.layout_id, &common::new_public_key()).expect("Error creating project");
+    let _ = proton_cli::new_project(&dao, &admin(), "Rehearsal", layout.layout_id, &common::new_public_key()).expect("Error creating project");
+
+    let all = proton_cli::list_projects(&dao, None).expect("Error listing projects");
+    let names = all.iter().map(|project| project.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(vec!["Rehearsal", "Summer Show", "Winter Show"], names);
+
+    let shows = proton_cli::list_projects(&dao, Some("SHOW")).expect("Error listing projects");
+    let names = shows.iter().map(|project| project.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(vec!["Summer Show", "Winter Show"], names);
+
+    let none = proton_cli::list_projects(&dao, Some("spring")).expect("Error listing projects");
+    assert!(none.is_empty());
+}
+
+#[test]
+fn lists_users_and_layouts_by_id() {
+    let dao = DaoMemory::new();
+    let first = dao.new_layout("Front Yard", vec![]).expect("Error creating layout");
+    let second = dao.new_layout("Back Yard", vec![]).expect("Error creating layout");
+    let alice = dao.add_user("alice", "pub_a").expect("Error adding user");
+    let _ = dao.add_user("bob", "pub_b").expect("Error adding user");
+
+    let layouts = proton_cli::list_layouts(&dao, None).expect("Error listing layouts");
+    let ids = layouts.iter().map(|layout| layout.layout_id).collect::<Vec<u32>>();
+    assert_eq!(vec![first.layout_id, second.layout_id], ids);
+
+    let back = proton_cli::list_layouts(&dao, Some("back")).expect("Error listing layouts");
+    assert_eq!(1, back.len());
+    assert_eq!(second.layout_id, back[0].layout_id);
+
+    let users = proton_cli::list_users(&dao, Some("ALI")).expect("Error listing users");
+    assert_eq!(1, users.len());
+    assert_eq!(alice, users[0].uid);
+}
+
+#[test]
+fn shows_layout_fixtures_and_channels() {
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let patch_path = common::get_test_file_path(TestFile::SmallPatch);
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let layout_id = proton_cli::new_layout(&dao, &admin(), &layout_path).expect("Error creating layout");
+    proton_cli::patch_layout(&dao, &admin(), layout_id, &patch_path).expect("Error patching layout");
+
+    let details = proton_cli::show_layout(&dao, layout_id).expect("Error showing layout");
+    assert_eq!("Small Layout", details.layout.name);
+    let fixids = details.fixtures.iter().map(|fixture| fixture.fixid).collect::<Vec<u32>>();
+    assert_eq!(details.layout.fixtures, fixids);
+
+    // Ordered by DMX channel after patching 1 -> 10 and 3 -> 12
+    let channels = details.channels.iter()
+        .map(|channel| (channel.channel_dmx, channel.name.as_str()))
+        .collect::<Vec<(u32, &str)>>();
+    assert_eq!(vec![(2, "Megatree Green"), (10, "Megatree Red"), (12, "Arch")], channels);
+}
+
+#[test]
+#[should_panic(expected = "LayoutNotFound")]
+fn show_layout_fails_if_layout_nonexistent() {
+    let dao = DaoMemory::new();
+    let _ = proton_cli::show_layout(&dao, 1).expect("Error showing layout");
+}
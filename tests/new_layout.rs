@@ -0,0 +1,84 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{ChannelDao, DaoMemory, FixtureDao, LayoutDao};
+use proton_cli::error::Error;
+
+
+#[test]
+fn creates_channels_and_fixtures() {
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+
+    let layout_id = proton_cli::new_layout(&dao, &common::sign_in(&dao, TestKey::AdminPem), &layout_path).expect("Error creating layout");
+
+    let layout = dao.get_layout(layout_id).expect("Error getting layout");
+    assert_eq!("Small Layout", layout.name);
+    assert_eq!(3, layout.get_num_channels(&dao).expect("Error counting channels"));
+    for fixid in &layout.fixtures {
+        assert!(dao.fixture_exists(*fixid).expect("Error checking fixture"));
+    }
+
+    let arch = dao.get_last_channel("Arch").expect("Error getting channel");
+    assert_eq!(3, arch.channel_internal);
+    assert_eq!(3, arch.channel_dmx);
+    assert_eq!((Some(10), Some(0), Some(-5)), arch.location);
+}
+
+#[test]
+fn patch_changes_dmx_channels() {
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let patch_path = common::get_test_file_path(TestFile::SmallPatch);
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let layout_id = proton_cli::new_layout(&dao, &admin(), &layout_path).expect("Error creating layout");
+
+    proton_cli::patch_layout(&dao, &admin(), layout_id, &patch_path).expect("Error patching layout");
+
+    let red = dao.get_last_channel("Megatree Red").expect("Error getting channel");
+    let green = dao.get_last_channel("Megatree Green").expect("Error getting channel");
+    let arch = dao.get_last_channel("Arch").expect("Error getting channel");
+    assert_eq!(10, red.channel_dmx);
+    assert_eq!(2, green.channel_dmx);
+    assert_eq!(12, arch.channel_dmx);
+}
+
+#[test]
+fn layouts_get_their_own_ids() {
+    let dao = DaoMemory::new();
+
+    let first = dao.new_layout("Front Yard", vec![]).expect("Error creating first layout");
+    let second = dao.new_layout("Back Yard", vec![]).expect("Error creating second layout");
+
+    assert!(first.layout_id != second.layout_id);
+    assert_eq!("Back Yard", dao.get_layout(second.layout_id).expect("Error getting layout").name);
+}
+
+#[test]
+fn fails_if_layout_exists() {
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let layout_id = proton_cli::new_layout(&dao, &admin(), &layout_path).expect("Error creating layout");
+    let arch = dao.get_last_channel("Arch").expect("Error getting channel");
+
+    // The layout's fixtures already exist too, and nothing is left behind
+    match proton_cli::new_layout(&dao, &admin(), &layout_path) {
+        Err(Error::DuplicateFixture(_)) => {},
+        _ => panic!("Expected DuplicateFixture"),
+    }
+    match dao.new_layout("Small Layout", vec![]) {
+        Err(Error::DuplicateLayout(name)) => assert_eq!("Small Layout", name),
+        _ => panic!("Expected DuplicateLayout"),
+    }
+    assert_eq!(arch.chanid, dao.get_last_channel("Arch").expect("Error getting channel").chanid);
+    assert_eq!(vec![layout_id], dao.list_layouts(None).expect("Error listing layouts").iter()
+        .map(|layout| layout.layout_id)
+        .collect::<Vec<u32>>());
+}
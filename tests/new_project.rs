@@ -0,0 +1,54 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::TestKey;
+
+use proton_cli::dao::{DaoMemory, LayoutDao, PermissionDao, ProjectDao, UserDao};
+use proton_cli::project_types::PermissionEnum;
+
+
+#[test]
+fn creates_project_and_root_admin() {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let layout = dao.new_layout("default", vec![]).expect("Error creating layout");
+
+    let root_pub_key = common::new_public_key();
+    let root_uid = proton_cli::new_project(&dao, &common::sign_in(&dao, TestKey::AdminPem), "Test Show", layout.layout_id, &root_pub_key)
+        .expect("Error creating project");
+
+    let project = dao.get_project("Test Show").expect("Error getting project");
+    assert_eq!(layout.layout_id, project.layout_id);
+    assert!(project.playlist.is_empty());
+
+    assert_eq!(root_uid, dao.get_user_id(&root_pub_key).expect("Error getting root uid"));
+    let root_user = dao.get_user(root_uid).expect("Error getting root user");
+    assert_eq!("root_Test Show", root_user.name);
+
+    let permissions = dao.get_all_permissions(root_uid).expect("Error getting permissions");
+    assert_eq!(1, permissions.len());
+    assert_eq!(PermissionEnum::Administrate, permissions[0].permission);
+    assert_eq!(Some("Test Show".to_owned()), permissions[0].project);
+}
+
+#[test]
+#[should_panic(expected = "LayoutNotFound")]
+fn fails_if_layout_nonexistent() {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let _ = proton_cli::new_project(&dao, &common::sign_in(&dao, TestKey::AdminPem), "Test Show", 1, &common::new_public_key()).expect("Error creating project");
+}
+
+#[test]
+#[should_panic(expected = "DuplicateProject")]
+fn fails_if_project_exists() {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let layout = dao.new_layout("default", vec![]).expect("Error creating layout");
+    let _ = proton_cli::new_project(&dao, &admin(), "Test Show", layout.layout_id, &common::new_public_key())
+        .expect("Error creating first project");
+    let _ = proton_cli::new_project(&dao, &admin(), "Test Show", layout.layout_id, &common::new_public_key())
+        .expect("Error creating second project");
+}
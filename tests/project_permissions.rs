@@ -0,0 +1,163 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{DaoMemory, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{LengthPolicy, PermissionEnum};
+use proton_cli::utils;
+
+
+/// Creates "Winter Show" (root has the good test key) with one sequence in its
+/// playlist, and "Summer Show" with another. Returns the sequence ids.
+fn create_shows(dao: &DaoMemory) -> (u32, u32) {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let layout_id = proton_cli::new_layout(dao, &admin(), &layout_path).expect("Error creating layout");
+    let root_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub))
+        .expect("Error reading key");
+    let _ = proton_cli::new_project(dao, &admin(), "Winter Show", layout_id, &root_key).expect("Error creating project");
+    let _ = proton_cli::new_project(dao, &admin(), "Summer Show", layout_id, &common::new_public_key())
+        .expect("Error creating project");
+
+    let mut seqids = vec![];
+    for proj_name in &["Winter Show", "Summer Show"] {
+        let seqid = proton_cli::new_vixen_sequence(
+            dao,
+            &admin(),
+            "Test Sequence",
+            common::get_test_file_path(TestFile::TestMusic),
+            1000,
+            50,
+            common::get_test_file_path(TestFile::SmallVixenData),
+            layout_id,
+            LengthPolicy::Exact).expect("Error creating vixen sequence");
+        proton_cli::insert_sequence(dao, &admin(), proj_name, seqid, None).expect("Error inserting sequence");
+        seqids.push(seqid);
+    }
+    (seqids[0], seqids[1])
+}
+
+/// Adds a user with the second test key
+fn add_member(dao: &DaoMemory) -> u32 {
+    let key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKey2Pub))
+        .expect("Error reading key");
+    dao.add_user("alice", &key).expect("Error adding user")
+}
+
+#[test]
+fn root_only_administers_own_project() {
+    let dao = DaoMemory::new();
+    let _ = create_shows(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+    let admin = vec![PermissionEnum::Administrate];
+
+    let _ = utils::check_valid_permission(&dao, &credentials(), Some("Winter Show"), &admin)
+        .expect("Error checking own project");
+    match utils::check_valid_permission(&dao, &credentials(), Some("Summer Show"), &admin) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction"),
+    }
+    match utils::check_valid_permission(&dao, &credentials(), None, &admin) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction"),
+    }
+}
+
+#[test]
+fn roles_expand_within_their_project() {
+    let dao = DaoMemory::new();
+    let (winter_seqid, summer_seqid) = create_shows(&dao);
+    let uid = add_member(&dao);
+    let root_credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    proton_cli::set_permission(&dao, &root_credentials, true, uid, "Sequencer", Some("Winter Show"), None, None)
+        .expect("Error granting role");
+
+    let credentials = || common::sign_in(&dao, TestKey::GoodKey2Pem);
+    let _ = utils::check_valid_permission(&dao, &credentials(), Some("Winter Show"), &vec![PermissionEnum::Viewer])
+        .expect("Error checking Viewer");
+    let _ = utils::check_sequence_permission(
+        &dao, &credentials(), winter_seqid, &vec![PermissionEnum::EditSequence(winter_seqid)])
+        .expect("Error checking EditSequence");
+    match utils::check_valid_permission(&dao, &credentials(), Some("Winter Show"), &vec![PermissionEnum::Operator]) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for Operator"),
+    }
+    match utils::check_sequence_permission(
+        &dao, &credentials(), summer_seqid, &vec![PermissionEnum::EditSequence(summer_seqid)]) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for another project's sequence"),
+    }
+}
+
+#[test]
+fn project_admin_cannot_grant_outside_project() {
+    let dao = DaoMemory::new();
+    let (_, summer_seqid) = create_shows(&dao);
+    let uid = add_member(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "Viewer", Some("Summer Show"), None, None) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for another project"),
+    }
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "Administrate", None, None, None) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for global Administrate"),
+    }
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "EditSequence", None, Some(summer_seqid), None) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for another project's sequence"),
+    }
+}
+
+#[test]
+fn playlists_only_take_reachable_sequences() {
+    let dao = DaoMemory::new();
+    let (_, summer_seqid) = create_shows(&dao);
+    let root_uid = dao.get_users_by_name("root_Winter Show").expect("Error getting root")[0].uid;
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    // Administrate in Winter Show says nothing about Summer Show's sequence
+    match proton_cli::insert_sequence(&dao, &credentials(), "Winter Show", summer_seqid, None) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for another project's sequence"),
+    }
+
+    let admin = common::sign_in(&dao, TestKey::AdminPem);
+    proton_cli::set_permission(&dao, &admin, true, root_uid, "Sequencer", Some("Summer Show"), None, None)
+        .expect("Error granting role");
+    proton_cli::insert_sequence(&dao, &credentials(), "Winter Show", summer_seqid, None)
+        .expect("Error inserting sequence");
+}
+
+#[test]
+fn roles_need_a_project() {
+    let dao = DaoMemory::new();
+    let _ = create_shows(&dao);
+    let uid = add_member(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "Operator", None, None, None) {
+        Err(Error::MissingPermissionArg) => {},
+        _ => panic!("Expected MissingPermissionArg"),
+    }
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "Operator", Some("Spring Show"), None, None) {
+        Err(Error::ProjectNotFound(_)) => {},
+        _ => panic!("Expected ProjectNotFound"),
+    }
+}
+
+#[test]
+fn role_grants() {
+    assert!(PermissionEnum::Administrate.grants(&PermissionEnum::EditSection(1, 2)));
+    assert!(PermissionEnum::Operator.grants(&PermissionEnum::Viewer));
+    assert!(!PermissionEnum::Operator.grants(&PermissionEnum::EditSequence(1)));
+    assert!(PermissionEnum::Sequencer.grants(&PermissionEnum::EditSection(1, 2)));
+    assert!(!PermissionEnum::Viewer.grants(&PermissionEnum::Operator));
+    assert!(PermissionEnum::EditSequence(1).grants(&PermissionEnum::EditSection(1, 2)));
+    assert!(!PermissionEnum::EditSequence(1).grants(&PermissionEnum::EditSection(2, 2)));
+}
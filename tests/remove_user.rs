@@ -0,0 +1,123 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::TestKey;
+
+use proton_cli::dao::{DaoMemory, LayoutDao, PermissionDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::PermissionEnum;
+use proton_cli::utils;
+
+
+/// Creates a project and an admin with the good test key. Returns the admin's uid.
+fn create_admin(dao: &DaoMemory) -> u32 {
+    let admin_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub))
+        .expect("Error reading key");
+    let admin_uid = dao.add_user("admin", &admin_key).expect("Error adding user");
+    let _ = dao.add_permission(admin_uid, None, &PermissionEnum::Administrate).expect("Error adding permission");
+    let admin = common::sign_in(dao, TestKey::GoodKeyPem);
+
+    let layout = dao.new_layout("default", vec![]).expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin, "Test Show", layout.layout_id, &common::new_public_key()).expect("Error creating project");
+    admin_uid
+}
+
+#[test]
+fn removes_user_and_permissions() {
+    let dao = DaoMemory::new();
+    let _ = create_admin(&dao);
+    let uid = dao.add_user("alice", "pub_a").expect("Error adding user");
+    let _ = dao.add_permission(uid, None, &PermissionEnum::EditSequence(1)).expect("Error adding permission");
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    let user = proton_cli::find_user(&dao, "alice").expect("Error finding user");
+    let removed = proton_cli::remove_user(&dao, &credentials, user.uid).expect("Error removing user");
+    assert_eq!(uid, removed.uid);
+    match dao.get_user(uid) {
+        Err(Error::UserNotFound) => {},
+        _ => panic!("User was not removed"),
+    }
+    assert!(dao.get_all_permissions(uid).expect("Error getting permissions").is_empty());
+}
+
+#[test]
+fn refuses_to_remove_project_root() {
+    let dao = DaoMemory::new();
+    let _ = create_admin(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    let root = proton_cli::find_user(&dao, "root_Test Show").expect("Error finding root");
+
+    match proton_cli::remove_user(&dao, &credentials, root.uid) {
+        Err(Error::CannotRemoveRoot(name)) => assert_eq!("root_Test Show", name),
+        Err(e) => panic!("Wrong error: {}", e),
+        Ok(_) => panic!("Removed a project root user"),
+    }
+    assert!(dao.get_user(root.uid).is_ok());
+}
+
+#[test]
+fn refuses_to_remove_last_admin() {
+    let dao = DaoMemory::new();
+    let admin_uid = create_admin(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    match proton_cli::remove_user(&dao, &credentials(), admin_uid) {
+        Err(Error::CannotRemoveLastAdmin(name)) => assert_eq!("admin", name),
+        Err(e) => panic!("Wrong error: {}", e),
+        Ok(_) => panic!("Removed the last administrator"),
+    }
+
+    // Possible once another administrator can still sign in
+    let other_uid = dao.add_user("other", &common::new_public_key()).expect("Error adding user");
+    let _ = dao.add_permission(other_uid, None, &PermissionEnum::Administrate).expect("Error adding permission");
+    let _ = proton_cli::remove_user(&dao, &credentials(), admin_uid).expect("Error removing user");
+}
+
+#[test]
+fn reports_ambiguous_names() {
+    let dao = DaoMemory::new();
+    let admin_uid = create_admin(&dao);
+    let bob = dao.add_user("bob", "pub_b").expect("Error adding user");
+    // A user named after another user's uid
+    let numeric = dao.add_user(&admin_uid.to_string(), "pub_n").expect("Error adding user");
+
+    match proton_cli::find_user(&dao, &admin_uid.to_string()) {
+        Err(Error::AmbiguousUser(name, uids)) => {
+            assert_eq!(admin_uid.to_string(), name);
+            assert_eq!(vec![numeric, admin_uid], uids);
+        },
+        _ => panic!("Expected AmbiguousUser"),
+    }
+    assert_eq!(bob, proton_cli::find_user(&dao, "bob").expect("Error finding user").uid);
+    assert_eq!(bob, proton_cli::find_user(&dao, &bob.to_string()).expect("Error finding user").uid);
+}
+
+#[test]
+fn user_names_are_unique() {
+    let dao = DaoMemory::new();
+    let _ = create_admin(&dao);
+    let _ = dao.add_user("bob", "pub_b1").expect("Error adding user");
+
+    match dao.add_user("bob", "pub_b2") {
+        Err(Error::DuplicateUser(key, name)) => {
+            assert_eq!("pub_b2", key);
+            assert_eq!("bob", name);
+        },
+        _ => panic!("Expected DuplicateUser"),
+    }
+}
+
+#[test]
+#[should_panic(expected = "UnauthorizedAction")]
+fn fails_without_administrate() {
+    let dao = DaoMemory::new();
+    let admin_uid = create_admin(&dao);
+    let uid = dao.add_user("alice", "pub_a").expect("Error adding user");
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    dao.delete_user(admin_uid).expect("Error deleting admin");
+    let editor_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub)).expect("Error reading key");
+    let _ = dao.add_user("editor", &editor_key).expect("Error adding user");
+
+    let _ = proton_cli::remove_user(&dao, &credentials, uid).expect("Error removing user");
+}
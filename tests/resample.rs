@@ -0,0 +1,126 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{ChannelDao, DaoMemory, DataDao, PermissionDao, SequenceDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{LengthPolicy, PermissionEnum, ResampleMode};
+use proton_cli::utils;
+
+
+/// Creates "Test Show" playing a one second sequence of the small layout (20 frames
+/// of 50 ms), with the first channel ramping up by 10 every frame from 0.
+/// Returns the sequence id and the channel ids by DMX channel.
+fn create_ramp(dao: &DaoMemory) -> (u32, Vec<u32>) {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_id = proton_cli::new_layout(dao, &admin(), common::get_test_file_path(TestFile::SmallLayout))
+        .expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &common::new_public_key())
+        .expect("Error creating project");
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        common::get_test_file_path(TestFile::TestMusic),
+        1000,
+        50,
+        common::get_test_file_path(TestFile::SmallVixenData),
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+    proton_cli::insert_sequence(dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+
+    let chan_ids = dao.list_channels(layout_id).expect("Error listing channels").iter()
+        .map(|channel| channel.chanid)
+        .collect::<Vec<u32>>();
+    let ramp = (0..20).map(|frame| frame * 10).collect::<Vec<u16>>();
+    dao.update_data(seqid, chan_ids[0], &ramp).expect("Error updating data");
+    (seqid, chan_ids)
+}
+
+#[test]
+fn linear_upsampling_interpolates_between_frames() {
+    let dao = DaoMemory::new();
+    let (seqid, chan_ids) = create_ramp(&dao);
+
+    let sequence = proton_cli::resample_sequence(&dao, &common::sign_in(&dao, TestKey::AdminPem), seqid, 25, ResampleMode::Linear)
+        .expect("Error resampling sequence");
+    assert_eq!((25, 40), (sequence.frame_duration_ms, sequence.num_frames));
+    assert_eq!(40, dao.get_sequence(seqid).expect("Error getting sequence").num_frames);
+
+    // New frames between old ones get their average; the last one holds the last old frame
+    let mut expected = (0..39).map(|frame| frame * 5).collect::<Vec<u16>>();
+    expected.push(190);
+    assert_eq!(expected, dao.get_data(seqid, chan_ids[0]).expect("Error getting data"));
+    assert_eq!(vec![20; 40], dao.get_data(seqid, chan_ids[1]).expect("Error getting data"));
+    assert_eq!(vec![30; 40], dao.get_data(seqid, chan_ids[2]).expect("Error getting data"));
+}
+
+#[test]
+fn modes_pick_values_differently() {
+    // At 75 ms, the second frame starts halfway through the old second frame
+    let expected = vec![
+        (ResampleMode::Nearest, 20),
+        (ResampleMode::Hold, 10),
+        (ResampleMode::Linear, 15),
+    ];
+    for (mode, second_frame) in expected {
+        let dao = DaoMemory::new();
+        let (seqid, chan_ids) = create_ramp(&dao);
+        let sequence = proton_cli::resample_sequence(&dao, &common::sign_in(&dao, TestKey::AdminPem), seqid, 75, mode)
+            .expect("Error resampling sequence");
+        // The sequence keeps at least its length
+        assert_eq!(14, sequence.num_frames);
+
+        let data = dao.get_data(seqid, chan_ids[0]).expect("Error getting data");
+        assert_eq!(14, data.len());
+        assert_eq!((0, second_frame), (data[0], data[1]), "{:?}", mode);
+        assert_eq!(30, data[2]);
+    }
+}
+
+#[test]
+fn downsampling_reads_data_before_shrinking() {
+    // DaoMemory refuses data stored for another frame count, like a backend
+    // bounding decoded frames by the sequence would
+    let dao = DaoMemory::new();
+    let (seqid, chan_ids) = create_ramp(&dao);
+    dao.set_frames(seqid, 100, 10).expect("Error setting frames");
+    match dao.get_data(seqid, chan_ids[0]) {
+        Err(Error::InvalidFrameCount(_, _, 20, 10)) => {},
+        _ => panic!("Expected InvalidFrameCount"),
+    }
+
+    let dao = DaoMemory::new();
+    let (seqid, chan_ids) = create_ramp(&dao);
+    let sequence = proton_cli::resample_sequence(&dao, &common::sign_in(&dao, TestKey::AdminPem), seqid, 100, ResampleMode::Hold)
+        .expect("Error resampling sequence");
+    assert_eq!(10, sequence.num_frames);
+    let expected = (0..10).map(|frame| frame * 20).collect::<Vec<u16>>();
+    assert_eq!(expected, dao.get_data(seqid, chan_ids[0]).expect("Error getting data"));
+}
+
+#[test]
+fn resampling_checks_duration_and_permission() {
+    let dao = DaoMemory::new();
+    let (seqid, chan_ids) = create_ramp(&dao);
+    match proton_cli::resample_sequence(&dao, &common::sign_in(&dao, TestKey::AdminPem), seqid, 20, ResampleMode::Hold) {
+        Err(Error::InvalidFrameDuration(20)) => {},
+        _ => panic!("Expected InvalidFrameDuration"),
+    }
+    match ResampleMode::from_name("cubic") {
+        Err(Error::InvalidResampleMode(_)) => {},
+        _ => panic!("Expected InvalidResampleMode"),
+    }
+
+    let key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub)).expect("Error reading key");
+    let uid = dao.add_user("viewer", &key).expect("Error adding user");
+    let _ = dao.add_permission(uid, Some("Test Show"), &PermissionEnum::Viewer).expect("Error adding permission");
+    match proton_cli::resample_sequence(&dao, &common::sign_in(&dao, TestKey::GoodKeyPem), seqid, 25, ResampleMode::Hold) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction"),
+    }
+    assert_eq!(20, dao.get_data(seqid, chan_ids[0]).expect("Error getting data").len());
+}
@@ -0,0 +1,154 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{DaoMemory, LayoutDao, PermissionDao, SequenceDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{LengthPolicy, PermissionEnum};
+use proton_cli::utils;
+
+
+/// Creates "Test Show" playing a one second sequence of the small layout, and a
+/// sequencer of the show with the good test key.
+/// Returns the sequence id, the layout's fixture ids and the sequencer's uid.
+fn create_show(dao: &DaoMemory) -> (u32, Vec<u32>, u32) {
+    common::new_admin(dao);
+    let admin = || common::sign_in(dao, TestKey::AdminPem);
+    let layout_id = proton_cli::new_layout(dao, &admin(), common::get_test_file_path(TestFile::SmallLayout))
+        .expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &common::new_public_key())
+        .expect("Error creating project");
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        common::get_test_file_path(TestFile::TestMusic),
+        1000,
+        50,
+        common::get_test_file_path(TestFile::SmallVixenData),
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+    proton_cli::insert_sequence(dao, &admin(), "Test Show", seqid, None).expect("Error inserting sequence");
+
+    let key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub)).expect("Error reading key");
+    let uid = dao.add_user("sequencer", &key).expect("Error adding user");
+    let _ = dao.add_permission(uid, Some("Test Show"), &PermissionEnum::Sequencer).expect("Error adding permission");
+    let fixtures = dao.get_layout(layout_id).expect("Error getting layout").fixtures;
+    (seqid, fixtures, uid)
+}
+
+#[test]
+fn creator_can_edit_new_section() {
+    let dao = DaoMemory::new();
+    let (seqid, fixtures, uid) = create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    let secid = proton_cli::new_section(&dao, &credentials, seqid, 0, 500, vec![fixtures[0]])
+        .expect("Error creating section");
+    let section = proton_cli::get_section(&dao, secid).expect("Error getting section");
+    assert_eq!(seqid, section.seqid);
+    assert_eq!((0, 500), (section.t_start, section.t_end));
+    assert_eq!(vec![fixtures[0]], section.fixtures);
+
+    let permissions = dao.get_all_permissions(uid).expect("Error getting permissions");
+    assert!(permissions.iter().any(|p| p.permission == PermissionEnum::EditSection(seqid, secid)));
+    assert_eq!(1, proton_cli::list_sections(&dao, seqid).expect("Error listing sections").len());
+}
+
+#[test]
+fn overlapping_sections_are_rejected() {
+    let dao = DaoMemory::new();
+    let (seqid, fixtures, _) = create_show(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    let first = proton_cli::new_section(&dao, &credentials(), seqid, 0, 500, vec![fixtures[0]])
+        .expect("Error creating section");
+    match proton_cli::new_section(&dao, &credentials(), seqid, 250, 750, vec![fixtures[1], fixtures[0]]) {
+        Err(Error::SectionOverlap(secid)) => assert_eq!(first, secid),
+        _ => panic!("Expected SectionOverlap"),
+    }
+
+    // Sharing only time or only fixtures is fine
+    let _ = proton_cli::new_section(&dao, &credentials(), seqid, 250, 750, vec![fixtures[1]])
+        .expect("Error creating section with other fixtures");
+    let _ = proton_cli::new_section(&dao, &credentials(), seqid, 500, 1000, vec![fixtures[0]])
+        .expect("Error creating section right after the first");
+
+    let starts = proton_cli::list_sections(&dao, seqid).expect("Error listing sections").iter()
+        .map(|section| section.t_start)
+        .collect::<Vec<u32>>();
+    assert_eq!(vec![0, 250, 500], starts);
+}
+
+#[test]
+fn sections_must_fit_sequence_and_layout() {
+    let dao = DaoMemory::new();
+    let (seqid, fixtures, _) = create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    let invalid = vec![
+        (0, 1001, vec![fixtures[0]]),
+        (500, 500, vec![fixtures[0]]),
+        (600, 500, vec![fixtures[0]]),
+        (0, 500, vec![]),
+        (0, 500, vec![999]),
+        (0, 500, vec![fixtures[0], fixtures[0]]),
+    ];
+    for (t_start, t_end, section_fixtures) in invalid {
+        match proton_cli::new_section(&dao, &credentials, seqid, t_start, t_end, section_fixtures) {
+            Err(Error::InvalidSection(_)) => {},
+            _ => panic!("Expected InvalidSection for {}-{} ms", t_start, t_end),
+        }
+    }
+    assert!(proton_cli::list_sections(&dao, seqid).expect("Error listing sections").is_empty());
+}
+
+#[test]
+fn deleting_section_needs_edit_section() {
+    let dao = DaoMemory::new();
+    let (seqid, fixtures, uid) = create_show(&dao);
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+    let secid = proton_cli::new_section(&dao, &credentials(), seqid, 0, 500, fixtures.clone())
+        .expect("Error creating section");
+
+    let key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKey2Pub)).expect("Error reading key");
+    let _ = dao.add_user("outsider", &key).expect("Error adding user");
+    let outsider = || common::sign_in(&dao, TestKey::GoodKey2Pem);
+    match proton_cli::new_section(&dao, &outsider(), seqid, 500, 1000, fixtures.clone()) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for new_section"),
+    }
+    match proton_cli::delete_section(&dao, &outsider(), secid) {
+        Err(Error::UnauthorizedAction) => {},
+        _ => panic!("Expected UnauthorizedAction for delete_section"),
+    }
+
+    // The creator keeps EditSection after losing the Sequencer role
+    dao.revoke_permission(uid, Some("Test Show"), &PermissionEnum::Sequencer).expect("Error revoking permission");
+    let _ = proton_cli::delete_section(&dao, &credentials(), secid).expect("Error deleting section");
+    match proton_cli::get_section(&dao, secid) {
+        Err(Error::SectionNotFound(_)) => {},
+        _ => panic!("Expected SectionNotFound"),
+    }
+    assert!(dao.get_all_permissions(uid).expect("Error getting permissions").is_empty());
+}
+
+#[test]
+fn deleting_sequence_deletes_its_sections() {
+    let dao = DaoMemory::new();
+    let (seqid, fixtures, _) = create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    let secid = proton_cli::new_section(&dao, &credentials, seqid, 0, 500, fixtures)
+        .expect("Error creating section");
+
+    let deletion = proton_cli::delete_sequence(&dao, &common::sign_in(&dao, TestKey::AdminPem), seqid, true, false)
+        .expect("Error deleting sequence");
+    assert_eq!(1, deletion.sections);
+    assert!(!dao.sequence_exists(seqid).expect("Error checking sequence"));
+    match proton_cli::get_section(&dao, secid) {
+        Err(Error::SectionNotFound(_)) => {},
+        _ => panic!("Expected SectionNotFound"),
+    }
+}
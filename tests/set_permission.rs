@@ -0,0 +1,119 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{DaoMemory, PermissionDao, UserDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::{LengthPolicy, PermissionEnum};
+use proton_cli::utils;
+
+
+/// Creates a project with one sequence and an admin with the good test key.
+/// Returns the sequence id.
+fn create_show(dao: &DaoMemory) -> u32 {
+    let admin_key = utils::file_as_string(common::get_key_file_path(TestKey::GoodKeyPub))
+        .expect("Error reading key");
+    let admin_uid = dao.add_user("admin", &admin_key).expect("Error adding user");
+    let _ = dao.add_permission(admin_uid, None, &PermissionEnum::Administrate).expect("Error adding permission");
+    let admin = || common::sign_in(dao, TestKey::GoodKeyPem);
+
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let layout_id = proton_cli::new_layout(dao, &admin(), &layout_path).expect("Error creating layout");
+    let _ = proton_cli::new_project(dao, &admin(), "Test Show", layout_id, &common::new_public_key()).expect("Error creating project");
+    let seqid = proton_cli::new_vixen_sequence(
+        dao,
+        &admin(),
+        "Test Sequence",
+        common::get_test_file_path(TestFile::TestMusic),
+        1000,
+        50,
+        common::get_test_file_path(TestFile::SmallVixenData),
+        layout_id,
+        LengthPolicy::Exact).expect("Error creating vixen sequence");
+    seqid
+}
+
+#[test]
+fn grants_and_revokes() {
+    let dao = DaoMemory::new();
+    let seqid = create_show(&dao);
+    let uid = dao.add_user("alice", "pub_a").expect("Error adding user");
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    proton_cli::set_permission(&dao, &credentials(), true, uid, "EditSequence", None, Some(seqid), None)
+        .expect("Error granting permission");
+    let permissions = dao.get_all_permissions(uid).expect("Error getting permissions");
+    assert_eq!(1, permissions.len());
+    assert_eq!(PermissionEnum::EditSequence(seqid), permissions[0].permission);
+    let stored = dao.get_permission(permissions[0].permid).expect("Error getting permission");
+    assert_eq!(uid, stored.uid);
+
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "EditSequence", None, Some(seqid), None) {
+        Err(Error::DuplicatePermission(_, _)) => {},
+        _ => panic!("Expected DuplicatePermission"),
+    }
+
+    proton_cli::set_permission(&dao, &credentials(), false, uid, "EditSequence", None, Some(seqid), None)
+        .expect("Error revoking permission");
+    assert!(dao.get_all_permissions(uid).expect("Error getting permissions").is_empty());
+
+    match proton_cli::set_permission(&dao, &credentials(), false, uid, "EditSequence", None, Some(seqid), None) {
+        Err(Error::PermissionNotHeld(_, _)) => {},
+        _ => panic!("Expected PermissionNotHeld"),
+    }
+}
+
+#[test]
+fn validates_targets() {
+    let dao = DaoMemory::new();
+    let seqid = create_show(&dao);
+    let uid = dao.add_user("alice", "pub_a").expect("Error adding user");
+    let credentials = || common::sign_in(&dao, TestKey::GoodKeyPem);
+
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "EditSequence", None, Some(seqid + 1), None) {
+        Err(Error::SequenceNotFound(_)) => {},
+        _ => panic!("Expected SequenceNotFound"),
+    }
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "EditSection", None, Some(seqid), Some(1)) {
+        Err(Error::SectionNotFound(1)) => {},
+        _ => panic!("Expected SectionNotFound"),
+    }
+    match proton_cli::set_permission(&dao, &credentials(), true, uid + 1, "Administrate", None, None, None) {
+        Err(Error::UserNotFound) => {},
+        _ => panic!("Expected UserNotFound"),
+    }
+    match proton_cli::set_permission(&dao, &credentials(), true, uid, "EditSequence", None, None, None) {
+        Err(Error::MissingPermissionArg) => {},
+        _ => panic!("Expected MissingPermissionArg"),
+    }
+}
+
+#[test]
+fn root_keeps_administrate() {
+    let dao = DaoMemory::new();
+    let _ = create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    let root = proton_cli::find_user(&dao, "root_Test Show").expect("Error finding root");
+
+    match proton_cli::set_permission(&dao, &credentials, false, root.uid, "Administrate", None, None, None) {
+        Err(Error::CannotRevokeRootAdmin(_)) => {},
+        _ => panic!("Expected CannotRevokeRootAdmin"),
+    }
+    assert_eq!(1, dao.get_all_permissions(root.uid).expect("Error getting permissions").len());
+}
+
+#[test]
+fn last_admin_keeps_administrate() {
+    let dao = DaoMemory::new();
+    let _ = create_show(&dao);
+    let credentials = common::sign_in(&dao, TestKey::GoodKeyPem);
+    let admin = proton_cli::find_user(&dao, "admin").expect("Error finding admin");
+
+    match proton_cli::set_permission(&dao, &credentials, false, admin.uid, "Administrate", None, None, None) {
+        Err(Error::CannotRemoveLastAdmin(_)) => {},
+        _ => panic!("Expected CannotRemoveLastAdmin"),
+    }
+    assert!(utils::is_global_admin(&dao, admin.uid).expect("Error checking admin"));
+}
@@ -0,0 +1,95 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::TestKey;
+
+use proton_cli::dao::{DaoMemory, UserDao};
+use proton_cli::utils;
+
+
+fn read_key(key: TestKey) -> String {
+    utils::file_as_string(common::get_key_file_path(key)).expect("Error reading key")
+}
+
+/// Registers a user with the given public key and signs in with the given private key
+fn sign_in_as(public_key: TestKey, private_key: TestKey) -> (u32, u32) {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let uid = proton_cli::new_user(&dao, &common::sign_in(&dao, TestKey::AdminPem), "alice", &read_key(public_key)).expect("Error adding user");
+    let credentials = common::sign_in(&dao, private_key);
+    (uid, proton_cli::verify_credentials(&dao, &credentials).expect("Error verifying"))
+}
+
+#[test]
+fn fingerprints_match_ssh_keygen() {
+    // Expected values from `ssh-keygen -l -f <key>`
+    assert_eq!(
+        "SHA256:YlPXgNpc8hueFAUad31V23h/AsayAAiMtvYMtCtnux4",
+        proton_cli::fingerprint(&read_key(TestKey::GoodKeyOpenSshPub)).expect("Error fingerprinting"));
+    assert_eq!(
+        "SHA256:Eb66hT+kwt90/ZhuZNylrFFMEiwLRwIHx6UWCDKnU9s",
+        proton_cli::fingerprint(&read_key(TestKey::Ed25519Pub)).expect("Error fingerprinting"));
+    assert_eq!(
+        "SHA256:pNZtsHHekOZtYx1/jnF8F/pqxlUkcsxWKnvWCDqe7GY",
+        proton_cli::fingerprint(&read_key(TestKey::EcdsaPub)).expect("Error fingerprinting"));
+}
+
+#[test]
+fn pem_and_openssh_forms_are_the_same_key() {
+    let dao = DaoMemory::new();
+    let uid = dao.add_user("good", &read_key(TestKey::GoodKeyPub)).expect("Error adding user");
+    let found = dao.get_user_id(&read_key(TestKey::GoodKeyOpenSshPub)).expect("Error getting user id");
+    assert_eq!(uid, found);
+}
+
+#[test]
+fn signs_in_with_ed25519_key() {
+    let (uid, signed_in) = sign_in_as(TestKey::Ed25519Pub, TestKey::Ed25519Priv);
+    assert_eq!(uid, signed_in);
+}
+
+#[test]
+fn signs_in_with_ecdsa_key() {
+    let (uid, signed_in) = sign_in_as(TestKey::EcdsaPub, TestKey::EcdsaPriv);
+    assert_eq!(uid, signed_in);
+}
+
+#[test]
+fn signs_in_with_openssh_rsa_key() {
+    let (uid, signed_in) = sign_in_as(TestKey::RsaOpenSshPub, TestKey::RsaOpenSshPriv);
+    assert_eq!(uid, signed_in);
+}
+
+#[test]
+fn pem_private_key_signs_in_as_openssh_registered_user() {
+    let (uid, signed_in) = sign_in_as(TestKey::GoodKeyOpenSshPub, TestKey::GoodKeyPem);
+    assert_eq!(uid, signed_in);
+}
+
+#[test]
+#[should_panic(expected = "InvalidSignature")]
+fn rejects_signature_from_another_ssh_key() {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let _ = proton_cli::new_user(&dao, &common::sign_in(&dao, TestKey::AdminPem), "alice", &read_key(TestKey::Ed25519Pub)).expect("Error adding user");
+    let mut credentials = common::sign_in(&dao, TestKey::EcdsaPriv);
+    credentials.public_key = read_key(TestKey::Ed25519Pub);
+    let _ = proton_cli::verify_credentials(&dao, &credentials).expect("Error verifying");
+}
+
+#[test]
+#[should_panic(expected = "DuplicateUser")]
+fn fails_if_key_registered_in_another_format() {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let _ = proton_cli::new_user(&dao, &admin(), "alice", &read_key(TestKey::GoodKeyPub)).expect("Error adding first user");
+    let _ = proton_cli::new_user(&dao, &admin(), "bob", &read_key(TestKey::GoodKeyOpenSshPub)).expect("Error adding second user");
+}
+
+#[test]
+#[should_panic(expected = "EncryptedPrivateKey")]
+fn fails_if_private_key_encrypted() {
+    let _ = common::sign_in(&DaoMemory::new(), TestKey::Ed25519EncryptedPriv);
+}
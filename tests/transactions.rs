@@ -0,0 +1,63 @@
+extern crate proton_cli;
+
+mod common;
+
+use common::{TestFile, TestKey};
+
+use proton_cli::dao::{DaoMemory, LayoutDao, ProjectDao, SequenceDao, TransactionDao};
+use proton_cli::error::Error;
+use proton_cli::project_types::LengthPolicy;
+
+
+#[test]
+fn failed_vixen_import_leaves_no_sequence() {
+    let dao = DaoMemory::new();
+    common::new_admin(&dao);
+    let admin = || common::sign_in(&dao, TestKey::AdminPem);
+    let layout_path = common::get_test_file_path(TestFile::SmallLayout);
+    let layout_id = proton_cli::new_layout(&dao, &admin(), &layout_path).expect("Error creating layout");
+
+    // Data file only has 2 of the layout's 3 channels
+    let music_path = common::get_test_file_path(TestFile::TestMusic);
+    let data_path = common::get_test_file_path(TestFile::ShortVixenData);
+    let result = proton_cli::new_vixen_sequence(
+        &dao,
+        &admin(),
+        "Test Sequence",
+        music_path,
+        1000,
+        50,
+        data_path,
+        layout_id,
+        LengthPolicy::Exact);
+
+    assert!(result.is_err());
+    assert!(dao.get_last_sequence("Test Sequence").is_err());
+}
+
+#[test]
+fn inner_rollback_keeps_outer_changes() {
+    let dao = DaoMemory::new();
+    let layout = dao.new_layout("default", vec![]).expect("Error creating layout");
+
+    let result: Result<(), Error> = dao.transaction(|| {
+        try!(dao.new_project("Outer", layout.layout_id));
+        let inner: Result<(), Error> = dao.transaction(|| {
+            try!(dao.new_project("Inner", layout.layout_id));
+            Err(Error::TodoErr)
+        });
+        assert!(inner.is_err());
+        Ok(())
+    });
+
+    assert!(result.is_ok());
+    assert!(dao.get_project("Outer").is_ok());
+    assert!(dao.get_project("Inner").is_err());
+}
+
+#[test]
+#[should_panic(expected = "NoTransaction")]
+fn commit_without_begin_fails() {
+    let dao = DaoMemory::new();
+    dao.commit_transaction().expect("Error committing");
+}